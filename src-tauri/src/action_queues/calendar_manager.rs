@@ -0,0 +1,197 @@
+use crate::action_queues::shared::timed_dispatch;
+use crate::integrations::events::AppEventType;
+use crate::integrations::queues::QueueManager;
+use crate::integrations::snapshots::meeting::{MeetingSlotSnapshot, MeetingSlotsSnapshot};
+use crate::integrations::snapshots::snapshots::SnapshotState;
+use crate::integrations::snapshots_emitter::snapshots_emitter::{SnapshotEmitRegistry, SnapshotEvent};
+use crate::integrations::ui::AppContext;
+use crate::sim::calendar::components::{CalendarEvent, CalendarEventId, CalendarEventRegistry, CalendarIndex};
+use crate::sim::calendar::scheduler::{bump_event, find_meeting_slots, meeting_event, MeetingRequest};
+use crate::sim::company::company::{PlayerControlled, WorkHourPolicy};
+use crate::sim::globals::MAX_MEETING_SLOTS;
+use crate::sim::person::components::PersonId;
+use crate::sim::resources::global::TickCounter;
+use crate::sim::sim_date::sim_date::SimDate;
+use crate::sim::team::components::Team;
+use legion::systems::CommandBuffer;
+use legion::world::SubWorld;
+use legion::{system, IntoQuery};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, trace, warn};
+
+pub enum CalendarCommand {
+    /// Finds slots for a meeting and books the best one when `book` is set.
+    /// Members of `team_id` are added to the required attendees.
+    ScheduleMeeting { request: MeetingRequest, team_id: Option<u32>, book: bool },
+}
+
+#[system]
+#[read_component(WorkHourPolicy)]
+#[read_component(PlayerControlled)]
+#[read_component(Team)]
+#[write_component(CalendarEvent)]
+pub fn handle_calendar_queue(
+    #[resource] queue_manager: &QueueManager,
+    #[resource] tick_counter: &Arc<TickCounter>,
+    #[resource] calendar_index: &mut CalendarIndex,
+    #[resource] calendar_event_registry: &Arc<CalendarEventRegistry>,
+    #[resource] snapshot_state: &Arc<SnapshotState>,
+    #[resource] emit_registry: &Arc<SnapshotEmitRegistry>,
+    #[resource] app_context: &Arc<AppContext>,
+    world: &mut SubWorld,
+    commands: &mut CommandBuffer,
+) {
+    trace!("Handling calendar queue");
+    let queue = &queue_manager.calendar_manager;
+    let dispatch_time_limit = Duration::from_millis(5);
+
+    timed_dispatch(queue, dispatch_time_limit, |cmd| {
+        let CalendarCommand::ScheduleMeeting { mut request, team_id, book } = cmd;
+
+        let now = tick_counter.current_date();
+        if !calendar_index.covers(&now) {
+            calendar_index.rebuild(now);
+        }
+        let policy = <(&WorkHourPolicy, &PlayerControlled)>::query()
+            .iter(world)
+            .next()
+            .map(|(policy, _)| policy.clone())
+            .unwrap_or_default();
+
+        if let Some(team_id) = team_id {
+            if let Some(team) = <&Team>::query().iter(world).find(|team| team.team_id.0 == team_id) {
+                for member in team.get_members_vec() {
+                    if !request.required.contains(&PersonId(member)) {
+                        request.required.push(PersonId(member));
+                    }
+                }
+            } else {
+                warn!("Team {} not found, scheduling without it", team_id);
+            }
+        }
+        request.optional.retain(|person| !request.required.contains(person));
+        // Meetings can only be booked from the next quarter tick onwards
+        request.window_start = request.window_start.max(SimDate::from(now.to_tick() + 1));
+
+        let slots = find_meeting_slots(calendar_index, &request, &policy, MAX_MEETING_SLOTS);
+        let mut booked_event_id = None;
+
+        match slots.first() {
+            None => {
+                warn!("No slot found for '{}'", request.title);
+                app_context.emit(
+                    AppEventType::ShowCustomNotification {
+                        notification_type: "warning".to_string(),
+                        title: "No time for a meeting".to_string(),
+                        message: format!("No slot found for '{}' where everyone required is free.", request.title),
+                        context_id: None,
+                    },
+                );
+            }
+            Some(slot) if book => {
+                let meeting = meeting_event(
+                    CalendarEventId::new(calendar_event_registry.generate_id() as u64),
+                    &request,
+                    slot,
+                );
+                info!("Booked '{}' at {:?}", meeting.details.title, meeting.start_time);
+                let meeting_id = meeting.id;
+                booked_event_id = Some(meeting_id.0);
+                calendar_index.add_event(meeting.clone());
+                let entity = commands.push((meeting,));
+                calendar_event_registry.insert(meeting_id, entity);
+
+                for event_id in &slot.bumped {
+                    let Some(event) = calendar_index.events.get(event_id).cloned() else {
+                        continue;
+                    };
+                    let Some(entity) = calendar_event_registry.get_entity_from_id(event_id) else {
+                        warn!("Bumped event {:?} has no entity", event_id);
+                        continue;
+                    };
+                    calendar_index.remove_event(*event_id);
+                    match bump_event(calendar_index, &event, slot.start, request.duration_ticks, &policy) {
+                        Some(moved) => {
+                            info!("Moved '{}' to make room for '{}'", moved.details.title, request.title);
+                            calendar_index.add_event(moved.clone());
+                            if let Ok(stored) = <&mut CalendarEvent>::query().get_mut(world, entity) {
+                                *stored = moved;
+                            }
+                        }
+                        None => {
+                            warn!("'{}' could not be moved and was cancelled", event.details.title);
+                            calendar_event_registry.remove(event_id);
+                            commands.remove(entity);
+                            app_context.emit(
+                                AppEventType::ShowCustomNotification {
+                                    notification_type: "warning".to_string(),
+                                    title: "Event cancelled".to_string(),
+                                    message: format!(
+                                        "'{}' was cancelled to make room for '{}'.",
+                                        event.details.title, request.title
+                                    ),
+                                    context_id: Some(event_id.0.to_string()),
+                                },
+                            );
+                        }
+                    }
+                }
+            }
+            Some(_) => {}
+        }
+
+        snapshot_state.meeting_slots.value.store(Arc::new(Arc::new(MeetingSlotsSnapshot {
+            title: request.title.clone(),
+            slots: slots.iter().map(MeetingSlotSnapshot::from).collect(),
+            booked_event_id,
+        })));
+        emit_registry.mark_data_updated(SnapshotEvent::MeetingSlots, tick_counter.value());
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::{HeadlessOptions, HeadlessSim};
+    use crate::sim::calendar::components::{EventPriority, EventType};
+    use crate::sim::person::components::Person;
+    use std::collections::HashSet;
+
+    fn book_meeting(sim: &mut HeadlessSim, title: &str, required: Vec<PersonId>) {
+        let now = sim.tick_counter.current_date();
+        let request = MeetingRequest {
+            title: title.to_string(),
+            event_type: EventType::Meeting,
+            required,
+            optional: vec![],
+            duration_ticks: 4,
+            window_start: now,
+            window_end: SimDate::from(now.to_tick() + 96 * 7),
+            priority: EventPriority::Normal,
+        };
+        sim.resources
+            .get::<QueueManager>()
+            .unwrap()
+            .calendar_manager
+            .queue
+            .push(CalendarCommand::ScheduleMeeting { request, team_id: None, book: true });
+        sim.tick();
+    }
+
+    #[test]
+    fn meetings_booked_after_a_load_get_new_ids() {
+        let args = ["--headless", "--ticks", "1", "--seed", "3"].map(str::to_string);
+        let mut sim = HeadlessSim::new(&HeadlessOptions::from_args(args).unwrap().unwrap()).unwrap();
+        let person_id = <&Person>::query().iter(&sim.world).map(|person| person.person_id).min().unwrap();
+
+        book_meeting(&mut sim, "Kickoff", vec![person_id]);
+        // Loading a game rebuilds the registries from the events in the world
+        sim.schedules.load_game_schedule.execute(&mut sim.world, &mut sim.resources);
+        book_meeting(&mut sim, "Retro", vec![person_id]);
+
+        let ids: Vec<CalendarEventId> = <&CalendarEvent>::query().iter(&sim.world).map(|event| event.id).collect();
+        assert_eq!(ids.len(), 2);
+        assert_eq!(ids.iter().collect::<HashSet<_>>().len(), 2, "duplicate ids {:?}", ids);
+    }
+}
@@ -1,4 +1,6 @@
+pub mod calendar_manager;
 pub mod game_speed_manager;
+pub mod offer_manager;
 pub mod shared;
 pub mod sim_manager;
 pub mod team_manager;
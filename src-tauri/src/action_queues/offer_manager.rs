@@ -0,0 +1,90 @@
+use crate::action_queues::shared::timed_dispatch;
+use crate::integrations::queues::QueueManager;
+use crate::sim::project::customer::{Customer, CustomerProject, OfferId};
+use crate::sim::project::functionality::FunctionalityId;
+use crate::sim::project::project::{ProjectId, ProjectStatus};
+use crate::sim::project::requirement::RequirementId;
+use crate::sim::project::task::TaskId;
+use crate::sim::project::utils::{spawn_project, ProjectRegistries};
+use crate::sim::registries::registry::Registry;
+use crate::sim::resources::global::{Dirty, TickCounter};
+use crate::sim::sim_date::sim_date::SimDate;
+use legion::systems::CommandBuffer;
+use legion::world::SubWorld;
+use legion::{system, Entity, Query};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, trace, warn};
+
+pub enum OfferCommand {
+    AcceptOffer { offer_id: u32 },
+    DeclineOffer { offer_id: u32 },
+}
+
+#[system]
+pub fn handle_offer_queue(
+    #[resource] queue_manager: &QueueManager,
+    #[resource] tick_counter: &Arc<TickCounter>,
+    #[resource] offer_registry: &Arc<Registry<OfferId, Entity>>,
+    #[resource] project_registry: &Arc<Registry<ProjectId, Entity>>,
+    #[resource] requirement_registry: &Arc<Registry<RequirementId, Entity>>,
+    #[resource] functionality_registry: &Arc<Registry<FunctionalityId, Entity>>,
+    #[resource] task_registry: &Arc<Registry<TaskId, Entity>>,
+    customer_query: &mut Query<&mut Customer>,
+    world: &mut SubWorld,
+    commands: &mut CommandBuffer,
+) {
+    trace!("Handling offer queue");
+    let queue = &queue_manager.offer_manager;
+    let dispatch_time_limit = Duration::from_millis(5);
+    let registries = ProjectRegistries {
+        projects: project_registry,
+        requirements: requirement_registry,
+        functionalities: functionality_registry,
+        tasks: task_registry,
+    };
+
+    timed_dispatch(queue, dispatch_time_limit, |cmd| {
+        let (offer_id, accept) = match cmd {
+            OfferCommand::AcceptOffer { offer_id } => (OfferId(offer_id), true),
+            OfferCommand::DeclineOffer { offer_id } => (OfferId(offer_id), false),
+        };
+        let Some(entity) = offer_registry.get_entity_from_id(&offer_id) else {
+            warn!("Offer with ID:{:?} not found. Skipping...", offer_id);
+            return;
+        };
+        let Ok(customer) = customer_query.get_mut(world, entity) else {
+            warn!("Cannot access customer for offer {:?}", offer_id);
+            return;
+        };
+        let Some(mut offer) = customer.take_offer(offer_id) else {
+            warn!("Offer {:?} is no longer open", offer_id);
+            offer_registry.remove(&offer_id);
+            return;
+        };
+        offer_registry.remove(&offer_id);
+        commands.add_component(entity, Dirty);
+
+        let now = tick_counter.current_date();
+        if !accept {
+            info!("Declined offer '{}' from {}", offer.project.name, customer.name);
+            return;
+        }
+        if offer.is_expired(&now) {
+            warn!("Offer '{}' from {} has expired", offer.project.name, customer.name);
+            return;
+        }
+
+        info!("Accepted offer '{}' from {}", offer.project.name, customer.name);
+        offer.project.deadline = Some(SimDate::from(
+            now.to_tick() + offer.duration_days as u64 * SimDate::QUARTER_TICKS_PER_DAY,
+        ));
+        let (project_id, _) =
+            spawn_project(commands, &registries, offer.project, ProjectStatus::Active);
+        customer.projects.push(CustomerProject {
+            project_id,
+            payout: offer.payout,
+            last_status: ProjectStatus::Active,
+        });
+    });
+}
@@ -15,6 +15,7 @@ use std::fmt;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
+use crate::db::checkpoints::{AutosavePolicy, CheckpointKind};
 use crate::db::init::{create_new_save_slot, SaveSlot, SavesDirectory};
 use crate::integrations::events::emit_app_event;
 use crate::integrations::snapshots::snapshots::SnapshotState;
@@ -25,6 +26,7 @@ use crate::sim::person::init::FirstRun;
 use crate::sim::registries::registry::Registry;
 use arc_swap::ArcSwap;
 use parking_lot::{Mutex, RwLock};
+use rand::Rng;
 use std::time::Duration;
 use tracing::field::debug;
 use tracing::{debug, error, info, trace, warn};
@@ -36,6 +38,8 @@ pub enum SimManagerCommand {
         company: CompanyPreset,
         employee: StartingEmployeesConfig,
         slot_id: String,
+        /// Random seed of the new game, picked at random when not given
+        seed: Option<u64>,
     },
     #[default]
     StopSim,
@@ -45,6 +49,11 @@ pub enum SimManagerCommand {
     },
     ResumeSim,
     LoadSim{slot_id:String},
+    /// Copies the current game into a named checkpoint of its save slot
+    CreateCheckpoint { name: String },
+    /// Replaces a slot's game state with one of its checkpoints and loads it
+    RestoreCheckpoint { slot_id: String, checkpoint_id: String },
+    SetAutosavePolicy(AutosavePolicy),
 }
 
 // #[derive(Default, Debug)]
@@ -58,6 +67,8 @@ pub enum SimManagerCommand {
 pub fn handle_sim_manager_queue(
     #[resource] queue_manager: &QueueManager,
     #[resource] sim_manager: &Arc<SimManager>,
+    #[resource] saves_directory: &Arc<SavesDirectory>,
+    #[resource] autosave_policy: &mut AutosavePolicy,
 ) {
     trace!("Handling sim manager queue");
     let queue = &queue_manager.sim_manager;
@@ -80,6 +91,25 @@ pub fn handle_sim_manager_queue(
         SimManagerCommand::LoadSim { .. } => {
             error!("Unexpected item in queue. LoadSim should be handled by new game queue")
         }
+        SimManagerCommand::RestoreCheckpoint { .. } => {
+            error!("Unexpected item in queue. RestoreCheckpoint should be handled by new game queue")
+        }
+        SimManagerCommand::CreateCheckpoint { name } => {
+            let created = sim_manager.with_save_slot(|slot| {
+                // The handle is dropped while the sim is paused
+                slot.ensure_db_handle_is_open(saves_directory)?;
+                slot.create_checkpoint(CheckpointKind::Manual, name.as_str())
+            });
+            match created {
+                Some(Ok(checkpoint)) => info!("Checkpoint '{}' created", checkpoint.id),
+                Some(Err(e)) => error!("Failed to create checkpoint '{}': {}", name, e),
+                None => warn!("No active save slot to create checkpoint '{}' in", name),
+            }
+        }
+        SimManagerCommand::SetAutosavePolicy(policy) => {
+            info!("Autosave policy set to {:?}", policy);
+            *autosave_policy = policy;
+        }
     });
 }
 
@@ -119,11 +149,14 @@ pub fn handle_new_game_manager_queue(
                 emit_registry,
                 Some(company),
                 Some(employee),
+                None,
             );
         }
-        SimManagerCommand::StartSim { company, employee, slot_id } => {
+        SimManagerCommand::StartSim { company, employee, slot_id, seed } => {
+            let seed = seed.unwrap_or_else(|| rand::rng().random());
+            info!("Starting a new game with seed {}", seed);
 
-            let Ok((actual_slot_id, slot_metadata)) = create_new_save_slot(saves_directory, slot_id.as_str(), company.name.as_str()) else {
+            let Ok((actual_slot_id, slot_metadata)) = create_new_save_slot(saves_directory, slot_id.as_str(), company.name.as_str(), seed) else {
                 error!("Failed to create new save slot");
                 return;
             };
@@ -152,6 +185,7 @@ pub fn handle_new_game_manager_queue(
                 emit_registry,
                 Some(company),
                 Some(employee),
+                Some(seed),
             );
         }
         SimManagerCommand::LoadSim { slot_id } => {
@@ -164,6 +198,20 @@ pub fn handle_new_game_manager_queue(
             load_game.slot_id.write().replace(slot_id);
 
         }
+        SimManagerCommand::RestoreCheckpoint { slot_id, checkpoint_id } => {
+            // Same as LoadSim, with the slot rewritten from the checkpoint before it is loaded
+            sim_manager.pause_sim();
+            *sim_manager.save_slot.lock() = None;
+
+            let restored = SaveSlot::load(slot_id.clone(), Arc::clone(saves_directory))
+                .and_then(|mut slot| slot.restore_checkpoint(checkpoint_id.as_str()));
+            if let Err(e) = restored {
+                error!("Failed to restore checkpoint '{}' of slot '{}': {}", checkpoint_id, slot_id, e);
+                return;
+            }
+            load_game.should_load.store(true, Ordering::Relaxed);
+            load_game.slot_id.write().replace(slot_id);
+        }
         cmd => {
             error!("Unexpected item in game manager queue {:?}", cmd);
         }
@@ -182,6 +230,8 @@ pub struct SimManager {
     pub company_preset: RwLock<CompanyPreset>,
     pub employees_preset: RwLock<StartingEmployeesConfig>,
     pub save_slot: Mutex<Option<SaveSlot>>, // let's be conservative and use mutex for save slots
+    /// Seed the next game starts from
+    pub seed: RwLock<u64>,
 }
 
 impl SimManager {
@@ -209,6 +259,7 @@ impl SimManager {
             company_preset: RwLock::new(CompanyPreset::default()),
             employees_preset: RwLock::new(StartingEmployeesConfig::default()),
             save_slot: Mutex::new(None),
+            seed: RwLock::new(0),
         }
     }
 
@@ -226,6 +277,7 @@ impl SimManager {
         emit_registry: &Arc<SnapshotEmitRegistry>,
         new_company_preset: Option<CompanyPreset>,
         new_employees_preset: Option<StartingEmployeesConfig>,
+        new_seed: Option<u64>,
     ) {
         info!("Resetting simulation...");
 
@@ -251,6 +303,10 @@ impl SimManager {
             None => {}
         }
 
+        if let Some(new_seed) = new_seed {
+            *self.seed.write() = new_seed;
+        }
+
         game_speed.write().set(GameSpeed::Normal);
 
         // debug!("Clearing entities...");
@@ -0,0 +1,209 @@
+use crate::db::constants::{db_keys, save_version, GAMESTATE_DB_FILENAME};
+use crate::db::init::{entries_checksum, sanitize_foldername, unique_slot_folder, SaveSlot, SaveSlotMetadata, SavesDirectory};
+use crate::utils::errors::{LoadDataFromDBError, SavesManagementError};
+use bincode::config::standard;
+use bincode::{decode_from_slice, encode_to_vec, Decode, Encode};
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use tracing::info;
+
+/// First bytes of every save archive.
+const ARCHIVE_MAGIC: &[u8; 8] = b"YNTDSAVE";
+/// Layout of the archive itself, independent of the save version inside.
+const ARCHIVE_FORMAT: u32 = 1;
+
+type ArchiveEntries = Vec<(Vec<u8>, Vec<u8>)>;
+
+/// Leading fields of the header, the same in every format. Decoding stops after them,
+/// so an archive from another version is refused before the rest of the header is read.
+#[derive(Debug, Decode)]
+struct ArchivePreamble {
+    format: u32,
+    save_version: String,
+}
+
+/// Describes the save in an archive. The archive is the magic bytes, this header and the bincode entries
+/// of the save, metadata left out since the header carries it.
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct SaveArchiveHeader {
+    pub format: u32,
+    pub save_version: String,
+    /// Folder name of the exported slot, sanitized again on import
+    pub slot_id: String,
+    pub metadata: SaveSlotMetadata,
+    pub entry_count: u64,
+    /// [`entries_checksum`] of the entries
+    pub content_hash: String,
+}
+
+fn content_hash(entries: &ArchiveEntries) -> String {
+    entries_checksum(entries.iter().map(|(key, value)| (key, value)))
+}
+
+fn invalid(reason: impl Into<String>) -> SavesManagementError {
+    SavesManagementError::InvalidArchive(reason.into())
+}
+
+fn write_archive(path: &Path, header: &SaveArchiveHeader, entries: &ArchiveEntries) -> Result<(), SavesManagementError> {
+    let mut bytes = ARCHIVE_MAGIC.to_vec();
+    bytes.extend(encode_to_vec(header, standard())?);
+    bytes.extend(encode_to_vec(entries, standard())?);
+    fs::write(path, bytes)?;
+    Ok(())
+}
+
+/// Reads an archive and checks it holds a complete save of the current version.
+pub fn read_archive(path: &Path) -> Result<(SaveArchiveHeader, ArchiveEntries), SavesManagementError> {
+    let bytes = fs::read(path)?;
+    let body = bytes.strip_prefix(ARCHIVE_MAGIC.as_slice()).ok_or_else(|| invalid("not a save archive"))?;
+
+    let (preamble, _) = decode_from_slice::<ArchivePreamble, _>(body, standard())?;
+    if preamble.format != ARCHIVE_FORMAT {
+        return Err(invalid(format!("unknown archive format {}", preamble.format)));
+    }
+    if preamble.save_version != save_version::SAVE_VERSION {
+        return Err(SavesManagementError::ArchiveVersionMismatch {
+            version: preamble.save_version,
+            supported: save_version::SAVE_VERSION.to_string(),
+        });
+    }
+
+    let (header, header_len) = decode_from_slice::<SaveArchiveHeader, _>(body, standard())?;
+    let (entries, _) = decode_from_slice::<ArchiveEntries, _>(&body[header_len..], standard())?;
+    if entries.len() as u64 != header.entry_count {
+        return Err(invalid(format!("{} entries, the header lists {}", entries.len(), header.entry_count)));
+    }
+    let content_hash = content_hash(&entries);
+    if content_hash != header.content_hash {
+        return Err(invalid(format!("content hash is {}, the header recorded {}", content_hash, header.content_hash)));
+    }
+    Ok((header, entries))
+}
+
+// Archive related fn
+impl SaveSlot {
+    /// Writes the slot's save to a single archive file.
+    pub fn export_archive(&self, path: &Path) -> Result<SaveArchiveHeader, SavesManagementError> {
+        let handle = self.handle.as_ref().ok_or(LoadDataFromDBError::MissingHandle)?;
+        handle.flush()?;
+        let metadata = self.load_entry::<SaveSlotMetadata>(db_keys::METADATA)?;
+
+        let mut entries = ArchiveEntries::new();
+        for entry in handle.iter() {
+            let (key, value) = entry?;
+            if key.as_ref() != db_keys::METADATA.as_bytes() {
+                entries.push((key.to_vec(), value.to_vec()));
+            }
+        }
+        let header = SaveArchiveHeader {
+            format: ARCHIVE_FORMAT,
+            save_version: metadata.save_version.clone(),
+            slot_id: self.slot_id.clone(),
+            metadata,
+            entry_count: entries.len() as u64,
+            content_hash: content_hash(&entries),
+        };
+        write_archive(path, &header, &entries)?;
+        info!("Exported slot '{}' to {:?} ({} entries).", self.slot_id, path, header.entry_count);
+        Ok(header)
+    }
+}
+
+/// Exports a slot that is not in use. The running game's slot is exported through its open handle instead,
+/// sled only lets one handle at a time use a database.
+pub fn export_save_slot(saves_directory: &Arc<SavesDirectory>, slot_id: &str, path: &Path) -> Result<SaveArchiveHeader, SavesManagementError> {
+    SaveSlot::load(slot_id.to_string(), Arc::clone(saves_directory))?.export_archive(path)
+}
+
+/// Creates a new slot from an archive and returns its id. The slot is named after the exported one,
+/// with a number appended when that name is taken.
+pub fn import_save_slot(saves_directory: &SavesDirectory, path: &Path) -> Result<String, SavesManagementError> {
+    let (header, entries) = read_archive(path)?;
+
+    let slot_id = unique_slot_folder(saves_directory, &sanitize_foldername(&header.slot_id));
+    let slot_path = saves_directory.0.join(&slot_id);
+    fs::create_dir_all(&slot_path)?;
+
+    let db = sled::Config::default().path(slot_path.join(GAMESTATE_DB_FILENAME)).open()?;
+    let mut batch = sled::Batch::default();
+    for (key, value) in entries {
+        batch.insert(key, value);
+    }
+    batch.insert(db_keys::METADATA, encode_to_vec(&header.metadata, standard())?);
+    db.apply_batch(batch)?;
+    db.flush()?;
+    info!("Imported {:?} as slot '{}' ({} entries).", path, slot_id, header.entry_count);
+    Ok(slot_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init::{create_new_save_slot, SaveBatch};
+    use tempfile::tempdir;
+
+    fn exported_slot(dir: &Path) -> (Arc<SavesDirectory>, std::path::PathBuf) {
+        let saves_dir = Arc::new(SavesDirectory(dir.join("saves")));
+        let (slot_id, meta) = create_new_save_slot(&saves_dir, "My Slot", "Company", 7).unwrap();
+        let mut slot = SaveSlot::load(slot_id, Arc::clone(&saves_dir)).unwrap();
+        let mut batch = SaveBatch::default();
+        batch.insert(db_keys::EMPLOYEES_LIST, &vec![1u32, 2]).unwrap();
+        batch.insert("employee1", &"Ann".to_string()).unwrap();
+        slot.commit(batch, meta).unwrap();
+
+        let archive = dir.join("my_slot.save");
+        slot.export_archive(&archive).unwrap();
+        (saves_dir, archive)
+    }
+
+    #[test]
+    fn imported_archive_is_a_copy_of_the_slot() {
+        let dir = tempdir().unwrap();
+        let (saves_dir, archive) = exported_slot(dir.path());
+
+        let slot_id = import_save_slot(&saves_dir, &archive).unwrap();
+        assert_eq!(slot_id, "My_Slot_1");
+        let slot = SaveSlot::load(slot_id, Arc::clone(&saves_dir)).unwrap();
+        slot.verify().unwrap();
+        assert_eq!(slot.load_entry::<Vec<u32>>(db_keys::EMPLOYEES_LIST).unwrap(), vec![1, 2]);
+        assert_eq!(slot.load_entry::<String>("employee1").unwrap(), "Ann");
+        assert_eq!(slot.metadata.unwrap().name, "Company");
+    }
+
+    #[test]
+    fn other_versions_and_damaged_archives_are_refused() {
+        let dir = tempdir().unwrap();
+        let (saves_dir, archive) = exported_slot(dir.path());
+        let (mut header, entries) = read_archive(&archive).unwrap();
+
+        header.save_version = "0.0.1a".to_string();
+        write_archive(&archive, &header, &entries).unwrap();
+        assert!(matches!(
+            import_save_slot(&saves_dir, &archive),
+            Err(SavesManagementError::ArchiveVersionMismatch { version, .. }) if version == "0.0.1a"
+        ));
+
+        header.save_version = save_version::SAVE_VERSION.to_string();
+        header.content_hash = "0".repeat(64);
+        write_archive(&archive, &header, &entries).unwrap();
+        assert!(matches!(import_save_slot(&saves_dir, &archive), Err(SavesManagementError::InvalidArchive(_))));
+
+        fs::write(&archive, b"PK\x03\x04 not a save").unwrap();
+        assert!(matches!(import_save_slot(&saves_dir, &archive), Err(SavesManagementError::InvalidArchive(_))));
+        assert_eq!(fs::read_dir(&saves_dir.0).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn imported_slot_names_are_sanitized() {
+        let dir = tempdir().unwrap();
+        let (saves_dir, archive) = exported_slot(dir.path());
+        let (mut header, entries) = read_archive(&archive).unwrap();
+        header.slot_id = "../../outside".to_string();
+        write_archive(&archive, &header, &entries).unwrap();
+
+        let slot_id = import_save_slot(&saves_dir, &archive).unwrap();
+        assert_eq!(slot_id, sanitize_foldername("../../outside"));
+        assert!(saves_dir.0.join(&slot_id).join(GAMESTATE_DB_FILENAME).is_dir());
+    }
+}
@@ -0,0 +1,292 @@
+use crate::db::constants::{db_keys, CHECKPOINTS_DIR, GAMESTATE_DB_FILENAME};
+use crate::db::init::{open_db, sanitize_foldername, SaveSlot};
+use crate::sim::resources::global::TickCounter;
+use crate::sim::sim_date::sim_date::SimDate;
+use crate::utils::errors::{LoadDataFromDBError, SavesManagementError};
+use bincode::config::standard;
+use bincode::{decode_from_slice, encode_to_vec, Decode, Encode};
+use serde::{Deserialize, Serialize};
+use sled::Db;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub enum CheckpointKind {
+    /// Taken by the autosave, pruned down to the newest few
+    Autosave,
+    /// Named by the player, only deleted on request
+    Manual,
+}
+
+/// Describes a copy of a save slot's game state, stored in the copy itself.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Encode, Decode)]
+pub struct CheckpointInfo {
+    /// Folder name under the slot's checkpoints directory
+    pub id: String,
+    pub name: String,
+    pub kind: CheckpointKind,
+    pub tick: u64,
+    pub sim_date: SimDate,
+    pub created_timestamp: i64,
+}
+
+/// How often `save_game_state` takes an autosave checkpoint.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutosaveCadence {
+    Never,
+    EveryTicks(u64),
+    /// At the start of every sim day
+    EveryDay,
+    /// At the start of every sim week
+    EveryWeek,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AutosavePolicy {
+    pub cadence: AutosaveCadence,
+    /// Autosaves kept per slot, older ones are pruned
+    pub keep: usize,
+}
+
+impl Default for AutosavePolicy {
+    fn default() -> Self {
+        Self { cadence: AutosaveCadence::EveryDay, keep: 5 }
+    }
+}
+
+impl AutosavePolicy {
+    /// Whether an autosave is due at `tick`. The first tick of a game never is, there is nothing to roll back to.
+    pub fn is_due(&self, tick: u64) -> bool {
+        if tick == 0 {
+            return false;
+        }
+        let date = SimDate::from(tick);
+        match self.cadence {
+            AutosaveCadence::Never => false,
+            AutosaveCadence::EveryTicks(n) => n > 0 && tick.is_multiple_of(n),
+            AutosaveCadence::EveryDay => date.quarter_tick == 1,
+            AutosaveCadence::EveryWeek => date.day == 1 && date.quarter_tick == 1,
+        }
+    }
+}
+
+fn open_checkpoint_db(path: &Path) -> Result<Db, SavesManagementError> {
+    Ok(open_db(&sled::Config::default().path(path.join(GAMESTATE_DB_FILENAME)).cache_capacity(1_000_000).flush_every_ms(None))?)
+}
+
+fn read_checkpoint_info(db: &Db) -> Result<CheckpointInfo, SavesManagementError> {
+    let bytes = db
+        .get(db_keys::CHECKPOINT_INFO)?
+        .ok_or_else(|| SavesManagementError::MetadataKeyNotFound(db_keys::CHECKPOINT_INFO.to_string()))?;
+    Ok(decode_from_slice(&bytes, standard())?.0)
+}
+
+/// Copies every entry of `from` into `to`, leaving out `skip_key`.
+fn copy_entries(from: &Db, to: &Db, skip_key: Option<&str>) -> Result<(), SavesManagementError> {
+    for entry in from.iter() {
+        let (key, value) = entry?;
+        if skip_key.is_some_and(|skip| key.as_ref() == skip.as_bytes()) {
+            continue;
+        }
+        to.insert(key, value)?;
+    }
+    Ok(())
+}
+
+// Checkpoint related fn
+impl SaveSlot {
+    pub fn checkpoints_path(&self) -> PathBuf {
+        self.path.join(CHECKPOINTS_DIR)
+    }
+
+    /// Folder of an existing checkpoint. Ids that are not plain folder names are treated as unknown,
+    /// so a command can never reach outside the checkpoints directory.
+    fn checkpoint_path(&self, checkpoint_id: &str) -> Result<PathBuf, SavesManagementError> {
+        let path = self.checkpoints_path().join(checkpoint_id);
+        if sanitize_foldername(checkpoint_id) != checkpoint_id || checkpoint_id.starts_with('.') || !path.is_dir() {
+            return Err(SavesManagementError::CheckpointNotFound(checkpoint_id.to_string()));
+        }
+        Ok(path)
+    }
+
+    /// Copies the current game state of the slot into a new checkpoint. An autosave taken at the same tick
+    /// as an existing one replaces it.
+    pub fn create_checkpoint(&mut self, kind: CheckpointKind, name: &str) -> Result<CheckpointInfo, SavesManagementError> {
+        let tick = match self.load_entry::<TickCounter>(db_keys::TICK_COUNTER) {
+            Ok(tick_counter) => tick_counter.value(),
+            Err(LoadDataFromDBError::KeyNotFound(_)) => 0,
+            Err(e) => return Err(e.into()),
+        };
+        let handle = self.handle.as_ref().ok_or(LoadDataFromDBError::MissingHandle)?;
+        handle.flush()?;
+
+        let id = match kind {
+            CheckpointKind::Autosave => format!("auto-{}", tick),
+            CheckpointKind::Manual => format!("manual-{}-{}", tick, sanitize_foldername(name)),
+        };
+        let path = self.checkpoints_path().join(&id);
+        if path.exists() {
+            fs::remove_dir_all(&path)?;
+        }
+        fs::create_dir_all(&path)?;
+
+        let info = CheckpointInfo {
+            id,
+            name: if name.is_empty() { format!("Autosave {}", tick) } else { name.to_string() },
+            kind,
+            tick,
+            sim_date: SimDate::from(tick),
+            created_timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64,
+        };
+        let db = open_checkpoint_db(&path)?;
+        copy_entries(handle, &db, None)?;
+        db.insert(db_keys::CHECKPOINT_INFO, encode_to_vec(&info, standard())?)?;
+        db.flush()?;
+        info!("Created checkpoint '{}' for slot '{}'.", info.id, self.slot_id);
+        Ok(info)
+    }
+
+    /// Checkpoints of the slot, newest first. Unreadable ones are skipped.
+    pub fn list_checkpoints(&self) -> Result<Vec<CheckpointInfo>, SavesManagementError> {
+        let checkpoints_path = self.checkpoints_path();
+        if !checkpoints_path.is_dir() {
+            return Ok(vec![]);
+        }
+        let mut checkpoints = Vec::new();
+        for entry in fs::read_dir(&checkpoints_path)? {
+            let path = entry?.path();
+            if !path.is_dir() {
+                continue;
+            }
+            match open_checkpoint_db(&path).and_then(|db| read_checkpoint_info(&db)) {
+                Ok(info) => checkpoints.push(info),
+                Err(e) => warn!("Skipping unreadable checkpoint at {:?}: {}", path, e),
+            }
+        }
+        checkpoints.sort_by(|a, b| b.tick.cmp(&a.tick).then(b.created_timestamp.cmp(&a.created_timestamp)));
+        Ok(checkpoints)
+    }
+
+    pub fn delete_checkpoint(&self, checkpoint_id: &str) -> Result<(), SavesManagementError> {
+        let path = self.checkpoint_path(checkpoint_id)?;
+        fs::remove_dir_all(path)?;
+        info!("Deleted checkpoint '{}' of slot '{}'.", checkpoint_id, self.slot_id);
+        Ok(())
+    }
+
+    /// Deletes all but the newest `keep` autosaves and returns the ids of the deleted ones.
+    /// Manual checkpoints are left alone.
+    pub fn prune_autosaves(&self, keep: usize) -> Result<Vec<String>, SavesManagementError> {
+        let mut pruned = Vec::new();
+        for checkpoint in self.list_checkpoints()?.into_iter().filter(|c| c.kind == CheckpointKind::Autosave).skip(keep) {
+            self.delete_checkpoint(&checkpoint.id)?;
+            pruned.push(checkpoint.id);
+        }
+        Ok(pruned)
+    }
+
+    /// Replaces the slot's game state with the checkpoint's. The checkpoint itself stays.
+    pub fn restore_checkpoint(&mut self, checkpoint_id: &str) -> Result<CheckpointInfo, SavesManagementError> {
+        let path = self.checkpoint_path(checkpoint_id)?;
+        let handle = self.handle.as_ref().ok_or(LoadDataFromDBError::MissingHandle)?;
+        let db = open_checkpoint_db(&path)?;
+        let info = read_checkpoint_info(&db)?;
+
+        handle.clear()?;
+        copy_entries(&db, handle, Some(db_keys::CHECKPOINT_INFO))?;
+        handle.flush()?;
+        info!("Restored slot '{}' from checkpoint '{}'.", self.slot_id, checkpoint_id);
+        Ok(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init::{create_new_save_slot, SavesDirectory};
+    use std::sync::Arc;
+    use tempfile::tempdir;
+
+    fn new_slot(dir: &Path) -> SaveSlot {
+        let saves_dir = SavesDirectory(dir.to_path_buf());
+        let (slot_id, _meta) = create_new_save_slot(&saves_dir, "slot", "Company", 7).unwrap();
+        SaveSlot::load(slot_id, Arc::new(saves_dir)).unwrap()
+    }
+
+    fn set_tick(slot: &mut SaveSlot, tick: u64) {
+        let tick_counter = TickCounter::default();
+        for _ in 0..tick {
+            tick_counter.tick();
+        }
+        slot.save_entry(db_keys::TICK_COUNTER, &tick_counter).unwrap();
+    }
+
+    #[test]
+    fn restore_brings_back_the_checkpointed_state() {
+        let dir = tempdir().unwrap();
+        let mut slot = new_slot(dir.path());
+        slot.save_entry("value", &1u32).unwrap();
+        set_tick(&mut slot, 10);
+
+        let info = slot.create_checkpoint(CheckpointKind::Manual, "Before the launch").unwrap();
+        assert_eq!(info.id, "manual-10-Before_the_launch");
+        assert_eq!(info.sim_date, SimDate::from(10));
+
+        slot.save_entry("value", &2u32).unwrap();
+        slot.save_entry("later", &3u32).unwrap();
+        slot.restore_checkpoint(&info.id).unwrap();
+
+        assert_eq!(slot.load_entry::<u32>("value").unwrap(), 1);
+        assert!(matches!(slot.load_entry::<u32>("later"), Err(LoadDataFromDBError::KeyNotFound(_))));
+        assert!(matches!(slot.load_entry::<CheckpointInfo>(db_keys::CHECKPOINT_INFO), Err(LoadDataFromDBError::KeyNotFound(_))));
+        assert_eq!(slot.list_checkpoints().unwrap(), vec![info.clone()]);
+
+        slot.delete_checkpoint(&info.id).unwrap();
+        assert!(slot.list_checkpoints().unwrap().is_empty());
+    }
+
+    #[test]
+    fn pruning_keeps_the_newest_autosaves_and_every_manual_checkpoint() {
+        let dir = tempdir().unwrap();
+        let mut slot = new_slot(dir.path());
+        for tick in [96, 192, 288] {
+            set_tick(&mut slot, tick);
+            slot.create_checkpoint(CheckpointKind::Autosave, "").unwrap();
+        }
+        set_tick(&mut slot, 300);
+        slot.create_checkpoint(CheckpointKind::Manual, "mine").unwrap();
+
+        assert_eq!(slot.prune_autosaves(2).unwrap(), vec!["auto-96".to_string()]);
+        let ids: Vec<String> = slot.list_checkpoints().unwrap().into_iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec!["manual-300-mine", "auto-288", "auto-192"]);
+    }
+
+    #[test]
+    fn ids_outside_the_checkpoints_directory_are_unknown() {
+        let dir = tempdir().unwrap();
+        let mut slot = new_slot(dir.path());
+        for id in ["..", "../slot", "missing"] {
+            assert!(matches!(slot.delete_checkpoint(id), Err(SavesManagementError::CheckpointNotFound(_))), "{id}");
+            assert!(matches!(slot.restore_checkpoint(id), Err(SavesManagementError::CheckpointNotFound(_))), "{id}");
+        }
+        assert!(slot.path.join(GAMESTATE_DB_FILENAME).is_dir());
+    }
+
+    #[test]
+    fn autosave_cadence() {
+        let daily = AutosavePolicy::default();
+        assert!(!daily.is_due(0));
+        assert!(!daily.is_due(95));
+        assert!(daily.is_due(96));
+
+        let weekly = AutosavePolicy { cadence: AutosaveCadence::EveryWeek, keep: 1 };
+        assert!(!weekly.is_due(96));
+        assert!(weekly.is_due(96 * 7));
+
+        let every_ten = AutosavePolicy { cadence: AutosaveCadence::EveryTicks(10), keep: 1 };
+        assert!(every_ten.is_due(20) && !every_ten.is_due(25));
+        assert!(!AutosavePolicy { cadence: AutosaveCadence::Never, keep: 1 }.is_due(96));
+    }
+}
@@ -7,10 +7,24 @@ pub mod db_keys {
     pub const USED_PROFILE_PICTURES: &str = "used_profile_pictures";
     pub const EMPLOYEES_LIST: &str = "employees_list";
     pub const CALENDAR_EVENTS: &str = "calendar_events";
+    pub const RECURRING_EVENT_TEMPLATES: &str = "recurring_event_templates";
+    pub const PROJECTS: &str = "projects";
+    pub const REQUIREMENTS: &str = "requirements";
+    pub const FUNCTIONALITIES: &str = "functionalities";
+    pub const TASKS: &str = "tasks";
+    pub const CUSTOMERS: &str = "customers";
+    pub const FINANCES: &str = "finances";
+    pub const WORK_HOUR_POLICY: &str = "work_hour_policy";
+    pub const SKILL_PROGRESS_PREFIX: &str = "skill_progress";
+    pub const BEHAVIOUR_PREFIX: &str = "behaviour";
+    pub const RNG_STATE: &str = "rng_state";
+    /// Only present in checkpoint copies of a save
+    pub const CHECKPOINT_INFO: &str = "checkpoint_info";
 }
 
 pub mod save_version{
-    pub const SAVE_VERSION: &str ="0.0.1a";
+    pub const SAVE_VERSION: &str ="0.0.3a";
 }
 
-pub const GAMESTATE_DB_FILENAME: &str = "gamestate.sled";
\ No newline at end of file
+pub const GAMESTATE_DB_FILENAME: &str = "gamestate.sled";
+pub const CHECKPOINTS_DIR: &str = "checkpoints";
\ No newline at end of file
@@ -2,6 +2,8 @@ use std::path::PathBuf;
 use tauri::Manager;
 use crate::db::constants::{db_keys, save_version, GAMESTATE_DB_FILENAME};
 use crate::db::{self};
+use crate::db::migrations::{migrate_save, read_listing_metadata, read_save_version};
+use crate::master_data::data_pack::{active_data_packs, DataPackInfo};
 use crate::sim::sim_date::sim_date::SimDate;
 use crate::utils::errors::{BincodeError, SavesManagementError};
 use crate::utils::errors::{LoadDataFromDBError, SaveDataToDBError};
@@ -10,9 +12,11 @@ use bincode::{decode_from_slice, Decode, Encode};
 // Consolidated imports
 use serde::{Deserialize, Serialize};
 use sled::{Db, IVec};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use std::task::Context;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use std::{fmt, fs};
 // rayon::in_place_scope_fifo is unused, consider removing if not needed elsewhere
 use tracing::{error, info, trace, warn};
@@ -21,6 +25,31 @@ use tracing::{error, info, trace, warn};
 #[derive(Debug, Clone)]
 pub struct SavesDirectory(pub PathBuf);
 
+/// sled lets go of the file lock of a closed database from its background threads,
+/// so a database closed a moment ago can still look locked for a short while.
+const DB_LOCK_RETRIES: u32 = 40;
+const DB_LOCK_RETRY_DELAY: Duration = Duration::from_millis(25);
+
+/// Opens a save database, waiting for a lock left behind by a handle that was just dropped.
+pub fn open_db(config: &sled::Config) -> sled::Result<Db> {
+    let mut attempt = 0;
+    loop {
+        match config.open() {
+            Err(sled::Error::Io(e)) if is_lock_error(&e) && attempt < DB_LOCK_RETRIES => {
+                trace!("Save database still locked ({}), retrying", e);
+                attempt += 1;
+                std::thread::sleep(DB_LOCK_RETRY_DELAY);
+            }
+            result => return result,
+        }
+    }
+}
+
+// sled has no dedicated error for a held lock, it wraps the failed flock in a generic io error.
+fn is_lock_error(e: &std::io::Error) -> bool {
+    e.kind() == std::io::ErrorKind::Other && e.to_string().starts_with("could not acquire lock")
+}
+
 
 #[derive(Serialize, Deserialize, Debug, Clone, Decode, Encode)]
 pub struct SaveSlotMetadata {
@@ -29,6 +58,50 @@ pub struct SaveSlotMetadata {
     pub sim_date: SimDate,
     pub save_version: String,
     pub last_saved_timestamp: i64,
+    /// Random seed the game started from
+    pub seed: u64,
+    /// Data packs active when the game was saved, in load order
+    pub data_packs: Vec<DataPackInfo>,
+    /// Hash of every other entry of the save, `None` until the first save of the game
+    pub checksum: Option<String>,
+}
+
+/// Entries of one save, written to the slot all at once by [`SaveSlot::commit`].
+#[derive(Debug, Default)]
+pub struct SaveBatch {
+    entries: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+
+impl SaveBatch {
+    /// Encodes the value into the batch, logging any error like [`SaveSlot::save_entry`].
+    pub fn insert<T: bincode::Encode>(&mut self, key: &str, value: &T) -> Result<(), SaveDataToDBError> {
+        match bincode::encode_to_vec(value, standard()) {
+            Ok(encoded) => {
+                self.entries.insert(key.as_bytes().to_vec(), encoded);
+                Ok(())
+            }
+            Err(e) => {
+                error!("Failed to encode key '{}': {}", key, e);
+                Err(SaveDataToDBError::Encoding(e))
+            }
+        }
+    }
+}
+
+/// Hash of the entries in key order, the metadata left out since it holds the hash.
+pub fn entries_checksum<K: AsRef<[u8]>, V: AsRef<[u8]>>(entries: impl IntoIterator<Item = (K, V)>) -> String {
+    let mut hasher = Sha256::new();
+    for (key, value) in entries {
+        let (key, value) = (key.as_ref(), value.as_ref());
+        if key == db_keys::METADATA.as_bytes() {
+            continue;
+        }
+        hasher.update((key.len() as u64).to_le_bytes());
+        hasher.update(key);
+        hasher.update((value.len() as u64).to_le_bytes());
+        hasher.update(value);
+    }
+    format!("{:x}", hasher.finalize())
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -99,8 +172,9 @@ impl SaveSlot {
             .cache_capacity(10_000_000) // Consistent with create_new_save_slot
             .flush_every_ms(Some(1000)); // Consistent with create_new_save_slot
 
-        match db_config.open() {
+        match open_db(&db_config) {
             Ok(db) => {
+                migrate_save(&db)?;
                 self.handle = Some(db);
                 info!("Successfully opened DB handle for slot '{}'.", self.slot_id);
                 Ok(())
@@ -154,7 +228,7 @@ impl SaveSlot {
         if gamestate_db_path.exists() && gamestate_db_path.is_dir() {
             // Attempt to open DB for metadata reading (try read-only first, then read-write fallback)
             let db_meta_config_ro = sled::Config::default().path(&gamestate_db_path).cache_capacity(1_000_000).flush_every_ms(None);
-            let db_meta = match db_meta_config_ro.open() {
+            let db_meta = match open_db(&db_meta_config_ro) {
                 Ok(db) => Ok(db),
                 Err(_e_ro) => {
                     info!("Failed to open DB in read-only mode for slot '{}'. Attempting read-write mode for metadata.", slot_id);
@@ -164,6 +238,7 @@ impl SaveSlot {
 
             match db_meta {
                 Ok(db) => {
+                    migrate_save(&db)?;
                     match db.get(db_keys::METADATA)? { // Propagates SledError
                         Some(ivec_data) => {
                             match decode_from_slice(&ivec_data[..], standard()) {
@@ -282,6 +357,52 @@ impl SaveSlot {
         }
     }
 
+    /// Replaces the whole game state of the slot with the batch and the metadata in one atomic write.
+    /// Keys the batch does not have are removed, and the metadata records the checksum of the batch.
+    /// A crash leaves either the previous save or this one, never a mix.
+    pub fn commit(&mut self, batch: SaveBatch, mut metadata: SaveSlotMetadata) -> Result<(), SaveDataToDBError> {
+        let handle = self.handle.as_ref().ok_or(SaveDataToDBError::MissingHandle)?;
+        metadata.checksum = Some(entries_checksum(&batch.entries));
+
+        let mut sled_batch = sled::Batch::default();
+        for key in handle.iter().keys() {
+            let key = key.map_err(SaveDataToDBError::Db)?;
+            if !batch.entries.contains_key(key.as_ref()) {
+                sled_batch.remove(key);
+            }
+        }
+        for (key, value) in batch.entries {
+            sled_batch.insert(key, value);
+        }
+        sled_batch.insert(db_keys::METADATA, bincode::encode_to_vec(&metadata, standard()).map_err(SaveDataToDBError::Encoding)?);
+
+        handle.apply_batch(sled_batch).map_err(|e| {
+            error!("Failed to commit save for slot '{}': {}", self.slot_id, e);
+            SaveDataToDBError::Db(e)
+        })?;
+        self.metadata = Some(metadata);
+        Ok(())
+    }
+
+    /// Checks the entries of the slot against the checksum in its metadata.
+    /// Saves that never recorded a checksum pass.
+    pub fn verify(&self) -> Result<(), SavesManagementError> {
+        let metadata = self.load_entry::<SaveSlotMetadata>(db_keys::METADATA)?;
+        let Some(expected) = metadata.checksum else {
+            return Ok(());
+        };
+        let handle = self.handle.as_ref().ok_or(LoadDataFromDBError::MissingHandle)?;
+        let entries = handle.iter().collect::<Result<Vec<_>, _>>()?;
+        let found = entries_checksum(entries.iter().map(|(key, value)| (key, value)));
+        if found != expected {
+            return Err(SavesManagementError::Corrupt {
+                slot_id: self.slot_id.clone(),
+                details: format!("checksum of {} entries is {}, the save recorded {}", entries.len() - 1, found, expected),
+            });
+        }
+        Ok(())
+    }
+
 
 }
 
@@ -317,21 +438,22 @@ pub fn scan_save_slots(saves_directory: &SavesDirectory) -> Result<Vec<SaveSlot>
 
                 match db_config.open() {
                     Ok(db) => {
-                        match db.get(db_keys::METADATA)? {
-                            Some(ivec_data) => {
-                                match decode_from_slice(&ivec_data[..], standard()) {
-                                    Ok((data, _size)) => {
-                                        slot_metadata = Some(data);
-                                        is_empty_slot = false;
-                                    }
-                                    Err(e) => {
-                                        eprintln!("Error deserializing bincode metadata for slot dir {}: {}", directory_name, e);
-                                    }
-                                }
+                        // Listing leaves the save as it is, it is migrated once the slot is loaded
+                        let metadata = read_save_version(&db).and_then(|version| match version {
+                            Some(version) => read_listing_metadata(&db, &version),
+                            None => Ok(None),
+                        });
+                        match metadata {
+                            Ok(Some(data)) => {
+                                slot_metadata = Some(data);
+                                is_empty_slot = false;
                             }
-                            None => {
+                            Ok(None) => {
                                 eprintln!("Metadata key '{}' not found in slot dir {}", db_keys::METADATA, directory_name);
                             }
+                            Err(e) => {
+                                eprintln!("Error reading metadata for slot dir {}: {}", directory_name, e);
+                            }
                         }
                     }
                     Err(e) => {
@@ -416,6 +538,18 @@ pub fn sanitize_foldername(name: &str) -> String {
 }
 
 
+/// `folder_name`, or `folder_name_N` with the first N not taken by another slot.
+pub fn unique_slot_folder(saves_directory: &SavesDirectory, folder_name: &str) -> String {
+    let mut final_folder_name = folder_name.to_string();
+    let mut counter = 1;
+    // Handle potential collisions by appending a number
+    while saves_directory.0.join(&final_folder_name).exists() {
+        final_folder_name = format!("{}_{}", folder_name, counter);
+        counter += 1;
+    }
+    final_folder_name
+}
+
 /// Creates a new save slot directory with a sanitized name and initializes it with metadata.
 /// The `user_visible_name` is stored in the metadata and also used to generate the sanitized folder name.
 /// Returns the sanitized folder name (slot_id) and the created metadata.
@@ -423,17 +557,12 @@ pub fn create_new_save_slot(
     saves_directory: &SavesDirectory,
     user_visible_name: &str, // This is the name the user sees and provides
     company_name: &str,
+    seed: u64,
 ) -> Result<(String, SaveSlotMetadata), SavesManagementError> {
     let sanitized_folder_name = sanitize_foldername(user_visible_name);
     info!("Creating new save slot. User name: '{}', Sanitized folder: '{}'", user_visible_name, sanitized_folder_name);
 
-    let mut final_folder_name = sanitized_folder_name.clone();
-    let mut counter = 1;
-    // Handle potential collisions by appending a number
-    while saves_directory.0.join(&final_folder_name).exists() {
-        final_folder_name = format!("{}_{}", sanitized_folder_name, counter);
-        counter += 1;
-    }
+    let final_folder_name = unique_slot_folder(saves_directory, &sanitized_folder_name);
     info!("Final folder name after collision check: '{}'", final_folder_name);
 
 
@@ -461,6 +590,9 @@ pub fn create_new_save_slot(
         sim_date: SimDate { year: 1, week: 1, day: 1, quarter_tick: 42 },
         save_version: save_version::SAVE_VERSION.to_string(),
         last_saved_timestamp: current_timestamp_secs,
+        seed,
+        data_packs: active_data_packs(),
+        checksum: None,
     };
     info!("Generated metadata: {:?}", metadata);
 
@@ -481,6 +613,18 @@ mod tests {
     use super::*;
     use tempfile::tempdir;
 
+    #[test]
+    fn open_db_reports_a_lock_that_is_never_released() {
+        let dir = tempdir().unwrap();
+        let config = sled::Config::default().path(dir.path().join("db"));
+        let _held = open_db(&config).unwrap();
+
+        match open_db(&config) {
+            Err(sled::Error::Io(e)) => assert!(is_lock_error(&e), "unexpected error: {}", e),
+            other => panic!("expected a lock error, got {:?}", other.map(|_| ())),
+        }
+    }
+
     #[test]
     fn ensure_db_handle_open_errors() {
         let dir = tempdir().unwrap();
@@ -517,7 +661,7 @@ mod tests {
     fn ensure_db_handle_open_success() {
         let dir = tempdir().unwrap();
         let saves_dir = SavesDirectory(dir.path().to_path_buf());
-        let (slot_id, _meta) = create_new_save_slot(&saves_dir, "slot", "Company").unwrap();
+        let (slot_id, _meta) = create_new_save_slot(&saves_dir, "slot", "Company", 7).unwrap();
         let mut slot = SaveSlot {
             slot_id: slot_id.clone(),
             path: dir.path().join(&slot_id),
@@ -533,11 +677,13 @@ mod tests {
     fn load_slot_success() {
         let dir = tempdir().unwrap();
         let saves_dir = SavesDirectory(dir.path().to_path_buf());
-        let (slot_id, meta) = create_new_save_slot(&saves_dir, "slot", "Company").unwrap();
+        let (slot_id, meta) = create_new_save_slot(&saves_dir, "slot", "Company", 7).unwrap();
         let slot = SaveSlot::load(slot_id.clone(), Arc::new(saves_dir)).unwrap();
         assert_eq!(slot.slot_id, slot_id);
         assert!(slot.handle.is_some());
-        assert_eq!(slot.metadata.unwrap().name, meta.name);
+        let metadata = slot.metadata.unwrap();
+        assert_eq!(metadata.name, meta.name);
+        assert_eq!(metadata.seed, 7);
         assert!(!slot.is_empty);
     }
 
@@ -583,10 +729,67 @@ mod tests {
     fn save_entry_roundtrip() {
         let dir = tempdir().unwrap();
         let saves_dir = SavesDirectory(dir.path().to_path_buf());
-        let (slot_id, _meta) = create_new_save_slot(&saves_dir, "slot", "Company").unwrap();
+        let (slot_id, _meta) = create_new_save_slot(&saves_dir, "slot", "Company", 7).unwrap();
         let mut slot = SaveSlot::load(slot_id, Arc::new(saves_dir)).unwrap();
         slot.save_entry("value", &123u32).unwrap();
         let loaded: u32 = slot.load_entry("value").unwrap();
         assert_eq!(loaded, 123u32);
     }
+
+    #[test]
+    fn commit_replaces_the_save_and_verifies() {
+        let dir = tempdir().unwrap();
+        let saves_dir = SavesDirectory(dir.path().to_path_buf());
+        let (slot_id, meta) = create_new_save_slot(&saves_dir, "slot", "Company", 7).unwrap();
+        let mut slot = SaveSlot::load(slot_id, Arc::new(saves_dir)).unwrap();
+        // Fresh slots have no checksum yet
+        slot.verify().unwrap();
+        slot.save_entry("employee1", &1u32).unwrap();
+
+        let mut batch = SaveBatch::default();
+        batch.insert("employee2", &2u32).unwrap();
+        batch.insert(db_keys::EMPLOYEES_LIST, &vec![2u32]).unwrap();
+        slot.commit(batch, meta).unwrap();
+
+        assert!(matches!(slot.load_entry::<u32>("employee1"), Err(LoadDataFromDBError::KeyNotFound(_))));
+        assert_eq!(slot.load_entry::<u32>("employee2").unwrap(), 2);
+        assert!(slot.load_entry::<SaveSlotMetadata>(db_keys::METADATA).unwrap().checksum.is_some());
+        slot.verify().unwrap();
+    }
+
+    #[test]
+    fn tampered_saves_are_corrupt() {
+        let dir = tempdir().unwrap();
+        let saves_dir = SavesDirectory(dir.path().to_path_buf());
+        let (slot_id, meta) = create_new_save_slot(&saves_dir, "slot", "Company", 7).unwrap();
+        let mut slot = SaveSlot::load(slot_id.clone(), Arc::new(saves_dir)).unwrap();
+        let mut batch = SaveBatch::default();
+        batch.insert(db_keys::EMPLOYEES_LIST, &vec![1u32, 2]).unwrap();
+        slot.commit(batch, meta).unwrap();
+
+        slot.save_entry(db_keys::EMPLOYEES_LIST, &vec![1u32]).unwrap();
+        match slot.verify().unwrap_err() {
+            SavesManagementError::Corrupt { slot_id: corrupt_slot, .. } => assert_eq!(corrupt_slot, slot_id),
+            err => panic!("unexpected error: {err:?}"),
+        }
+    }
+
+    #[test]
+    fn scanning_lists_old_saves_without_migrating_them() {
+        use crate::db::migrations::fixtures::write_save;
+
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("old").join(GAMESTATE_DB_FILENAME);
+        let db = sled::open(&db_path).unwrap();
+        write_save(&db, "0.0.1a");
+        drop(db);
+
+        let slots = scan_save_slots(&SavesDirectory(dir.path().to_path_buf())).unwrap();
+        let metadata = slots[0].metadata.as_ref().unwrap();
+        assert_eq!((metadata.name.as_str(), metadata.save_version.as_str()), ("Fixture", "0.0.1a"));
+        assert!(!slots[0].is_empty);
+
+        let db = open_db(&sled::Config::default().path(&db_path)).unwrap();
+        assert_eq!(read_save_version(&db).unwrap().as_deref(), Some("0.0.1a"));
+    }
 }
@@ -0,0 +1,255 @@
+use crate::db::checkpoints::CheckpointInfo;
+use crate::db::constants::{db_keys, GAMESTATE_DB_FILENAME};
+use crate::db::init::{open_db, SaveSlotMetadata};
+use crate::sim::calendar::components::{CalendarEvent, RecurringEventTemplate};
+use crate::sim::company::company::{Company, WorkHourPolicy};
+use crate::sim::company::finance::Finances;
+use crate::sim::person::components::ProfilePicture;
+use crate::sim::person::skill_progression::SkillProgress;
+use crate::sim::persistence::persistence::{SavedBehaviour, SavedEmployee};
+use crate::sim::project::customer::Customer;
+use crate::sim::project::functionality::Functionality;
+use crate::sim::project::project::Project;
+use crate::sim::project::requirement::Requirement;
+use crate::sim::project::task::Task;
+use crate::sim::resources::global::TickCounter;
+use crate::sim::team::components::Team;
+use crate::sim::utils::random::SimRngState;
+use crate::utils::errors::SavesManagementError;
+use bincode::config::standard;
+use bincode::{decode_from_slice, Decode};
+use serde::Serialize;
+use serde_json::{json, Value};
+use sled::Db;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::Path;
+
+/// Opens the game state of a slot folder, a checkpoint folder, or a `gamestate.sled` folder itself.
+/// Nothing is migrated, the entries are read as they are on disk.
+pub fn open_save_db(path: &Path) -> Result<Db, SavesManagementError> {
+    let nested = path.join(GAMESTATE_DB_FILENAME);
+    let db_path = if nested.is_dir() { nested } else { path.to_path_buf() };
+    // sled creates missing databases, a typo in the path should not
+    if !db_path.join("conf").is_file() {
+        return Err(SavesManagementError::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("No save found at {:?}", path),
+        )));
+    }
+    Ok(open_db(&sled::Config::default().path(db_path))?)
+}
+
+fn decode_json<T: Decode<()> + Serialize>(bytes: &[u8]) -> Result<Value, String> {
+    let (value, _) = decode_from_slice::<T, _>(bytes, standard()).map_err(|e| e.to_string())?;
+    serde_json::to_value(value).map_err(|e| e.to_string())
+}
+
+/// Decodes an entry with the type its key is saved as. Entries that do not decode are described instead,
+/// a broken entry is what the dump is looking for.
+fn decode_entry(key: &str, bytes: &[u8]) -> Value {
+    let decoded = match key {
+        db_keys::METADATA => decode_json::<SaveSlotMetadata>(bytes),
+        db_keys::CHECKPOINT_INFO => decode_json::<CheckpointInfo>(bytes),
+        db_keys::COMPANY => decode_json::<Company>(bytes),
+        db_keys::TEAMS => decode_json::<Vec<Team>>(bytes),
+        db_keys::TICK_COUNTER => decode_json::<TickCounter>(bytes),
+        db_keys::USED_PROFILE_PICTURES => decode_json::<Vec<ProfilePicture>>(bytes),
+        db_keys::EMPLOYEES_LIST => decode_json::<Vec<u32>>(bytes),
+        db_keys::CALENDAR_EVENTS => decode_json::<Vec<CalendarEvent>>(bytes),
+        db_keys::RECURRING_EVENT_TEMPLATES => decode_json::<Vec<RecurringEventTemplate>>(bytes),
+        db_keys::PROJECTS => decode_json::<Vec<Project>>(bytes),
+        db_keys::REQUIREMENTS => decode_json::<Vec<Requirement>>(bytes),
+        db_keys::FUNCTIONALITIES => decode_json::<Vec<Functionality>>(bytes),
+        db_keys::TASKS => decode_json::<Vec<Task>>(bytes),
+        db_keys::CUSTOMERS => decode_json::<Vec<Customer>>(bytes),
+        db_keys::FINANCES => decode_json::<Finances>(bytes),
+        db_keys::WORK_HOUR_POLICY => decode_json::<WorkHourPolicy>(bytes),
+        db_keys::RNG_STATE => decode_json::<SimRngState>(bytes),
+        _ if key.starts_with(db_keys::EMPLOYEE_PREFIX) => decode_json::<SavedEmployee>(bytes),
+        _ if key.starts_with(db_keys::SKILL_PROGRESS_PREFIX) => decode_json::<SkillProgress>(bytes),
+        _ if key.starts_with(db_keys::BEHAVIOUR_PREFIX) => decode_json::<SavedBehaviour>(bytes),
+        _ => Err("unknown key".to_string()),
+    };
+    decoded.unwrap_or_else(|error| json!({ "undecoded": error, "bytes": bytes.len() }))
+}
+
+/// Every entry of a save decoded into JSON, by key.
+pub fn dump_save(db: &Db) -> Result<BTreeMap<String, Value>, SavesManagementError> {
+    let mut dump = BTreeMap::new();
+    for entry in db.iter() {
+        let (key, value) = entry?;
+        let key = String::from_utf8_lossy(&key).into_owned();
+        let decoded = decode_entry(&key, &value);
+        dump.insert(key, decoded);
+    }
+    Ok(dump)
+}
+
+/// One difference between two saves. `path` leads from the entry key to the field, list items with an id
+/// are named by it: `teams[team_id=2].members`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum SaveChange {
+    Added { path: String, value: Value },
+    Removed { path: String, value: Value },
+    Changed { path: String, from: Value, to: Value },
+}
+
+impl fmt::Display for SaveChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveChange::Added { path, value } => write!(f, "+ {}: {}", path, value),
+            SaveChange::Removed { path, value } => write!(f, "- {}: {}", path, value),
+            SaveChange::Changed { path, from, to } => write!(f, "~ {}: {} -> {}", path, from, to),
+        }
+    }
+}
+
+/// Field level differences from the `old` dump to the `new` one.
+pub fn diff_saves(old: &BTreeMap<String, Value>, new: &BTreeMap<String, Value>) -> Vec<SaveChange> {
+    let mut changes = Vec::new();
+    for key in old.keys().chain(new.keys()).collect::<BTreeSet<_>>() {
+        match (old.get(key), new.get(key)) {
+            (Some(old), Some(new)) => diff_values(key.clone(), old, new, &mut changes),
+            (None, Some(new)) => changes.push(SaveChange::Added { path: key.clone(), value: new.clone() }),
+            (Some(old), None) => changes.push(SaveChange::Removed { path: key.clone(), value: old.clone() }),
+            (None, None) => {}
+        }
+    }
+    changes
+}
+
+fn diff_values(path: String, old: &Value, new: &Value, changes: &mut Vec<SaveChange>) {
+    if old == new {
+        return;
+    }
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            for field in old.keys().chain(new.keys()).collect::<BTreeSet<_>>() {
+                let field_path = format!("{}.{}", path, field);
+                match (old.get(field), new.get(field)) {
+                    (Some(old), Some(new)) => diff_values(field_path, old, new, changes),
+                    (None, Some(new)) => changes.push(SaveChange::Added { path: field_path, value: new.clone() }),
+                    (Some(old), None) => changes.push(SaveChange::Removed { path: field_path, value: old.clone() }),
+                    (None, None) => {}
+                }
+            }
+        }
+        (Value::Array(old), Value::Array(new)) => diff_lists(path, old, new, changes),
+        _ => changes.push(SaveChange::Changed { path, from: old.clone(), to: new.clone() }),
+    }
+}
+
+/// Lists of plain values are compared as sets, their order is often that of a hash set.
+/// Lists of objects are matched by id when they have one, by position otherwise.
+fn diff_lists(path: String, old: &[Value], new: &[Value], changes: &mut Vec<SaveChange>) {
+    let is_plain = |value: &Value| !value.is_object() && !value.is_array();
+    if old.iter().chain(new).all(is_plain) {
+        for value in new.iter().filter(|value| !old.contains(value)) {
+            changes.push(SaveChange::Added { path: path.clone(), value: value.clone() });
+        }
+        for value in old.iter().filter(|value| !new.contains(value)) {
+            changes.push(SaveChange::Removed { path: path.clone(), value: value.clone() });
+        }
+        return;
+    }
+
+    if let Some(id_field) = id_field(old, new) {
+        let by_id = |list: &[Value]| -> BTreeMap<String, Value> {
+            list.iter().map(|item| (item[id_field.as_str()].to_string(), item.clone())).collect()
+        };
+        let (old, new) = (by_id(old), by_id(new));
+        for id in old.keys().chain(new.keys()).collect::<BTreeSet<_>>() {
+            let item_path = format!("{}[{}={}]", path, id_field, id);
+            match (old.get(id), new.get(id)) {
+                (Some(old), Some(new)) => diff_values(item_path, old, new, changes),
+                (None, Some(new)) => changes.push(SaveChange::Added { path: item_path, value: new.clone() }),
+                (Some(old), None) => changes.push(SaveChange::Removed { path: item_path, value: old.clone() }),
+                (None, None) => {}
+            }
+        }
+        return;
+    }
+
+    for index in 0..old.len().max(new.len()) {
+        let item_path = format!("{}[{}]", path, index);
+        match (old.get(index), new.get(index)) {
+            (Some(old), Some(new)) => diff_values(item_path, old, new, changes),
+            (None, Some(new)) => changes.push(SaveChange::Added { path: item_path, value: new.clone() }),
+            (Some(old), None) => changes.push(SaveChange::Removed { path: item_path, value: old.clone() }),
+            (None, None) => {}
+        }
+    }
+}
+
+/// Field naming the items of both lists: `id`, or a field ending in `_id`, set on every item and unique in each list.
+fn id_field(old: &[Value], new: &[Value]) -> Option<String> {
+    let first = old.first().or(new.first())?.as_object()?;
+    let mut candidates: Vec<&String> = first.keys().filter(|field| *field == "id" || field.ends_with("_id")).collect();
+    candidates.sort_by_key(|field| *field != "id");
+    candidates
+        .into_iter()
+        .find(|field| {
+            [old, new].iter().all(|list| {
+                let ids: Vec<String> =
+                    list.iter().filter_map(|item| item.get(field.as_str())).filter(|id| !id.is_null()).map(Value::to_string).collect();
+                ids.len() == list.len() && ids.iter().collect::<BTreeSet<_>>().len() == ids.len()
+            })
+        })
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init::{create_new_save_slot, SaveBatch, SaveSlot, SavesDirectory};
+    use std::sync::Arc;
+    use tempfile::tempdir;
+
+    #[test]
+    fn dump_decodes_entries_by_key() {
+        let dir = tempdir().unwrap();
+        let saves_dir = SavesDirectory(dir.path().to_path_buf());
+        let (slot_id, meta) = create_new_save_slot(&saves_dir, "slot", "Company", 7).unwrap();
+        let mut slot = SaveSlot::load(slot_id.clone(), Arc::new(saves_dir)).unwrap();
+        let mut batch = SaveBatch::default();
+        batch.insert(db_keys::EMPLOYEES_LIST, &vec![3u32]).unwrap();
+        batch.insert(db_keys::COMPANY, &Company { name: "Acme".to_string(), slogan: String::new() }).unwrap();
+        batch.insert("mystery", &1u8).unwrap();
+        slot.commit(batch, meta).unwrap();
+        drop(slot);
+
+        let dump = dump_save(&open_save_db(&dir.path().join(slot_id)).unwrap()).unwrap();
+        assert_eq!(dump[db_keys::EMPLOYEES_LIST], json!([3]));
+        assert_eq!(dump[db_keys::COMPANY]["name"], "Acme");
+        assert_eq!(dump[db_keys::METADATA]["seed"], 7);
+        assert_eq!(dump["mystery"]["bytes"], 1);
+        assert!(open_save_db(&dir.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn diff_names_fields_and_list_items() {
+        let team = |members: Vec<u32>| json!([{"team_id": 2, "name": "Core", "description": "", "members": members}]);
+        let old = BTreeMap::from([
+            ("employee1".to_string(), json!({"hunger": {"value": 20}, "name": "Ann"})),
+            (db_keys::TEAMS.to_string(), team(vec![1])),
+        ]);
+        let new = BTreeMap::from([
+            ("employee1".to_string(), json!({"hunger": {"value": 35}, "name": "Ann"})),
+            (db_keys::TEAMS.to_string(), team(vec![4, 1])),
+            ("employee4".to_string(), json!({"name": "Bob"})),
+        ]);
+
+        let changes: Vec<String> = diff_saves(&old, &new).iter().map(|change| change.to_string()).collect();
+        assert_eq!(
+            changes,
+            vec![
+                "~ employee1.hunger.value: 20 -> 35",
+                "+ employee4: {\"name\":\"Bob\"}",
+                "+ teams[team_id=2].members: 4",
+            ]
+        );
+        assert!(diff_saves(&new, &new).is_empty());
+    }
+}
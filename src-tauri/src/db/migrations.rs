@@ -0,0 +1,468 @@
+use crate::db::constants::{db_keys, save_version};
+use crate::db::init::SaveSlotMetadata;
+use crate::master_data::data_pack::DataPackInfo;
+use crate::sim::calendar::components::recurring_event_template::RecurringEventTemplateId;
+use crate::sim::calendar::components::{CalendarEvent, CalendarEventId, EventDetails};
+use crate::sim::sim_date::sim_date::SimDate;
+use crate::utils::errors::SavesManagementError;
+use bincode::config::standard;
+use bincode::{decode_from_slice, encode_to_vec, Decode, Encode};
+use sled::Db;
+use tracing::info;
+
+/// Leading fields of the metadata, the same in every save version. Decoding stops after them,
+/// so the version can be read before knowing how the rest of the save is laid out.
+#[derive(Debug, Clone, Decode)]
+struct SaveVersionHeader {
+    name: String,
+    employee_count: u32,
+    sim_date: SimDate,
+    save_version: String,
+}
+
+/// Fields of the metadata every save version starts with, enough to list a save by.
+#[derive(Debug, Clone, Decode)]
+struct SaveListingHeader {
+    name: String,
+    employee_count: u32,
+    sim_date: SimDate,
+    save_version: String,
+    last_saved_timestamp: i64,
+}
+
+/// Rewrites the raw entries of a save from one version to the next, metadata included.
+pub struct Migration {
+    pub from: &'static str,
+    pub to: &'static str,
+    pub migrate: fn(&Db) -> Result<(), String>,
+}
+
+/// Every migration step, oldest first. A save is brought to [`save_version::SAVE_VERSION`] by running the steps
+/// from its version on. Changing a saved struct means bumping the version and adding a step here.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: "0.0.1a",
+        to: "0.0.2a",
+        migrate: migrate_to_0_0_2a,
+    },
+    Migration {
+        from: "0.0.2a",
+        to: "0.0.3a",
+        migrate: add_checksum,
+    },
+];
+
+/// Version of the save in `db`, `None` when it has no metadata yet.
+pub fn read_save_version(db: &Db) -> Result<Option<String>, SavesManagementError> {
+    match db.get(db_keys::METADATA)? {
+        Some(bytes) => {
+            let (header, _) = decode_from_slice::<SaveVersionHeader, _>(&bytes, standard())?;
+            Ok(Some(header.save_version))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Metadata to list a save of `version` with, read without migrating it. Saves of older versions are listed from
+/// the fields every version shares, the rest is left at its default until the save is loaded.
+pub fn read_listing_metadata(db: &Db, version: &str) -> Result<Option<SaveSlotMetadata>, SavesManagementError> {
+    let Some(bytes) = db.get(db_keys::METADATA)? else {
+        return Ok(None);
+    };
+    if version == save_version::SAVE_VERSION {
+        let (metadata, _) = decode_from_slice::<SaveSlotMetadata, _>(&bytes, standard())?;
+        return Ok(Some(metadata));
+    }
+    let (header, _) = decode_from_slice::<SaveListingHeader, _>(&bytes, standard())?;
+    Ok(Some(SaveSlotMetadata {
+        name: header.name,
+        employee_count: header.employee_count,
+        sim_date: header.sim_date,
+        save_version: header.save_version,
+        last_saved_timestamp: header.last_saved_timestamp,
+        seed: 0,
+        data_packs: vec![],
+        checksum: None,
+    }))
+}
+
+/// Brings the save in `db` to the current version. Returns whether anything was migrated.
+pub fn migrate_save(db: &Db) -> Result<bool, SavesManagementError> {
+    let mut migrated = false;
+    while let Some(version) = read_save_version(db)? {
+        if version == save_version::SAVE_VERSION {
+            break;
+        }
+        // Every version this build ever wrote has a step, any other version comes from a newer build
+        let step = MIGRATIONS.iter().find(|step| step.from == version).ok_or_else(|| SavesManagementError::SaveTooNew {
+            version: version.clone(),
+            supported: save_version::SAVE_VERSION.to_string(),
+        })?;
+        info!("Migrating save from {} to {}", step.from, step.to);
+        (step.migrate)(db).map_err(|reason| SavesManagementError::UnmigratableSave { version: version.clone(), reason })?;
+        if read_save_version(db)?.as_deref() != Some(step.to) {
+            return Err(SavesManagementError::UnmigratableSave {
+                version,
+                reason: format!("migration did not bring the save to {}", step.to),
+            });
+        }
+        migrated = true;
+    }
+    if migrated {
+        db.flush()?;
+    }
+    Ok(migrated)
+}
+
+fn decode_entry<T: Decode<()>>(db: &Db, key: &str) -> Result<T, String> {
+    let bytes = db.get(key).map_err(|e| e.to_string())?.ok_or_else(|| format!("'{}' is missing", key))?;
+    decode_from_slice(&bytes, standard()).map(|(value, _)| value).map_err(|e| format!("'{}' does not decode: {}", key, e))
+}
+
+fn encode_entry<T: Encode>(db: &Db, key: &str, value: &T) -> Result<(), String> {
+    let bytes = encode_to_vec(value, standard()).map_err(|e| e.to_string())?;
+    db.insert(key, bytes).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Metadata as saved by 0.0.1a.
+#[derive(Debug, Clone, Encode, Decode)]
+struct SaveSlotMetadataV0_0_1a {
+    name: String,
+    employee_count: u32,
+    sim_date: SimDate,
+    save_version: String,
+    last_saved_timestamp: i64,
+}
+
+/// Metadata as saved by 0.0.2a.
+#[derive(Debug, Clone, Encode, Decode)]
+struct SaveSlotMetadataV0_0_2a {
+    name: String,
+    employee_count: u32,
+    sim_date: SimDate,
+    save_version: String,
+    last_saved_timestamp: i64,
+    seed: u64,
+    data_packs: Vec<DataPackInfo>,
+}
+
+/// Calendar event as saved by 0.0.1a, before events could recur on their own.
+#[derive(Debug, Clone, Encode, Decode)]
+struct CalendarEventV0_0_1a {
+    id: CalendarEventId,
+    start_time: SimDate,
+    details: EventDetails,
+    template_id: Option<RecurringEventTemplateId>,
+}
+
+fn migrate_to_0_0_2a(db: &Db) -> Result<(), String> {
+    add_event_recurrence(db)?;
+    add_seed_and_data_packs(db)
+}
+
+/// 0.0.2a events carry their own recurrence, events of older saves become one-off events.
+/// Builds that added the field still wrote 0.0.1a for a while, events already in the new layout are kept.
+fn add_event_recurrence(db: &Db) -> Result<(), String> {
+    let Some(bytes) = db.get(db_keys::CALENDAR_EVENTS).map_err(|e| e.to_string())? else {
+        return Ok(());
+    };
+    if decode_whole::<Vec<CalendarEvent>>(&bytes).is_some() {
+        return Ok(());
+    }
+    let old = decode_whole::<Vec<CalendarEventV0_0_1a>>(&bytes)
+        .ok_or_else(|| format!("'{}' does not decode", db_keys::CALENDAR_EVENTS))?;
+    let events: Vec<CalendarEvent> = old
+        .into_iter()
+        .map(|event| CalendarEvent {
+            id: event.id,
+            start_time: event.start_time,
+            details: event.details,
+            template_id: event.template_id,
+            recurrence: None,
+        })
+        .collect();
+    encode_entry(db, db_keys::CALENDAR_EVENTS, &events)
+}
+
+/// Decodes `bytes` only if the value takes up all of them, telling layouts apart that share a prefix.
+fn decode_whole<T: Decode<()>>(bytes: &[u8]) -> Option<T> {
+    match decode_from_slice::<T, _>(bytes, standard()) {
+        Ok((value, read)) if read == bytes.len() => Some(value),
+        _ => None,
+    }
+}
+
+/// 0.0.2a records the seed and the data packs. Games from before had no fixed seed, they continue from seed 0,
+/// and no recorded packs means any active packs are accepted.
+fn add_seed_and_data_packs(db: &Db) -> Result<(), String> {
+    let old: SaveSlotMetadataV0_0_1a = decode_entry(db, db_keys::METADATA)?;
+    let metadata = SaveSlotMetadataV0_0_2a {
+        name: old.name,
+        employee_count: old.employee_count,
+        sim_date: old.sim_date,
+        save_version: "0.0.2a".to_string(),
+        last_saved_timestamp: old.last_saved_timestamp,
+        seed: 0,
+        data_packs: vec![],
+    };
+    encode_entry(db, db_keys::METADATA, &metadata)
+}
+
+/// 0.0.3a records a checksum of the save. Older saves get none, the next save of the game records it.
+fn add_checksum(db: &Db) -> Result<(), String> {
+    let old: SaveSlotMetadataV0_0_2a = decode_entry(db, db_keys::METADATA)?;
+    let metadata = SaveSlotMetadata {
+        name: old.name,
+        employee_count: old.employee_count,
+        sim_date: old.sim_date,
+        save_version: "0.0.3a".to_string(),
+        last_saved_timestamp: old.last_saved_timestamp,
+        seed: old.seed,
+        data_packs: old.data_packs,
+        checksum: None,
+    };
+    encode_entry(db, db_keys::METADATA, &metadata)
+}
+
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use super::*;
+    use crate::db::init::entries_checksum;
+    use crate::sim::ai::goap::CurrentGoal;
+    use crate::sim::calendar::components::{CalendarEvent, EventPriority, EventType, Recurrence, RecurrencePattern};
+    use crate::sim::company::company::Company;
+    use crate::sim::persistence::persistence::SavedEmployee;
+    use crate::sim::person::components::{Gender, Person, PersonId, ProfilePicture};
+    use crate::sim::person::morale::StressLevel;
+    use crate::sim::person::needs::{Energy, Hunger};
+    use crate::sim::person::personality_matrix::PersonalityMatrix;
+    use crate::sim::person::skills::SkillSet;
+    use crate::sim::person::spawner::TalentGrade;
+    use crate::sim::person::stats::{Stats, StatsConfig};
+    use crate::sim::person::thoughts::{ArchivedThoughts, Thoughts};
+    use crate::sim::resources::global::TickCounter;
+    use crate::sim::team::components::Team;
+
+    /// Writes a save the way the given version laid it out: a company with one employee and one calendar event,
+    /// a few ticks in.
+    pub(crate) fn write_save(db: &Db, version: &str) {
+        // Entries whose layout has not changed since 0.0.1a
+        encode_entry(db, db_keys::EMPLOYEES_LIST, &vec![1u32]).unwrap();
+        encode_entry(db, &format!("{}1", db_keys::EMPLOYEE_PREFIX), &employee()).unwrap();
+        encode_entry(db, db_keys::TEAMS, &Vec::<Team>::new()).unwrap();
+        encode_entry(db, db_keys::COMPANY, &Company { name: "Fixture".to_string(), slogan: String::new() }).unwrap();
+        let tick_counter = TickCounter::default();
+        for _ in 0..100 {
+            tick_counter.tick();
+        }
+        encode_entry(db, db_keys::TICK_COUNTER, &tick_counter).unwrap();
+
+        // Events recur on their own from 0.0.2a on
+        let event_start = SimDate { year: 1, week: 1, day: 3, quarter_tick: 37 };
+        if version == "0.0.1a" {
+            let event = CalendarEventV0_0_1a {
+                id: CalendarEventId::new(1),
+                start_time: event_start,
+                details: standup_details(),
+                template_id: None,
+            };
+            encode_entry(db, db_keys::CALENDAR_EVENTS, &vec![event]).unwrap();
+        } else {
+            let event = CalendarEvent {
+                id: CalendarEventId::new(1),
+                start_time: event_start,
+                details: standup_details(),
+                template_id: None,
+                recurrence: Some(Recurrence::new(RecurrencePattern::Weekly)),
+            };
+            encode_entry(db, db_keys::CALENDAR_EVENTS, &vec![event]).unwrap();
+        }
+
+        let sim_date = SimDate { year: 1, week: 1, day: 2, quarter_tick: 5 };
+        match version {
+            "0.0.1a" => {
+                let metadata = SaveSlotMetadataV0_0_1a {
+                    name: "Fixture".to_string(),
+                    employee_count: 1,
+                    sim_date,
+                    save_version: version.to_string(),
+                    last_saved_timestamp: 1_700_000_000,
+                };
+                encode_entry(db, db_keys::METADATA, &metadata).unwrap();
+            }
+            "0.0.2a" => {
+                let metadata = SaveSlotMetadataV0_0_2a {
+                    name: "Fixture".to_string(),
+                    employee_count: 1,
+                    sim_date,
+                    save_version: version.to_string(),
+                    last_saved_timestamp: 1_700_000_000,
+                    seed: 11,
+                    data_packs: vec![],
+                };
+                encode_entry(db, db_keys::METADATA, &metadata).unwrap();
+            }
+            "0.0.3a" => {
+                let entries = db.iter().collect::<Result<Vec<_>, _>>().unwrap();
+                let metadata = SaveSlotMetadata {
+                    name: "Fixture".to_string(),
+                    employee_count: 1,
+                    sim_date,
+                    save_version: version.to_string(),
+                    last_saved_timestamp: 1_700_000_000,
+                    seed: 11,
+                    data_packs: vec![],
+                    checksum: Some(entries_checksum(entries)),
+                };
+                encode_entry(db, db_keys::METADATA, &metadata).unwrap();
+            }
+            _ => panic!("No fixture for save version {version}"),
+        }
+        db.flush().unwrap();
+    }
+
+    fn employee() -> SavedEmployee {
+        SavedEmployee {
+            person: Person {
+                person_id: PersonId(1),
+                name: "Ada Fixture".to_string(),
+                gender: Gender::Female,
+                team: None,
+                talent_grade: TalentGrade::Apt,
+                joined: 0,
+            },
+            stats: Stats::from(StatsConfig::default()),
+            profile_picture: ProfilePicture::default(),
+            personality_matrix: PersonalityMatrix::default(),
+            skill_set: SkillSet::default(),
+            energy: Energy::default(),
+            hunger: Hunger::default(),
+            current_goal: CurrentGoal::default(),
+            stress_level: StressLevel::default(),
+            thoughts: Thoughts::new(),
+            archived_thoughts: ArchivedThoughts::new(),
+        }
+    }
+
+    /// Details of the calendar event in the fixture saves.
+    pub(crate) fn standup_details() -> EventDetails {
+        EventDetails {
+            title: "Standup".to_string(),
+            duration_ticks: 2,
+            participants: vec![],
+            event_type: EventType::Meeting,
+            priority: EventPriority::Normal,
+        }
+    }
+
+    /// Every version a save can be in.
+    pub(crate) fn all_versions() -> Vec<&'static str> {
+        MIGRATIONS.iter().map(|step| step.from).chain([save_version::SAVE_VERSION]).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fixtures::{all_versions, standup_details, write_save};
+    use super::*;
+    use tempfile::tempdir;
+
+    fn open(dir: &std::path::Path) -> Db {
+        sled::Config::default().path(dir).open().unwrap()
+    }
+
+    #[test]
+    fn every_past_version_migrates_to_the_current_one() {
+        for version in all_versions() {
+            let dir = tempdir().unwrap();
+            let db = open(dir.path());
+            write_save(&db, version);
+
+            assert_eq!(migrate_save(&db).unwrap(), version != save_version::SAVE_VERSION);
+            let (metadata, _) = decode_from_slice::<SaveSlotMetadata, _>(&db.get(db_keys::METADATA).unwrap().unwrap(), standard()).unwrap();
+            assert_eq!(metadata.save_version, save_version::SAVE_VERSION, "from {version}");
+            assert_eq!(metadata.name, "Fixture");
+            let events: Vec<CalendarEvent> = decode_entry(&db, db_keys::CALENDAR_EVENTS).unwrap();
+            assert_eq!(events.len(), 1, "from {version}");
+            // Already current, nothing more to do
+            assert!(!migrate_save(&db).unwrap());
+        }
+    }
+
+    #[test]
+    fn calendar_events_of_0_0_1a_become_one_off_events() {
+        let dir = tempdir().unwrap();
+        let db = open(dir.path());
+        write_save(&db, "0.0.1a");
+        let legacy = CalendarEventV0_0_1a {
+            id: CalendarEventId::new(3),
+            start_time: SimDate { year: 1, week: 1, day: 2, quarter_tick: 37 },
+            details: standup_details(),
+            template_id: None,
+        };
+        encode_entry(&db, db_keys::CALENDAR_EVENTS, &vec![legacy.clone(), legacy]).unwrap();
+
+        migrate_save(&db).unwrap();
+        let events: Vec<CalendarEvent> = decode_entry(&db, db_keys::CALENDAR_EVENTS).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].id, CalendarEventId::new(3));
+        assert_eq!(events[0].details.title, "Standup");
+        assert!(events.iter().all(|event| event.recurrence.is_none()));
+    }
+
+    #[test]
+    fn calendar_events_already_recurring_in_0_0_1a_are_kept() {
+        use crate::sim::calendar::components::{Recurrence, RecurrencePattern};
+
+        let dir = tempdir().unwrap();
+        let db = open(dir.path());
+        write_save(&db, "0.0.1a");
+        let event = CalendarEvent {
+            id: CalendarEventId::new(4),
+            start_time: SimDate { year: 1, week: 1, day: 2, quarter_tick: 37 },
+            details: standup_details(),
+            template_id: None,
+            recurrence: Some(Recurrence::new(RecurrencePattern::Weekly)),
+        };
+        encode_entry(&db, db_keys::CALENDAR_EVENTS, &vec![event]).unwrap();
+
+        migrate_save(&db).unwrap();
+        let events: Vec<CalendarEvent> = decode_entry(&db, db_keys::CALENDAR_EVENTS).unwrap();
+        assert_eq!(events[0].recurrence, Some(Recurrence::new(RecurrencePattern::Weekly)));
+    }
+
+    #[test]
+    fn migrations_chain_up_to_the_current_version() {
+        let mut version = MIGRATIONS[0].from;
+        for step in MIGRATIONS {
+            assert_eq!(step.from, version);
+            version = step.to;
+        }
+        assert_eq!(version, save_version::SAVE_VERSION);
+    }
+
+    #[test]
+    fn newer_saves_are_refused() {
+        let dir = tempdir().unwrap();
+        let db = open(dir.path());
+        write_save(&db, save_version::SAVE_VERSION);
+        let mut metadata: SaveSlotMetadata = decode_entry(&db, db_keys::METADATA).unwrap();
+        metadata.save_version = "9.9.9".to_string();
+        encode_entry(&db, db_keys::METADATA, &metadata).unwrap();
+
+        assert!(matches!(migrate_save(&db), Err(SavesManagementError::SaveTooNew { version, .. }) if version == "9.9.9"));
+    }
+
+    #[test]
+    fn broken_old_saves_are_unmigratable() {
+        let dir = tempdir().unwrap();
+        let db = open(dir.path());
+        write_save(&db, "0.0.1a");
+        // Header intact, timestamp cut off
+        let bytes = db.get(db_keys::METADATA).unwrap().unwrap();
+        db.insert(db_keys::METADATA, &bytes[..bytes.len() - 1]).unwrap();
+
+        assert!(matches!(migrate_save(&db), Err(SavesManagementError::UnmigratableSave { version, .. }) if version == "0.0.1a"));
+    }
+}
@@ -1,2 +1,6 @@
 pub mod init;
-pub mod constants;
\ No newline at end of file
+pub mod constants;
+pub mod migrations;
+pub mod checkpoints;
+pub mod archive;
+pub mod inspect;
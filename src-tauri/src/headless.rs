@@ -0,0 +1,452 @@
+use crate::action_queues::sim_manager::SimManager;
+use crate::db::init::SavesDirectory;
+use crate::integrations::queues::{QueueManager, UICommandQueues};
+use crate::integrations::snapshots::snapshots::SnapshotState;
+use crate::integrations::snapshots_emitter::sink::JsonLinesSink;
+use crate::integrations::ui::AppContext;
+use crate::master_data::data_pack::{load_master_data, MASTER_DATA};
+use crate::resources::init::{initialize_emit_registries, initialize_non_shared_resources};
+use crate::schedules::init::{init_schedules, GameSchedules};
+use crate::sim::ai::goap::CurrentGoal;
+use crate::sim::company::company::PlayerControlled;
+use crate::sim::company::finance::Finances;
+use crate::sim::game_speed::components::GameSpeedManager;
+use crate::sim::new_game::new_game::{CompanyPreset, StartingEmployeesConfig};
+use crate::sim::persistence::persistence::LoadGame;
+use crate::sim::person::components::Person;
+use crate::sim::person::init::{FirstRun, ShouldGenerateEmployees};
+use crate::sim::person::morale::StressLevel;
+use crate::sim::person::needs::{Energy, Hunger};
+use crate::sim::resources::global::{AssetBasePath, TickCounter};
+use crate::sim::utils::sim_reset::ResetRequest;
+use crate::utils::errors::HeadlessError;
+use legion::{IntoQuery, Resources, World};
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::info;
+
+pub const USAGE: &str = "Usage: you-need-to-deliver --headless --ticks <n> [--seed <n>] [--company <name>] \
+[--employees <name>] [--every <ticks>] [--assets <dir>] [--mods <dir>] [--out <file.csv|file.json>] [--snapshots <file.jsonl>]";
+
+/// Settings of a headless run, read from the command line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeadlessOptions {
+    pub ticks: u64,
+    pub seed: u64,
+    /// Company preset name, the first preset when not given
+    pub company: Option<String>,
+    /// Starting employees config name, the first config when not given
+    pub employees: Option<String>,
+    /// Ticks between two metric samples
+    pub sample_every: u64,
+    pub asset_base_path: PathBuf,
+    pub mods_path: Option<PathBuf>,
+    /// Metrics are written as JSON when the file ends in `.json`, as CSV otherwise
+    pub output: PathBuf,
+    /// Snapshots and events are streamed here as JSON lines when set
+    pub snapshots: Option<PathBuf>,
+}
+
+impl HeadlessOptions {
+    /// `None` unless `--headless` is on the command line.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Option<Self>, HeadlessError> {
+        let args: Vec<String> = args.into_iter().collect();
+        if !args.iter().any(|arg| arg == "--headless") {
+            return Ok(None);
+        }
+        let mut options = Self {
+            ticks: 0,
+            seed: 0,
+            company: None,
+            employees: None,
+            sample_every: 4,
+            asset_base_path: PathBuf::from("assets"),
+            mods_path: None,
+            output: PathBuf::from("headless_metrics.csv"),
+            snapshots: None,
+        };
+
+        let mut args = args.into_iter().filter(|arg| arg != "--headless");
+        while let Some(flag) = args.next() {
+            let mut value = || args.next().ok_or_else(|| HeadlessError::Args(format!("{flag} needs a value\n{USAGE}")));
+            let number = |value: String| {
+                value.parse::<u64>().map_err(|_| HeadlessError::Args(format!("{flag} expects a number, got '{value}'\n{USAGE}")))
+            };
+            match flag.as_str() {
+                "--ticks" => options.ticks = number(value()?)?,
+                "--seed" => options.seed = number(value()?)?,
+                "--every" => options.sample_every = number(value()?)?.max(1),
+                "--company" => options.company = Some(value()?),
+                "--employees" => options.employees = Some(value()?),
+                "--assets" => options.asset_base_path = PathBuf::from(value()?),
+                "--mods" => options.mods_path = Some(PathBuf::from(value()?)),
+                "--out" => options.output = PathBuf::from(value()?),
+                "--snapshots" => options.snapshots = Some(PathBuf::from(value()?)),
+                _ => return Err(HeadlessError::Args(format!("Unknown argument '{flag}'\n{USAGE}"))),
+            }
+        }
+        if options.ticks == 0 {
+            return Err(HeadlessError::Args(format!("--ticks is required\n{USAGE}")));
+        }
+        Ok(Some(options))
+    }
+}
+
+/// Averages over the employees at one point of the run.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetricsSample {
+    pub tick: u64,
+    pub date: String,
+    pub employees: usize,
+    pub avg_stress: f32,
+    pub max_stress: f32,
+    pub avg_energy: f32,
+    pub avg_hunger: f32,
+    pub cash: i64,
+    /// How many employees pursue each goal
+    pub goals: BTreeMap<String, usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HeadlessReport {
+    pub seed: u64,
+    pub company: String,
+    pub employees: String,
+    pub ticks: u64,
+    pub samples: Vec<MetricsSample>,
+}
+
+impl HeadlessReport {
+    pub fn write_csv(&self, out: &mut impl Write) -> std::io::Result<()> {
+        let goals: BTreeSet<&String> = self.samples.iter().flat_map(|sample| sample.goals.keys()).collect();
+        write!(out, "tick,date,employees,avg_stress,max_stress,avg_energy,avg_hunger,cash")?;
+        for goal in &goals {
+            write!(out, ",goal_{}", goal)?;
+        }
+        writeln!(out)?;
+        for sample in &self.samples {
+            write!(
+                out,
+                "{},{},{},{:.3},{:.3},{:.3},{:.3},{}",
+                sample.tick,
+                sample.date,
+                sample.employees,
+                sample.avg_stress,
+                sample.max_stress,
+                sample.avg_energy,
+                sample.avg_hunger,
+                sample.cash
+            )?;
+            for goal in &goals {
+                write!(out, ",{}", sample.goals.get(*goal).copied().unwrap_or_default())?;
+            }
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+
+    pub fn write_to(&self, path: &PathBuf) -> Result<(), HeadlessError> {
+        let mut out = BufWriter::new(File::create(path)?);
+        if path.extension().is_some_and(|extension| extension == "json") {
+            serde_json::to_writer_pretty(&mut out, self)?;
+        } else {
+            self.write_csv(&mut out)?;
+        }
+        out.flush()?;
+        Ok(())
+    }
+}
+
+fn sample(world: &World, tick_counter: &TickCounter) -> MetricsSample {
+    let date = tick_counter.current_date();
+    let mut sample = MetricsSample {
+        tick: tick_counter.value(),
+        date: format!("Y{}-W{}-D{}-Q{}", date.year, date.week, date.day, date.quarter_tick),
+        employees: 0,
+        avg_stress: 0.0,
+        max_stress: 0.0,
+        avg_energy: 0.0,
+        avg_hunger: 0.0,
+        cash: 0,
+        goals: BTreeMap::new(),
+    };
+    for (_, stress, energy, hunger, goal) in
+        <(&Person, &StressLevel, &Energy, &Hunger, Option<&CurrentGoal>)>::query().iter(world)
+    {
+        sample.employees += 1;
+        sample.avg_stress += stress.current;
+        sample.max_stress = sample.max_stress.max(stress.current);
+        sample.avg_energy += energy.value() as f32;
+        sample.avg_hunger += hunger.value() as f32;
+        let goal = goal.map_or_else(|| "None".to_string(), |goal| goal.0.to_string());
+        *sample.goals.entry(goal).or_default() += 1;
+    }
+    if sample.employees > 0 {
+        let count = sample.employees as f32;
+        sample.avg_stress /= count;
+        sample.avg_energy /= count;
+        sample.avg_hunger /= count;
+    }
+    sample.cash = <(&Finances, &PlayerControlled)>::query().iter(world).map(|(finances, _)| finances.cash).sum();
+    sample
+}
+
+fn find_preset<T: Clone>(presets: &[T], name: &Option<String>, name_of: impl Fn(&T) -> &str, kind: &'static str) -> Result<T, HeadlessError> {
+    match name {
+        Some(name) => presets.iter().find(|preset| name_of(preset) == name).cloned(),
+        None => presets.first().cloned(),
+    }
+    .ok_or_else(|| HeadlessError::UnknownPreset { kind, name: name.clone().unwrap_or_default() })
+}
+
+/// A game running on the schedules alone, without the app or a simulation thread.
+pub struct HeadlessSim {
+    pub world: World,
+    pub resources: Resources,
+    pub schedules: GameSchedules,
+    pub tick_counter: Arc<TickCounter>,
+    pub sim_manager: Arc<SimManager>,
+    pub company: String,
+    pub employees: String,
+}
+
+impl HeadlessSim {
+    /// Sets up the resources of a game for `options` without starting it. Saves go to `saves_directory`.
+    pub fn prepare(options: &HeadlessOptions, saves_directory: SavesDirectory) -> Result<Self, HeadlessError> {
+        // Without a mods folder only the base pack is read
+        load_master_data(&options.asset_base_path, &options.mods_path.clone().unwrap_or_default())?;
+        let master_data = MASTER_DATA.get().cloned().unwrap_or_default();
+        let company = find_preset(&master_data.company_presets, &options.company, |preset: &CompanyPreset| &preset.name, "company preset")?;
+        let employees = find_preset(
+            &master_data.starting_employee_configs,
+            &options.employees,
+            |config: &StartingEmployeesConfig| &config.name,
+            "starting employees config",
+        )?;
+
+        let sim_manager = Arc::new(SimManager::default());
+        *sim_manager.company_preset.write() = company.clone();
+        *sim_manager.employees_preset.write() = employees.clone();
+        *sim_manager.seed.write() = options.seed;
+        sim_manager.resume_sim();
+
+        let queue_manager = QueueManager::new();
+        let command_queues = UICommandQueues { runtime: queue_manager.dispatch(), control: queue_manager.sim_manager_dispatch() };
+        let snapshot_state = Arc::new(SnapshotState::default());
+        let snapshot_registry = Arc::new(initialize_emit_registries(&snapshot_state));
+        let tick_counter = Arc::new(TickCounter::default());
+
+        let mut resources = Resources::default();
+        let app_context = match &options.snapshots {
+            Some(path) => AppContext::with_sink(Arc::new(JsonLinesSink::create(path)?)),
+            None => AppContext::headless(),
+        };
+        resources.insert(Arc::new(app_context));
+        resources.insert(Arc::new(ResetRequest::default()));
+        resources.insert(Arc::new(FirstRun::default()));
+        resources.insert(Arc::clone(&sim_manager));
+        resources.insert(queue_manager);
+        resources.insert(Arc::new(command_queues));
+        resources.insert(Arc::clone(&tick_counter));
+        resources.insert(Arc::new(RwLock::new(GameSpeedManager::default())));
+        resources.insert(snapshot_state);
+        resources.insert(AssetBasePath(options.asset_base_path.clone()));
+        resources.insert(snapshot_registry);
+        resources.insert(Arc::new(saves_directory));
+        resources.insert(Arc::new(LoadGame::default()));
+        initialize_non_shared_resources(&mut resources);
+
+        Ok(Self {
+            world: World::default(),
+            resources,
+            schedules: init_schedules(),
+            tick_counter,
+            sim_manager,
+            company: company.name,
+            employees: employees.name,
+        })
+    }
+
+    /// A new game for `options`, employees generated. Nothing is saved, there is no save slot.
+    pub fn new(options: &HeadlessOptions) -> Result<Self, HeadlessError> {
+        let mut sim = Self::prepare(options, SavesDirectory(PathBuf::new()))?;
+        sim.world.push((ShouldGenerateEmployees(true),));
+        sim.schedules.startup.execute(&mut sim.world, &mut sim.resources);
+        Ok(sim)
+    }
+
+    /// Runs the schedules of one tick, in the order the simulation thread runs them.
+    pub fn tick(&mut self) {
+        self.schedules.dispatcher_queue.execute(&mut self.world, &mut self.resources);
+        self.schedules.subsystem_command.execute(&mut self.world, &mut self.resources);
+        self.schedules.sim.execute(&mut self.world, &mut self.resources);
+        self.schedules.pre_integration.execute(&mut self.world, &mut self.resources);
+        self.schedules.integration.execute(&mut self.world, &mut self.resources);
+        self.schedules.post_integration.execute(&mut self.world, &mut self.resources);
+    }
+}
+
+/// Runs the game schedules without the app, as fast as they go, sampling metrics along the way.
+pub fn run_headless(options: &HeadlessOptions) -> Result<HeadlessReport, HeadlessError> {
+    let mut sim = HeadlessSim::new(options)?;
+    info!("Headless run of {} ticks, seed {}, '{}' with '{}'", options.ticks, options.seed, sim.company, sim.employees);
+
+    let started = Instant::now();
+    let mut samples = vec![sample(&sim.world, &sim.tick_counter)];
+    for _ in 0..options.ticks {
+        sim.tick();
+        if sim.tick_counter.value() % options.sample_every == 0 {
+            samples.push(sample(&sim.world, &sim.tick_counter));
+        }
+    }
+    info!("Ran {} ticks in {:?}", options.ticks, started.elapsed());
+
+    Ok(HeadlessReport { seed: options.seed, company: sim.company, employees: sim.employees, ticks: options.ticks, samples })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::archive::import_save_slot;
+    use crate::db::init::{create_new_save_slot, SaveSlot};
+    use crate::integrations::snapshots_emitter::snapshots_emitter::SnapshotEmitRegistry;
+    use crate::sim::action::action::ActionIntent;
+    use crate::sim::ai::goap::{CurrentPlan, EmployeeGoapFacts};
+    use crate::sim::ai::planner::PlannedGoal;
+    use crate::sim::company::work_hours::WorkHours;
+    use crate::sim::person::components::PersonId;
+    use crate::sim::persistence::persistence::load_game_state;
+    use crate::sim::person::thoughts::Thoughts;
+    use tempfile::tempdir;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn options_come_from_the_command_line() {
+        assert_eq!(HeadlessOptions::from_args(args("--verbose")).unwrap(), None);
+        let options = HeadlessOptions::from_args(args("--headless --ticks 96 --seed 5 --out run.json")).unwrap().unwrap();
+        assert_eq!(options.ticks, 96);
+        assert_eq!(options.seed, 5);
+        assert_eq!(options.output, PathBuf::from("run.json"));
+        assert!(matches!(HeadlessOptions::from_args(args("--headless --ticks x")), Err(HeadlessError::Args(_))));
+        assert!(matches!(HeadlessOptions::from_args(args("--headless --seed 1")), Err(HeadlessError::Args(_))));
+    }
+
+    #[test]
+    fn csv_has_a_column_per_goal() {
+        let sample = |tick: u64, goal: &str| MetricsSample {
+            tick,
+            date: String::new(),
+            employees: 1,
+            avg_stress: 1.0,
+            max_stress: 1.0,
+            avg_energy: 50.0,
+            avg_hunger: 50.0,
+            cash: 10,
+            goals: BTreeMap::from([(goal.to_string(), 1)]),
+        };
+        let report = HeadlessReport {
+            seed: 0,
+            company: String::new(),
+            employees: String::new(),
+            ticks: 2,
+            samples: vec![sample(1, "Rest"), sample(2, "Work")],
+        };
+        let mut out = Vec::new();
+        report.write_csv(&mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert!(lines[0].ends_with(",cash,goal_Rest,goal_Work"));
+        assert!(lines[1].ends_with(",10,1,0"));
+        assert!(lines[2].ends_with(",10,0,1"));
+    }
+
+    #[test]
+    fn same_seed_same_run() {
+        let options = HeadlessOptions::from_args(args("--headless --ticks 48 --every 12 --seed 3")).unwrap().unwrap();
+        let first = run_headless(&options).unwrap();
+        let second = run_headless(&options).unwrap();
+        assert!(first.samples[0].employees > 0);
+        assert_eq!(first.samples.len(), 5);
+        assert_eq!(first, second);
+    }
+
+    /// Everything about an employee that decides what they do next, by id.
+    fn employee_states(world: &World) -> BTreeMap<PersonId, String> {
+        <(
+            &Person,
+            &StressLevel,
+            &Energy,
+            &Hunger,
+            &CurrentGoal,
+            &Thoughts,
+            Option<&ActionIntent>,
+            Option<&CurrentPlan>,
+            Option<&PlannedGoal>,
+            Option<&EmployeeGoapFacts>,
+            Option<&WorkHours>,
+        )>::query()
+        .iter(world)
+        .map(|(person, stress, energy, hunger, goal, thoughts, intent, plan, planned_goal, facts, work_hours)| {
+            let state = (stress, energy, hunger, goal, thoughts, intent, plan, planned_goal, facts, work_hours);
+            (person.person_id, format!("{:?}", state))
+        })
+        .collect()
+    }
+
+    #[test]
+    fn reloaded_game_runs_on_like_the_original() {
+        let dir = tempdir().unwrap();
+        let options = HeadlessOptions::from_args(args("--headless --ticks 1 --seed 9")).unwrap().unwrap();
+        let mut original = HeadlessSim::new(&options).unwrap();
+        for _ in 0..60 {
+            original.tick();
+        }
+
+        // Save the state of one tick and carry it over as an archive, the slot stays with the original game
+        let saves_directory = Arc::new(SavesDirectory(dir.path().join("original")));
+        let (slot_id, _) = create_new_save_slot(&saves_directory, "Round trip", &original.company, options.seed).unwrap();
+        original.sim_manager.set_save_slot(SaveSlot::load(slot_id, Arc::clone(&saves_directory)).unwrap());
+        original.tick();
+        let archive = dir.path().join("round_trip.save");
+        original.sim_manager.with_save_slot(|slot| slot.export_archive(&archive)).unwrap().unwrap();
+        original.sim_manager.clear_save_slot();
+
+        let reloaded_saves = SavesDirectory(dir.path().join("reloaded"));
+        let slot_id = import_save_slot(&reloaded_saves, &archive).unwrap();
+        let mut reloaded = HeadlessSim::prepare(&options, reloaded_saves).unwrap();
+        let snapshot_state = Arc::clone(&reloaded.resources.get::<Arc<SnapshotState>>().unwrap());
+        let snapshot_registry = Arc::clone(&reloaded.resources.get::<Arc<SnapshotEmitRegistry>>().unwrap());
+        let load_game = Arc::new(LoadGame::default());
+        *load_game.slot_id.write() = Some(slot_id);
+        load_game_state(
+            &mut reloaded.world,
+            &mut reloaded.resources,
+            &load_game,
+            &reloaded.tick_counter,
+            &reloaded.sim_manager,
+            &snapshot_state,
+            &snapshot_registry,
+            &mut reloaded.schedules,
+        )
+        .unwrap();
+        reloaded.sim_manager.clear_save_slot();
+        assert!(!employee_states(&original.world).is_empty());
+        assert_eq!(employee_states(&reloaded.world), employee_states(&original.world));
+
+        for tick in 0..96 {
+            original.tick();
+            reloaded.tick();
+            assert_eq!(employee_states(&reloaded.world), employee_states(&original.world), "{tick} ticks after the reload");
+        }
+        assert_eq!(reloaded.tick_counter.value(), original.tick_counter.value());
+    }
+}
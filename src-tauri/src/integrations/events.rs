@@ -1,7 +1,7 @@
 // src/events.rs
 
 use serde::Serialize;
-use tauri::{AppHandle, Emitter};
+use crate::integrations::snapshots_emitter::sink::{send_to, SnapshotSink};
 
 // --- 1. Define the Single, Generic UI Notification Payload Struct ---
 // This struct remains the same, as it's the standard format for the frontend.
@@ -40,6 +40,10 @@ pub enum AppEventType {
         employee_name: String,
         role: String,
     },
+    Bankruptcy {
+        company_name: String,
+        balance: i64,
+    },
     BugFound {
         bug_id: u32,
         description: String, // Description provided by the system
@@ -58,7 +62,7 @@ pub enum AppEventType {
 
 // --- 3. The Wrapper Function That Constructs the Payload ---
 // This function maps the AppEventType to its string name and constructs the UINotificationPayload.
-pub fn emit_app_event(app_handle: &AppHandle, event_type: AppEventType) {
+pub fn emit_app_event(sink: &dyn SnapshotSink, event_type: AppEventType) {
     let (event_name, payload) = match event_type {
         AppEventType::TalentPoolRefreshed => {
             ("talent_pool_refreshed", UINotificationPayload {
@@ -108,6 +112,14 @@ pub fn emit_app_event(app_handle: &AppHandle, event_type: AppEventType) {
                 message: format!("{} has joined as a {}!", employee_name, role),
             })
         },
+        AppEventType::Bankruptcy { company_name, balance } => {
+            ("bankruptcy", UINotificationPayload {
+                context_id: None,
+                notification_type: "error".to_string(),
+                title: "Bankrupt".to_string(),
+                message: format!("{} has run out of money. Balance: {}", company_name, balance),
+            })
+        },
         AppEventType::BugFound { bug_id, description, severity } => {
             ("bug_found", UINotificationPayload {
                 context_id: Some(bug_id.to_string()),
@@ -142,10 +154,7 @@ pub fn emit_app_event(app_handle: &AppHandle, event_type: AppEventType) {
     };
 
     // `payload` is now always UINotificationPayload, which implements Serialize + Clone.
-    app_handle.emit(event_name, payload)
-        .unwrap_or_else(|e| {
-            eprintln!("Failed to emit event '{}': {:?}", event_name, e);
-        });
+    send_to(sink, event_name, &payload);
 }
 
 
@@ -1,4 +1,6 @@
 use crate::action_queues::game_speed_manager::GameSpeedManagerCommand;
+use crate::action_queues::calendar_manager::CalendarCommand;
+use crate::action_queues::offer_manager::OfferCommand;
 use crate::action_queues::sim_manager::SimManager;
 use crate::action_queues::sim_manager::SimManagerCommand;
 use crate::action_queues::team_manager::{TeamAssignmentCommand, TeamManagerCommand};
@@ -31,6 +33,8 @@ pub enum SimCommand {
     TeamManager(TeamManagerCommand),
     TeamAssignment(TeamAssignmentCommand),
     Thought(ThoughtCommand),
+    Offer(OfferCommand),
+    Calendar(CalendarCommand),
 }
 
 impl fmt::Debug for SimCommand {
@@ -41,6 +45,8 @@ impl fmt::Debug for SimCommand {
             SimCommand::TeamManager(_) => write!(f, "SimCommand::TeamManager(...)"),
             SimCommand::TeamAssignment(_) => write!(f, "SimCommand::TeamAssignment(...)") ,
             SimCommand::Thought(_) => write!(f, "SimCommand::Thought(...)") ,
+            SimCommand::Offer(_) => write!(f, "SimCommand::Offer(...)") ,
+            SimCommand::Calendar(_) => write!(f, "SimCommand::Calendar(...)") ,
         }
     }
 }
@@ -66,6 +72,8 @@ pub struct QueueManager {
     pub team_manager: SystemCommandQueue<TeamManagerCommand>,
     pub team_assignment: SystemCommandQueue<TeamAssignmentCommand>,
     pub thought_manager: SystemCommandQueue<ThoughtCommand>,
+    pub offer_manager: SystemCommandQueue<OfferCommand>,
+    pub calendar_manager: SystemCommandQueue<CalendarCommand>,
 }
 
 impl QueueManager {
@@ -76,6 +84,8 @@ impl QueueManager {
         while self.game_speed_manager.queue.pop().is_some() {}
         while self.sim_manager.queue.pop().is_some() {}
         while self.thought_manager.queue.pop().is_some() {}
+        while self.offer_manager.queue.pop().is_some() {}
+        while self.calendar_manager.queue.pop().is_some() {}
     }
     pub fn print_summary(&self) {
         info!("{}", self.get_summary_string());
@@ -91,6 +101,8 @@ impl QueueManager {
             team_manager: SystemCommandQueue::<TeamManagerCommand>::new(),
             team_assignment: SystemCommandQueue::<TeamAssignmentCommand>::new(),
             thought_manager: SystemCommandQueue::<ThoughtCommand>::new(),
+            offer_manager: SystemCommandQueue::<OfferCommand>::new(),
+            calendar_manager: SystemCommandQueue::<CalendarCommand>::new(),
         }
     }
 
@@ -124,6 +136,8 @@ impl QueueManager {
                     SimCommand::TeamManager(cmd) => self.team_manager.queue.push(cmd),
                     SimCommand::TeamAssignment(cmd) => self.team_assignment.queue.push(cmd),
                     SimCommand::Thought(cmd) => self.thought_manager.queue.push(cmd),
+                    SimCommand::Offer(cmd) => self.offer_manager.queue.push(cmd),
+                    SimCommand::Calendar(cmd) => self.calendar_manager.queue.push(cmd),
                 }
             } else {
                 trace!("{} items dispatched", count);
@@ -154,10 +168,10 @@ impl QueueManager {
                 count += 1;
                 info!("Sim manager command: {:?}", command);
                 match command {
-                    SimManagerCommand::StartSim { company, employee, slot_id } => {
+                    SimManagerCommand::StartSim { company, employee, slot_id, seed } => {
                         self.new_game_manager
                             .queue
-                            .push(SimManagerCommand::StartSim { company, employee, slot_id });
+                            .push(SimManagerCommand::StartSim { company, employee, slot_id, seed });
                     }
 
                     SimManagerCommand::ResetSim { employee, company } => {
@@ -173,7 +187,9 @@ impl QueueManager {
                     SimManagerCommand::StopSim => {
                         self.sim_manager.queue.push(command);
                     }
-                    SimManagerCommand::ResumeSim => {
+                    SimManagerCommand::ResumeSim
+                    | SimManagerCommand::CreateCheckpoint { .. }
+                    | SimManagerCommand::SetAutosavePolicy(_) => {
                         self.sim_manager.queue.push(command);
                     }
                     SimManagerCommand::LoadSim { slot_id } => {
@@ -181,6 +197,9 @@ impl QueueManager {
                             .queue
                             .push(SimManagerCommand::LoadSim { slot_id });
                     }
+                    SimManagerCommand::RestoreCheckpoint { .. } => {
+                        self.new_game_manager.queue.push(command);
+                    }
                 }
             } else {
                 trace!("{} items dispatched", count);
@@ -1,11 +1,12 @@
 use crate::sim::company::company::Company;
+use crate::sim::company::finance::Finances;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
 pub struct CompanySnapshot {
     pub name: String,
     pub slogan: String,
-
+    pub finances: FinancesSnapshot,
 }
 
 impl From<&Company> for CompanySnapshot {
@@ -13,6 +14,7 @@ impl From<&Company> for CompanySnapshot {
         Self{
             name: company.name.clone(),
             slogan: company.slogan.clone() ,
+            finances: FinancesSnapshot::default(),
         }
     }
 }
@@ -22,6 +24,7 @@ impl From<Company> for CompanySnapshot {
         Self{
             name: company.name.clone(),
             slogan: company.slogan.clone() ,
+            finances: FinancesSnapshot::default(),
         }
     }
 }
@@ -30,4 +33,34 @@ impl PartialEq<&Company> for CompanySnapshot {
     fn eq(&self, other: &&Company) -> bool {
         self.name == other.name && self.slogan == other.slogan
     }
-}
\ No newline at end of file
+}
+
+/// Balance and runway of the company, kept up to date separately from the company details.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FinancesSnapshot {
+    pub balance: i64,
+    pub weekly_payroll: i64,
+    pub weekly_recurring_costs: i64,
+    pub weekly_burn: i64,
+    pub runway_days: Option<u32>,
+    pub is_bankrupt: bool,
+}
+
+impl From<&Finances> for FinancesSnapshot {
+    fn from(finances: &Finances) -> Self {
+        Self {
+            balance: finances.cash,
+            weekly_payroll: finances.weekly_payroll,
+            weekly_recurring_costs: finances.weekly_recurring_costs(),
+            weekly_burn: finances.weekly_burn(),
+            runway_days: finances.runway_days(),
+            is_bankrupt: finances.is_bankrupt,
+        }
+    }
+}
+
+impl PartialEq<&Finances> for FinancesSnapshot {
+    fn eq(&self, other: &&Finances) -> bool {
+        self == &FinancesSnapshot::from(*other)
+    }
+}
@@ -0,0 +1,74 @@
+use crate::sim::project::customer::{Customer, ProjectOffer};
+use crate::sim::sim_date::sim_date::SimDate;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct CustomerSnapshot {
+    pub id: u32,
+    pub name: String,
+    pub industry: String,
+    pub reputation: u8,
+    pub budget: u32,
+    pub open_offers: usize,
+    pub active_projects: Vec<u32>,
+}
+
+impl From<&Customer> for CustomerSnapshot {
+    fn from(value: &Customer) -> Self {
+        Self {
+            id: value.id.0,
+            name: value.name.clone(),
+            industry: value.industry.to_string(),
+            reputation: value.reputation,
+            budget: value.budget,
+            open_offers: value.offers.len(),
+            active_projects: value.projects.iter().map(|project| project.project_id.0).collect(),
+        }
+    }
+}
+
+impl PartialEq<&Customer> for CustomerSnapshot {
+    fn eq(&self, other: &&Customer) -> bool {
+        self == &CustomerSnapshot::from(*other)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct OfferSnapshot {
+    pub id: u32,
+    pub customer_id: u32,
+    pub customer_name: String,
+    pub industry: String,
+    pub customer_reputation: u8,
+    pub project_name: String,
+    pub requirements: Vec<String>,
+    pub total_work: f32,
+    pub payout: u32,
+    pub duration_days: u32,
+    pub posted_at: SimDate,
+    pub expires_at: SimDate,
+}
+
+impl OfferSnapshot {
+    pub fn new(offer: &ProjectOffer, customer: &Customer) -> Self {
+        Self {
+            id: offer.id.0,
+            customer_id: customer.id.0,
+            customer_name: customer.name.clone(),
+            industry: customer.industry.to_string(),
+            customer_reputation: customer.reputation,
+            project_name: offer.project.name.clone(),
+            requirements: offer
+                .project
+                .requirements
+                .iter()
+                .map(|requirement| requirement.description.clone())
+                .collect(),
+            total_work: offer.total_work(),
+            payout: offer.payout,
+            duration_days: offer.duration_days,
+            posted_at: offer.posted_at,
+            expires_at: offer.expires_at,
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use crate::sim::calendar::scheduler::MeetingSlot;
+use crate::sim::sim_date::sim_date::SimDate;
+use serde::{Deserialize, Serialize};
+
+/// Result of the last meeting scheduling request.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct MeetingSlotsSnapshot {
+    pub title: String,
+    pub slots: Vec<MeetingSlotSnapshot>,
+    /// Id of the booked event, `None` when the request was only a preview or no slot was found
+    pub booked_event_id: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct MeetingSlotSnapshot {
+    pub start: SimDate,
+    pub available_optional: Vec<u32>,
+    pub bumped_event_ids: Vec<u64>,
+    pub score: i32,
+}
+
+impl From<&MeetingSlot> for MeetingSlotSnapshot {
+    fn from(value: &MeetingSlot) -> Self {
+        Self {
+            start: value.start,
+            available_optional: value.available_optional.iter().map(|person| person.0).collect(),
+            bumped_event_ids: value.bumped.iter().map(|event_id| event_id.0).collect(),
+            score: value.score,
+        }
+    }
+}
@@ -14,3 +14,6 @@ pub mod stress;
 pub mod stress_history;
 pub mod working_hours;
 pub mod thoughts;
+pub mod project;
+pub mod customer;
+pub mod meeting;
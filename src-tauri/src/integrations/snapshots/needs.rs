@@ -1,7 +1,7 @@
 use crate::sim::person::needs::{Energy, Hunger};
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
 pub struct EnergySnapshot{
     pub level: u8,
     pub description: String,
@@ -20,7 +20,7 @@ impl PartialEq<&Energy> for EnergySnapshot{
     }
 }
 
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
 pub struct HungerSnapshot{
     pub level: u8,
     pub description: String,
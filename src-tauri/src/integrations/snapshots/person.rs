@@ -1,7 +1,7 @@
 use crate::integrations::snapshots::needs::{EnergySnapshot, HungerSnapshot};
 use crate::integrations::snapshots::personality::PersonalitySnapshot;
 use crate::integrations::snapshots::profile_picture::ProfilePictureSnapshot;
-use crate::integrations::snapshots::skills::SkillSetSnapshot;
+use crate::integrations::snapshots::skills::{SkillGrowthSnapshot, SkillSetSnapshot};
 use crate::integrations::snapshots::stats::StatsSnapshot;
 use crate::sim::person::components::{Person, ProfilePicture};
 use crate::sim::person::personality_matrix::PersonalityMatrix;
@@ -11,7 +11,7 @@ use crate::sim::person::stats::Stats;
 use crate::sim::sim_date::sim_date::SimDate;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PersonSnapshot {
     pub(crate) stats: StatsSnapshot,
     pub(crate) profile_picture: ProfilePictureSnapshot,
@@ -20,6 +20,7 @@ pub struct PersonSnapshot {
     pub(crate) gender: String,
     pub(crate) personality: PersonalitySnapshot,
     pub(crate) assigned_skill: SkillSetSnapshot,
+    pub skill_history: Vec<SkillGrowthSnapshot>,
     pub talent_grade: TalentGrade,
     pub team: Option<u32>,
     /// The tick number this snapshot was last updated
@@ -58,6 +59,7 @@ impl
             profile_picture: ProfilePictureSnapshot::from(picture),
             personality: PersonalitySnapshot::from(personality),
             assigned_skill: SkillSetSnapshot::from(skillset),
+            skill_history: vec![],
             updated: current_tick,
             joined_tick: person.joined,
             team: person.team.map(|id| id.0),
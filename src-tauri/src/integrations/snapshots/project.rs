@@ -0,0 +1,70 @@
+use crate::sim::project::project::Project;
+use crate::sim::project::task::Task;
+use crate::sim::sim_date::sim_date::SimDate;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ProjectSnapshot {
+    pub id: u32,
+    pub name: String,
+    pub status: String,
+    pub progress: f32,
+    pub deadline: Option<SimDate>,
+    pub requirement_ids: Vec<u32>,
+    pub functionality_ids: Vec<u32>,
+}
+
+impl From<&Project> for ProjectSnapshot {
+    fn from(value: &Project) -> Self {
+        Self {
+            id: value.id.0,
+            name: value.name.clone(),
+            status: value.status.to_string(),
+            progress: value.progress,
+            deadline: value.deadline,
+            requirement_ids: value.requirements.iter().map(|id| id.0).collect(),
+            functionality_ids: value.functionalities.iter().map(|id| id.0).collect(),
+        }
+    }
+}
+
+impl PartialEq<&Project> for ProjectSnapshot {
+    fn eq(&self, other: &&Project) -> bool {
+        self == &ProjectSnapshot::from(*other)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct TaskSnapshot {
+    pub id: u32,
+    pub project_id: u32,
+    pub description: String,
+    pub assigned_to: Option<u32>,
+    pub total_work: f32,
+    pub remaining_work: f32,
+    pub progress: f32,
+    pub contribution_weight: f32,
+    pub required_skills: Vec<String>,
+}
+
+impl From<&Task> for TaskSnapshot {
+    fn from(value: &Task) -> Self {
+        Self {
+            id: value.id.0,
+            project_id: value.project_id.0,
+            description: value.description.clone(),
+            assigned_to: value.assigned_to.map(|person_id| person_id.0),
+            total_work: value.total_work,
+            remaining_work: value.remaining_work,
+            progress: value.progress(),
+            contribution_weight: value.contribution_weight,
+            required_skills: value.required_skills.iter().map(|skill| skill.0.clone()).collect(),
+        }
+    }
+}
+
+impl PartialEq<&Task> for TaskSnapshot {
+    fn eq(&self, other: &&Task) -> bool {
+        self == &TaskSnapshot::from(*other)
+    }
+}
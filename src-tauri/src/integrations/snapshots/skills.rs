@@ -1,5 +1,9 @@
+use crate::master_data::skill_graph::SkillGraph;
 use crate::master_data::skills::GLOBAL_SKILLS;
-use crate::sim::person::skills::{GlobalSkill, SkillSet};
+use crate::sim::person::skill_progression::{SkillGrowthRecord, SkillProgress};
+use crate::sim::person::skills::{GlobalSkill, SkillId, SkillSet};
+use std::collections::HashMap;
+use crate::sim::sim_date::sim_date::SimDate;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
@@ -54,6 +58,36 @@ impl From<&SkillSet> for SkillSetSnapshot {
     }
 }
 
+/// A skill going up or down a point, for the skill history in the UI.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillGrowthSnapshot {
+    pub date: SimDate,
+    pub skill_id: String,
+    pub skill_name: String,
+    pub source: String,
+    pub value: u32,
+}
+
+impl From<&SkillGrowthRecord> for SkillGrowthSnapshot {
+    fn from(value: &SkillGrowthRecord) -> Self {
+        let skill_name = GLOBAL_SKILLS
+            .get()
+            .and_then(|skills| skills.get(&value.skill))
+            .map_or_else(|| value.skill.0.clone(), |skill| skill.name.clone());
+        Self {
+            date: value.date,
+            skill_id: value.skill.0.clone(),
+            skill_name,
+            source: format!("{:?}", value.source),
+            value: value.value,
+        }
+    }
+}
+
+/// Skill history of an employee, most recent change last.
+pub fn skill_history_snapshot(progress: &SkillProgress) -> Vec<SkillGrowthSnapshot> {
+    progress.history.iter().map(SkillGrowthSnapshot::from).collect()
+}
 
 pub struct GlobalSkillSnapshot {
     pub id: String,
@@ -78,4 +112,43 @@ impl From<&GlobalSkill> for GlobalSkillSnapshot {
                 .join(","),
         }
     }
-}
\ No newline at end of file
+}
+/// A skill in the skill tree, with its links to other skills.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillTreeNodeSnapshot {
+    pub id: String,
+    pub name: String,
+    pub tier: String,
+    pub domain: String,
+    pub prerequisites: Vec<String>,
+    pub unlocks: Vec<String>,
+    pub feedback: Vec<String>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillTreeSnapshot {
+    pub skills: Vec<SkillTreeNodeSnapshot>,
+}
+
+impl From<(&HashMap<SkillId, GlobalSkill>, &SkillGraph)> for SkillTreeSnapshot {
+    fn from((global_skills, graph): (&HashMap<SkillId, GlobalSkill>, &SkillGraph)) -> Self {
+        let ids = |skills: &[SkillId]| skills.iter().map(|skill| skill.0.clone()).collect::<Vec<_>>();
+        let mut skills: Vec<SkillTreeNodeSnapshot> = global_skills
+            .values()
+            .map(|skill| {
+                let GlobalSkillSnapshot { id, name, tier, domain, .. } = GlobalSkillSnapshot::from(skill);
+                SkillTreeNodeSnapshot {
+                    id,
+                    name,
+                    tier,
+                    domain,
+                    prerequisites: ids(graph.prerequisites_of(&skill.id)),
+                    unlocks: ids(graph.unlocks(&skill.id)),
+                    feedback: skill.feedback.iter().map(|link| link.target.0.clone()).collect(),
+                }
+            })
+            .collect();
+        skills.sort_by(|a, b| a.id.cmp(&b.id));
+        Self { skills }
+    }
+}
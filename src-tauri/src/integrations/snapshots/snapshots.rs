@@ -1,7 +1,10 @@
 use crate::integrations::snapshots::company::CompanySnapshot;
 use crate::integrations::snapshots::debug_display::DebugDisplayEntrySnapshot;
 use crate::integrations::snapshots::game_speed::GameSpeedSnapshot;
+use crate::integrations::snapshots::meeting::MeetingSlotsSnapshot;
 use crate::integrations::snapshots::person::PersonSnapshot;
+use crate::integrations::snapshots::project::{ProjectSnapshot, TaskSnapshot};
+use crate::integrations::snapshots::customer::{CustomerSnapshot, OfferSnapshot};
 use crate::integrations::snapshots::stress::StressSnapshot;
 use crate::integrations::snapshots::stress_history::StressHistorySnapshot;
 use crate::integrations::snapshots::team::TeamSnapshot;
@@ -16,6 +19,9 @@ use std::hash::Hash;
 use std::sync::Arc;
 use crate::sim::person::components::PersonId;
 use crate::sim::team::components::TeamId;
+use crate::sim::project::project::ProjectId;
+use crate::sim::project::task::TaskId;
+use crate::sim::project::customer::{CustomerId, OfferId};
 
 /// this is tha main integration state
 #[derive(Debug)]
@@ -30,6 +36,11 @@ pub struct SnapshotState {
     pub stress_history: Arc<DashMap<PersonId, StressHistorySnapshot>>,
     pub working_hours: Arc<DashMap<PersonId, WorkingHoursSnapshot>>,
     pub thoughts: Arc<DashMap<PersonId, ThoughtsSnapshot>>,
+    pub projects: Arc<DashMap<ProjectId, ProjectSnapshot>>,
+    pub tasks: Arc<DashMap<TaskId, TaskSnapshot>>,
+    pub customers: Arc<DashMap<CustomerId, CustomerSnapshot>>,
+    pub offers: Arc<DashMap<OfferId, OfferSnapshot>>,
+    pub meeting_slots: Arc<SnapshotField<MeetingSlotsSnapshot>>,
 
 }
 
@@ -42,6 +53,10 @@ impl SnapshotState {
         self.stress_history.clear();
         self.working_hours.clear();
         self.thoughts.clear();
+        self.projects.clear();
+        self.tasks.clear();
+        self.customers.clear();
+        self.offers.clear();
     }
 }
 
@@ -58,6 +73,11 @@ impl Default for SnapshotState {
             stress_history: Arc::new(DashMap::<PersonId, StressHistorySnapshot>::new()),
             working_hours: Arc::new(DashMap::<PersonId, WorkingHoursSnapshot>::new()),
             thoughts: Arc::new(DashMap::<PersonId, ThoughtsSnapshot>::new()),
+            projects: Arc::new(DashMap::<ProjectId, ProjectSnapshot>::new()),
+            tasks: Arc::new(DashMap::<TaskId, TaskSnapshot>::new()),
+            customers: Arc::new(DashMap::<CustomerId, CustomerSnapshot>::new()),
+            offers: Arc::new(DashMap::<OfferId, OfferSnapshot>::new()),
+            meeting_slots: Arc::new(SnapshotField::from(MeetingSlotsSnapshot::default())),
         }
     }
 }
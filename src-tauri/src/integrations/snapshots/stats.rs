@@ -1,7 +1,7 @@
 use crate::sim::person::stats::{StatType, Stats};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
 pub struct StatsSnapshot {
     // Cognition
     pub judgement: u16,
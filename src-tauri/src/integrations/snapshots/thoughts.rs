@@ -1,7 +1,7 @@
 use crate::sim::person::thoughts::Thought;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ThoughtsSnapshot {
     pub person_id: u32,
     pub thoughts: Vec<Thought>,
@@ -0,0 +1,255 @@
+use crate::integrations::snapshots_emitter::sink::{send_to, SnapshotSink};
+use crate::integrations::snapshots_emitter::snapshots_emitter::{ExportFrequency, SnapshotEmitter, SnapshotEmitterConfig};
+use dashmap::DashMap;
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use tracing::{error, trace};
+
+/// Changes to a collection since the previous message for the same event.
+///
+/// `seq` goes up by one with every message, a frontend that sees a gap has missed a delta and calls
+/// `refresh_data`. A `full` message carries the whole collection in `added` and replaces what the frontend has.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SnapshotDelta {
+    pub seq: u64,
+    pub full: bool,
+    /// `[key, snapshot]` pairs
+    pub added: Vec<(Value, Value)>,
+    /// `[key, changed fields]` pairs
+    pub updated: Vec<(Value, Map<String, Value>)>,
+    pub removed: Vec<Value>,
+}
+
+impl SnapshotDelta {
+    fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty()
+    }
+}
+
+struct DeltaState<K, V> {
+    seq: u64,
+    last_full_tick: u64,
+    /// What the frontend has, the snapshot for cheap comparison and its serialized form for field diffs
+    sent: HashMap<K, (V, Value)>,
+}
+
+/// Emits only what changed in a collection since the last emit, entity by entity, with a full resync every
+/// `resync_every` ticks and whenever the UI asks for one.
+pub struct SnapshotDeltaEmitter<K, V>
+where
+    K: Eq + Hash + Clone + Serialize,
+    V: Serialize + Clone + PartialEq,
+{
+    pub map: Arc<DashMap<K, V>>,
+    pub config: SnapshotEmitterConfig,
+    pub resync_every: u64,
+    state: Mutex<DeltaState<K, V>>,
+}
+
+impl<K, V> SnapshotDeltaEmitter<K, V>
+where
+    K: Eq + Hash + Clone + Serialize,
+    V: Serialize + Clone + PartialEq,
+{
+    pub fn new(map: Arc<DashMap<K, V>>, config: SnapshotEmitterConfig, resync_every: u64) -> Self {
+        Self {
+            map,
+            config,
+            resync_every,
+            state: Mutex::new(DeltaState { seq: 0, last_full_tick: 0, sent: HashMap::new() }),
+        }
+    }
+
+    /// Compares the collection with what was sent and records the new state as sent.
+    fn diff(&self, state: &mut DeltaState<K, V>, full: bool) -> Result<SnapshotDelta, serde_json::Error> {
+        let mut delta = SnapshotDelta { seq: state.seq + 1, full, added: Vec::new(), updated: Vec::new(), removed: Vec::new() };
+        if full {
+            state.sent.clear();
+        }
+
+        let mut present = HashSet::with_capacity(self.map.len());
+        for entry in self.map.iter() {
+            let (key, snapshot) = (entry.key(), entry.value());
+            present.insert(key.clone());
+            match state.sent.get_mut(key) {
+                Some((sent, _)) if sent == snapshot => {}
+                Some((sent, sent_value)) => {
+                    let value = serde_json::to_value(snapshot)?;
+                    let fields = changed_fields(sent_value, &value);
+                    if !fields.is_empty() {
+                        delta.updated.push((serde_json::to_value(key)?, fields));
+                    }
+                    *sent = snapshot.clone();
+                    *sent_value = value;
+                }
+                None => {
+                    let value = serde_json::to_value(snapshot)?;
+                    delta.added.push((serde_json::to_value(key)?, value.clone()));
+                    state.sent.insert(key.clone(), (snapshot.clone(), value));
+                }
+            }
+        }
+
+        let removed: Vec<K> = state.sent.keys().filter(|key| !present.contains(*key)).cloned().collect();
+        for key in removed {
+            state.sent.remove(&key);
+            delta.removed.push(serde_json::to_value(&key)?);
+        }
+        Ok(delta)
+    }
+
+    fn send(&self, tick: Option<u64>, full: bool, sink: &dyn SnapshotSink) {
+        let mut state = self.state.lock();
+        let full = full || state.seq == 0;
+        let delta = match self.diff(&mut state, full) {
+            Ok(delta) => delta,
+            Err(err) => {
+                error!("Failed to diff '{}': {}", self.config.event_name, err);
+                return;
+            }
+        };
+        if let Some(tick) = tick {
+            self.config.last_sent_tick.store(tick, Ordering::Relaxed);
+            if full {
+                state.last_full_tick = tick;
+            }
+        }
+        if !full && delta.is_empty() {
+            return;
+        }
+        trace!("Event name {} emitting seq {} (full: {})", self.config.event_name, delta.seq, full);
+        state.seq = delta.seq;
+        send_to(sink, self.config.event_name.as_str(), &delta);
+    }
+}
+
+/// Top level fields that differ, with their new value. Fields are never dropped from a snapshot type,
+/// so only the new object's fields need checking.
+fn changed_fields(old: &Value, new: &Value) -> Map<String, Value> {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => new
+            .iter()
+            .filter(|(field, value)| old.get(*field) != Some(*value))
+            .map(|(field, value)| (field.clone(), value.clone()))
+            .collect(),
+        // Not a struct, send the whole value under an empty field name
+        _ if old != new => Map::from_iter([(String::new(), new.clone())]),
+        _ => Map::new(),
+    }
+}
+
+impl<K, V> SnapshotEmitter for SnapshotDeltaEmitter<K, V>
+where
+    K: Eq + Hash + Clone + Serialize,
+    V: Serialize + Clone + PartialEq,
+{
+    fn maybe_emit(&self, tick: u64, last_update_map: &DashMap<&'static str, u64>, sink: &dyn SnapshotSink) -> bool {
+        let should_emit = match self.config.frequency {
+            ExportFrequency::EveryTick => true,
+            ExportFrequency::EveryNTicks(n) => tick.is_multiple_of(n),
+            ExportFrequency::ManualOnly => false,
+        };
+        // Unchanged entities cost a comparison, so without an update mark the collection is still checked
+        if should_emit && self.config.last_sent_tick.load(Ordering::Relaxed) != tick {
+            let resync = self.resync_every > 0 && tick >= self.state.lock().last_full_tick + self.resync_every;
+            self.send(Some(tick), resync, sink);
+        }
+        should_emit
+    }
+
+    fn emit(&self, tick: Option<u64>, sink: &dyn SnapshotSink) {
+        self.send(tick, true, sink);
+    }
+
+    fn reset(&self) {
+        self.config.last_sent_tick.store(0, Ordering::Relaxed);
+        let mut state = self.state.lock();
+        state.seq = 0;
+        state.last_full_tick = 0;
+        state.sent.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integrations::snapshots_emitter::sink::MemorySink;
+    use crate::integrations::snapshots_emitter::snapshots_emitter::SnapshotEvent;
+    use serde_json::json;
+    use std::sync::atomic::AtomicU64;
+
+    #[derive(Debug, Clone, PartialEq, Serialize)]
+    struct Row {
+        name: &'static str,
+        stress: u32,
+    }
+
+    fn emitter(map: &Arc<DashMap<u32, Row>>, resync_every: u64) -> SnapshotDeltaEmitter<u32, Row> {
+        let config = SnapshotEmitterConfig {
+            frequency: ExportFrequency::EveryTick,
+            event_name: SnapshotEvent::Persons,
+            last_sent_tick: AtomicU64::new(0),
+        };
+        SnapshotDeltaEmitter::new(Arc::clone(map), config, resync_every)
+    }
+
+    fn last_delta(sink: &MemorySink) -> Value {
+        sink.last(SnapshotEvent::Persons.as_str()).unwrap()
+    }
+
+    #[test]
+    fn sends_added_changed_fields_and_removed() {
+        let map = Arc::new(DashMap::from_iter([(1, Row { name: "Ann", stress: 0 }), (2, Row { name: "Bob", stress: 0 })]));
+        let emitter = emitter(&map, 0);
+        let sink = MemorySink::default();
+        let last_update = DashMap::new();
+
+        emitter.maybe_emit(1, &last_update, &sink);
+        let first = last_delta(&sink);
+        assert_eq!(first["seq"], 1);
+        assert_eq!(first["full"], true);
+        assert_eq!(first["added"].as_array().unwrap().len(), 2);
+
+        // Nothing changed, nothing sent
+        emitter.maybe_emit(2, &last_update, &sink);
+        assert_eq!(sink.events().len(), 1);
+
+        map.get_mut(&1).unwrap().stress = 5;
+        map.remove(&2);
+        map.insert(3, Row { name: "Cid", stress: 1 });
+        emitter.maybe_emit(3, &last_update, &sink);
+        assert_eq!(
+            last_delta(&sink),
+            json!({
+                "seq": 2,
+                "full": false,
+                "added": [[3, {"name": "Cid", "stress": 1}]],
+                "updated": [[1, {"stress": 5}]],
+                "removed": [2],
+            })
+        );
+    }
+
+    #[test]
+    fn resyncs_periodically_and_on_request() {
+        let map = Arc::new(DashMap::from_iter([(1, Row { name: "Ann", stress: 0 })]));
+        let emitter = emitter(&map, 10);
+        let sink = MemorySink::default();
+        let last_update = DashMap::new();
+
+        emitter.maybe_emit(1, &last_update, &sink);
+        emitter.maybe_emit(11, &last_update, &sink);
+        let resync = last_delta(&sink);
+        assert_eq!((resync["seq"].clone(), resync["full"].clone()), (json!(2), json!(true)));
+        assert_eq!(resync["added"], json!([[1, {"name": "Ann", "stress": 0}]]));
+
+        emitter.emit(None, &sink);
+        assert_eq!(last_delta(&sink)["seq"], 3);
+        assert_eq!(last_delta(&sink)["full"], true);
+    }
+}
@@ -1 +1,3 @@
 pub mod snapshots_emitter;
+pub mod sink;
+pub mod delta;
@@ -0,0 +1,104 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use tauri::{AppHandle, Emitter};
+use tracing::error;
+
+/// Where snapshots and app events end up: the Tauri frontend, memory in tests, or a file other tools can read.
+pub trait SnapshotSink {
+    fn send(&self, event: &str, payload: Value);
+}
+
+/// Serializes the payload and hands it to the sink.
+pub fn send_to(sink: &dyn SnapshotSink, event: &str, payload: &impl Serialize) {
+    match serde_json::to_value(payload) {
+        Ok(payload) => sink.send(event, payload),
+        Err(err) => error!("Failed to serialize '{}': {}", event, err),
+    }
+}
+
+impl SnapshotSink for AppHandle {
+    fn send(&self, event: &str, payload: Value) {
+        if let Err(err) = self.emit(event, payload) {
+            error!("Failed to emit event '{}': {:?}", event, err);
+        }
+    }
+}
+
+/// Keeps everything it receives, so tests can assert on what would have reached the UI.
+#[derive(Debug, Default)]
+pub struct MemorySink {
+    events: Mutex<Vec<(String, Value)>>,
+}
+
+impl MemorySink {
+    pub fn events(&self) -> Vec<(String, Value)> {
+        self.events.lock().clone()
+    }
+
+    /// Latest payload sent for the event.
+    pub fn last(&self, event: &str) -> Option<Value> {
+        self.events.lock().iter().rev().find(|(name, _)| name == event).map(|(_, payload)| payload.clone())
+    }
+
+    pub fn clear(&self) {
+        self.events.lock().clear();
+    }
+}
+
+impl SnapshotSink for MemorySink {
+    fn send(&self, event: &str, payload: Value) {
+        self.events.lock().push((event.to_string(), payload));
+    }
+}
+
+/// Writes one `{"event": .., "payload": ..}` object per line.
+pub struct JsonLinesSink {
+    writer: Mutex<BufWriter<File>>,
+}
+
+impl JsonLinesSink {
+    pub fn create(path: &Path) -> std::io::Result<Self> {
+        Ok(Self { writer: Mutex::new(BufWriter::new(File::create(path)?)) })
+    }
+}
+
+impl SnapshotSink for JsonLinesSink {
+    fn send(&self, event: &str, payload: Value) {
+        let mut writer = self.writer.lock();
+        if let Err(err) = writeln!(writer, "{}", json!({ "event": event, "payload": payload })) {
+            error!("Failed to write event '{}': {}", event, err);
+        }
+    }
+}
+
+impl Drop for JsonLinesSink {
+    fn drop(&mut self) {
+        let _ = self.writer.get_mut().flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::BufRead;
+
+    #[test]
+    fn json_lines_sink_writes_one_event_per_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snapshots.jsonl");
+        {
+            let sink = JsonLinesSink::create(&path).unwrap();
+            send_to(&sink, "tick_snapshot", &1);
+            send_to(&sink, "tick_snapshot", &vec!["a", "b"]);
+        }
+        let lines: Vec<Value> = std::io::BufReader::new(File::open(&path).unwrap())
+            .lines()
+            .map(|line| serde_json::from_str(&line.unwrap()).unwrap())
+            .collect();
+        assert_eq!(lines, vec![json!({"event": "tick_snapshot", "payload": 1}), json!({"event": "tick_snapshot", "payload": ["a", "b"]})]);
+    }
+}
@@ -7,14 +7,14 @@ use serde::Serialize;
 use std::hash::Hash;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tauri::{AppHandle, Emitter};
+use crate::integrations::snapshots_emitter::sink::{send_to, SnapshotSink};
 use tracing::field::debug;
 use tracing::{debug, info, instrument, trace};
 use arc_swap::ArcSwap;
 
 pub trait SnapshotEmitter {
-    fn maybe_emit(&self, tick: u64, last_update_map:  &DashMap<&'static str, u64>, app: &AppHandle) -> bool;
-    fn emit(&self, tick: Option<u64>, app: &AppHandle);
+    fn maybe_emit(&self, tick: u64, last_update_map:  &DashMap<&'static str, u64>, sink: &dyn SnapshotSink) -> bool;
+    fn emit(&self, tick: Option<u64>, sink: &dyn SnapshotSink);
     
     fn reset(&self);
 }
@@ -51,9 +51,9 @@ impl SnapshotEmitRegistry {
     }
 
     #[instrument(skip_all, level = "trace")]
-    pub fn maybe_emit_all(&self, tick: u64, app: &AppHandle) {
+    pub fn maybe_emit_all(&self, tick: u64, sink: &dyn SnapshotSink) {
         for emitter in &self.emitters {
-            let _did_emit = emitter.maybe_emit(tick, &self.last_update_map, app);
+            let _did_emit = emitter.maybe_emit(tick, &self.last_update_map, sink);
         }
     }
 
@@ -68,9 +68,9 @@ impl SnapshotEmitRegistry {
         }
     }
     
-    pub fn force_emit_all(&self, app: &AppHandle) {
+    pub fn force_emit_all(&self, sink: &dyn SnapshotSink) {
         for emitter in &self.emitters {
-            let _did_emit = emitter.emit(None, app);
+            let _did_emit = emitter.emit(None, sink);
         }
     }
 
@@ -83,7 +83,9 @@ pub fn run_snapshot_emitters(
     #[resource] tick_counter: &Arc<TickCounter>,
 ) {
     let current_tick = tick_counter.value(); // However you expose tick as u64
-    registry.maybe_emit_all(current_tick, &app_context.app_handle);
+    if let Some(sink) = &app_context.sink {
+        registry.maybe_emit_all(current_tick, sink.as_ref());
+    }
 }
 
 #[derive(Debug)]
@@ -93,7 +95,7 @@ pub struct SnapshotFieldEmitter<T> {
 }
 
 impl<T: Serialize + std::fmt::Debug> SnapshotEmitter for SnapshotFieldEmitter<T> {
-    fn maybe_emit(&self, tick: u64, last_update_map:  &DashMap<&'static str, u64>, app: &AppHandle) -> bool {
+    fn maybe_emit(&self, tick: u64, last_update_map:  &DashMap<&'static str, u64>, sink: &dyn SnapshotSink) -> bool {
         let should_emit = match self.config.frequency {
             ExportFrequency::EveryTick => true,
             ExportFrequency::EveryNTicks(n) => tick % n == 0,
@@ -111,11 +113,11 @@ impl<T: Serialize + std::fmt::Debug> SnapshotEmitter for SnapshotFieldEmitter<T>
         let  last_sent = self.config.last_sent_tick.load(Ordering::Relaxed);
         debug!("Event name {}, always emit? {}. last update? {} {}", self.config.event_name, always_emit, last_update, last_sent);
         if should_emit && ( always_emit || last_sent <= last_update  ) {//use `<=` , some new game scenario will result in last sent == last lets send the update if last sent and last update is the same.
-            self.emit( Some(tick), app);
+            self.emit( Some(tick), sink);
         }
         should_emit
     }
-    fn emit(&self, tick: Option<u64>, app: &AppHandle) {
+    fn emit(&self, tick: Option<u64>, sink: &dyn SnapshotSink) {
         debug!("Event name {} emitting..", self.config.event_name );
         //&& self.config.last_sent_tick.load(Ordering::Relaxed) != tick {
         if let Some(tick) = tick {
@@ -124,7 +126,7 @@ impl<T: Serialize + std::fmt::Debug> SnapshotEmitter for SnapshotFieldEmitter<T>
         let data: &T = &*self.field.value.load();
         debug!("Data {:?} ", data );
         // info!("Snapshot field: {:?} {:?} {:?}", self.config.event_name, data,  &*self.field.value.load_full());
-        let _ = app.emit(self.config.event_name.into(), data);
+        send_to(sink, self.config.event_name.as_str(), data);
     }
 
     fn reset(&self) {
@@ -146,7 +148,7 @@ where
     K: Eq + Hash + Clone,
     V: Serialize + Clone,
 {
-    fn maybe_emit(&self, tick: u64, last_update_map:  &DashMap<&'static str, u64>, app: &AppHandle) -> bool {
+    fn maybe_emit(&self, tick: u64, last_update_map:  &DashMap<&'static str, u64>, sink: &dyn SnapshotSink) -> bool {
         let should_emit = match self.config.frequency {
             ExportFrequency::EveryTick => true,
             ExportFrequency::EveryNTicks(n) => tick % n == 0,
@@ -165,18 +167,18 @@ where
         let  last_sent = self.config.last_sent_tick.load(Ordering::Relaxed);
         trace!("Event name {}, always emit? {}. last update? {}  map:{:?}", self.config.event_name, always_emit, last_update, last_update_map);
         if should_emit  && ( always_emit || last_sent <= last_update  )  && last_sent!= tick {
-           self.emit(Some(tick), app);
+           self.emit(Some(tick), sink);
         }
         should_emit
     }
 
-    fn emit(&self, tick: Option<u64>, app: &AppHandle) {
+    fn emit(&self, tick: Option<u64>, sink: &dyn SnapshotSink) {
         trace!("Event name {} emitting..", self.config.event_name );
         if let Some(tick) = tick {
             self.config.last_sent_tick.store(tick, Ordering::Relaxed);
         }
         let all: Vec<V> = self.map.iter().map(|entry| entry.value().clone()).collect();
-        let _ = app.emit(self.config.event_name.into(), &all);
+        send_to(sink, self.config.event_name.as_str(), &all);
     }
 
     fn reset(&self) {
@@ -195,7 +197,12 @@ pub enum SnapshotEvent {
     StressHistory,
     MonthlyAvailability,
     Thoughts,
-    CalendarEvents
+    CalendarEvents,
+    Projects,
+    Tasks,
+    Customers,
+    Offers,
+    MeetingSlots,
 }
 
 impl SnapshotEvent {
@@ -210,7 +217,12 @@ impl SnapshotEvent {
             SnapshotEvent::StressHistory => "stress_history_snapshot",
             SnapshotEvent::MonthlyAvailability => "monthly_availability_snapshot",
             SnapshotEvent::Thoughts => "thoughts_snapshot",
-            SnapshotEvent::CalendarEvents => "calendar_events_snapshot"
+            SnapshotEvent::CalendarEvents => "calendar_events_snapshot",
+            SnapshotEvent::Projects => "projects_snapshot",
+            SnapshotEvent::Tasks => "tasks_snapshot",
+            SnapshotEvent::Customers => "customers_snapshot",
+            SnapshotEvent::Offers => "offers_snapshot",
+            SnapshotEvent::MeetingSlots => "meeting_slots_snapshot",
         }
     }
 }
@@ -269,4 +281,54 @@ where
     fn from(map: DashMap<K, V>) -> Self {
         Self { map }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integrations::snapshots_emitter::sink::MemorySink;
+    use serde_json::json;
+
+    #[test]
+    fn registry_sends_updated_snapshots_to_the_sink() {
+        let field = Arc::new(SnapshotField::from(1u32));
+        let mut registry = SnapshotEmitRegistry::new();
+        registry.register(SnapshotFieldEmitter {
+            field: Arc::clone(&field),
+            config: SnapshotEmitterConfig {
+                frequency: ExportFrequency::EveryTick,
+                event_name: SnapshotEvent::GameSpeed,
+                last_sent_tick: AtomicU64::new(0),
+            },
+        });
+        let sink = MemorySink::default();
+
+        registry.mark_data_updated(SnapshotEvent::GameSpeed, 1);
+        registry.maybe_emit_all(1, &sink);
+        assert_eq!(sink.last(SnapshotEvent::GameSpeed.as_str()), Some(json!(1)));
+
+        field.value.store(Arc::new(Arc::new(5)));
+        registry.mark_data_updated(SnapshotEvent::GameSpeed, 3);
+        registry.maybe_emit_all(3, &sink);
+        assert_eq!(sink.last(SnapshotEvent::GameSpeed.as_str()), Some(json!(5)));
+    }
+
+    #[test]
+    fn manual_snapshots_only_go_out_when_forced() {
+        let mut registry = SnapshotEmitRegistry::new();
+        registry.register(SnapshotCollectionEmitter {
+            map: Arc::new(DashMap::from_iter([(1u32, "Alice".to_string())])),
+            config: SnapshotEmitterConfig {
+                frequency: ExportFrequency::ManualOnly,
+                event_name: SnapshotEvent::Persons,
+                last_sent_tick: AtomicU64::new(0),
+            },
+        });
+        let sink = MemorySink::default();
+
+        registry.maybe_emit_all(1, &sink);
+        assert!(sink.events().is_empty());
+
+        registry.force_emit_all(&sink);
+        assert_eq!(sink.events(), vec![(SnapshotEvent::Persons.as_str().to_string(), json!(["Alice"]))]);
+    }
+}
@@ -1,29 +1,36 @@
 use crate::integrations::snapshots::company::CompanySnapshot;
 use crate::integrations::snapshots::debug_display::DebugDisplayEntrySnapshot;
 use crate::integrations::snapshots::person::PersonSnapshot;
-use crate::integrations::snapshots::skills::SkillSetSnapshot;
+use crate::integrations::snapshots::skills::{skill_history_snapshot, SkillSetSnapshot};
 use crate::integrations::snapshots::snapshots::SnapshotState;
 use crate::integrations::snapshots::stress::StressSnapshot;
 use crate::integrations::snapshots::stress_history::StressHistorySnapshot;
 use crate::integrations::snapshots::working_hours::WorkingHoursSnapshot;
 use crate::integrations::snapshots::thoughts::{ThoughtsSnapshot, DirtyThought};
 use crate::integrations::snapshots::team::TeamSnapshot;
+use crate::integrations::snapshots::project::{ProjectSnapshot, TaskSnapshot};
+use crate::integrations::snapshots::customer::{CustomerSnapshot, OfferSnapshot};
 use crate::integrations::snapshots_emitter::snapshots_emitter::{
     SnapshotEmitRegistry, SnapshotEvent, SnapshotFieldEmitter,
 };
 use crate::sim::action::action::ActionIntent;
 use crate::sim::company::company::{Company, PlayerControlled};
+use crate::sim::company::finance::Finances;
 use crate::sim::game_speed::components::GameSpeedManager;
 use crate::sim::person::components::{Person, PersonId, ProfilePicture};
 use crate::sim::person::morale::StressLevel;
 use crate::sim::person::needs::{Energy, Hunger};
 use crate::sim::person::personality_matrix::PersonalityMatrix;
+use crate::sim::person::skill_progression::SkillProgress;
 use crate::sim::person::skills::{SkillId, SkillSet};
 use crate::sim::person::thoughts::Thoughts;
 use crate::sim::person::spawner::spawn_person;
 use crate::sim::person::stats::Stats;
 use crate::sim::resources::global::{Dirty, TickCounter};
 use crate::sim::team::components::Team;
+use crate::sim::project::project::Project;
+use crate::sim::project::task::Task;
+use crate::sim::project::customer::Customer;
 use crate::sim::utils::debugging::DebugDisplayComponent;
 use crate::sim::utils::snapshots::replace_if_changed;
 use arc_swap::ArcSwap;
@@ -102,6 +109,8 @@ pub fn push_company_to_integration(
         replace_if_changed::<CompanySnapshot, Company>(&mut mutable_snapshot_data, &company);
 
     if changed {
+        // The finances are pushed by `push_finances_to_integration`, keep them.
+        mutable_snapshot_data.finances = current_arc_snapshot.finances.clone();
         // If `changed` is true, `mutable_snapshot_data` now holds the new, updated snapshot.
         // Store this new snapshot into the ArcSwap.
         // `ArcSwap::store` expects an `Arc<T>`, T in our case is always an Arc of the data. So it's another `Arc::new()`
@@ -117,6 +126,26 @@ pub fn push_company_to_integration(
     cmd.remove_component::<Dirty>(*entity);
 }
 
+#[system(for_each)]
+pub fn push_finances_to_integration(
+    #[resource] tick_counter: &Arc<TickCounter>,
+    #[resource] app_state: &Arc<SnapshotState>,
+    #[resource] emit_registry: &Arc<SnapshotEmitRegistry>,
+    finances: &Finances,
+    _player_controlled_tag: &PlayerControlled,
+) {
+    let current_arc_snapshot = app_state.company.value.load_full();
+    let mut mutable_snapshot_data = (**current_arc_snapshot).clone();
+
+    if replace_if_changed(&mut mutable_snapshot_data.finances, finances) {
+        app_state
+            .company
+            .value
+            .store(Arc::new(Arc::new(mutable_snapshot_data)));
+        emit_registry.mark_data_updated(SnapshotEvent::Company, tick_counter.value());
+    }
+}
+
 #[system(for_each)]
 pub fn tick_needs(energy: &mut Energy, hunger: &mut Hunger, stats: &Stats) {
     energy.tick(stats);
@@ -180,6 +209,7 @@ pub fn push_persons_to_integration(
     profile_picture: &ProfilePicture,
     personality: &PersonalityMatrix,
     skill_set: &SkillSet,
+    skill_progress: Option<&SkillProgress>,
     _dirty: &Dirty,
     cmd: &mut CommandBuffer,
 ) {
@@ -187,6 +217,7 @@ pub fn push_persons_to_integration(
     let registry = &app_state.persons;
 
     let skillset_snapshot = SkillSetSnapshot::from(skill_set);
+    let skill_history = skill_progress.map(skill_history_snapshot).unwrap_or_default();
     match registry.entry(person.person_id) {
         Entry::Occupied(mut existing) => {
             let existing_person = existing.get_mut();
@@ -209,6 +240,10 @@ pub fn push_persons_to_integration(
             changed |= replace_if_changed(&mut existing_person.stats, stats);
             changed |= replace_if_changed(&mut existing_person.profile_picture, profile_picture);
             changed |= replace_if_changed(&mut existing_person.assigned_skill, &skillset_snapshot);
+            if existing_person.skill_history != skill_history {
+                changed = true;
+                existing_person.skill_history = skill_history;
+            }
             if changed {
                 existing_person.updated = current_tick;
             }
@@ -222,7 +257,7 @@ pub fn push_persons_to_integration(
                 skill_set,
                 current_tick,
             ));
-            vacant.insert(person);
+            vacant.insert(PersonSnapshot { skill_history, ..person });
         }
     };
     cmd.remove_component::<Dirty>(*entity);
@@ -448,3 +483,79 @@ pub fn push_thoughts_to_integration(
     emit_registry.mark_data_updated(SnapshotEvent::Thoughts, current_tick);
     cmd.remove_component::<DirtyThought>(*entity);
 }
+
+#[system(for_each)]
+pub fn push_projects_to_integration(
+    #[resource] tick_counter: &Arc<TickCounter>,
+    #[resource] app_state: &Arc<SnapshotState>,
+    #[resource] emit_registry: &Arc<SnapshotEmitRegistry>,
+    project: &Project,
+) {
+    let changed = match app_state.projects.entry(project.id) {
+        Entry::Occupied(mut occ) => replace_if_changed(occ.get_mut(), project),
+        Entry::Vacant(v) => {
+            v.insert(ProjectSnapshot::from(project));
+            true
+        }
+    };
+
+    if changed {
+        emit_registry.mark_data_updated(SnapshotEvent::Projects, tick_counter.value());
+    }
+}
+
+#[system(for_each)]
+pub fn push_tasks_to_integration(
+    #[resource] tick_counter: &Arc<TickCounter>,
+    #[resource] app_state: &Arc<SnapshotState>,
+    #[resource] emit_registry: &Arc<SnapshotEmitRegistry>,
+    task: &Task,
+) {
+    let changed = match app_state.tasks.entry(task.id) {
+        Entry::Occupied(mut occ) => replace_if_changed(occ.get_mut(), task),
+        Entry::Vacant(v) => {
+            v.insert(TaskSnapshot::from(task));
+            true
+        }
+    };
+
+    if changed {
+        emit_registry.mark_data_updated(SnapshotEvent::Tasks, tick_counter.value());
+    }
+}
+
+// Customers carry their open offers, so the offer snapshots are refreshed together with the customer's.
+#[system(for_each)]
+pub fn push_customers_to_integration(
+    #[resource] tick_counter: &Arc<TickCounter>,
+    #[resource] app_state: &Arc<SnapshotState>,
+    #[resource] emit_registry: &Arc<SnapshotEmitRegistry>,
+    entity: &Entity,
+    customer: &Customer,
+    _dirty: &Dirty,
+    cmd: &mut CommandBuffer,
+) {
+    let current_tick = tick_counter.value();
+
+    let changed = match app_state.customers.entry(customer.id) {
+        Entry::Occupied(mut occ) => replace_if_changed(occ.get_mut(), customer),
+        Entry::Vacant(v) => {
+            v.insert(CustomerSnapshot::from(customer));
+            true
+        }
+    };
+    if changed {
+        emit_registry.mark_data_updated(SnapshotEvent::Customers, current_tick);
+    }
+
+    app_state
+        .offers
+        .retain(|_, offer| offer.customer_id != customer.id.0);
+    for offer in &customer.offers {
+        app_state
+            .offers
+            .insert(offer.id, OfferSnapshot::new(offer, customer));
+    }
+    emit_registry.mark_data_updated(SnapshotEvent::Offers, current_tick);
+    cmd.remove_component::<Dirty>(*entity);
+}
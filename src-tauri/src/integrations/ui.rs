@@ -2,20 +2,59 @@ use crate::action_queues::sim_manager::SimManager;
 use dashmap::DashMap;
 use std::sync::Arc;
 
+use crate::action_queues::calendar_manager::CalendarCommand;
+use crate::action_queues::offer_manager::OfferCommand;
 use crate::action_queues::sim_manager::SimManagerCommand;
 use crate::action_queues::team_manager::{TeamAssignmentCommand, TeamManagerCommand};
-use crate::db::init::{create_new_save_slot, scan_save_slots, SaveSlot, SavesDirectory};
+use crate::db::archive;
+use crate::db::checkpoints::{AutosavePolicy, CheckpointInfo};
+use crate::db::init::{create_new_save_slot, sanitize_foldername, scan_save_slots, SaveSlot, SavesDirectory};
+use crate::integrations::events::{emit_app_event, AppEventType};
 use crate::integrations::queues::{ExposedQueue, SimCommand, UICommandQueues};
+use crate::utils::errors::SavesManagementError;
+use crate::integrations::snapshots::customer::OfferSnapshot;
+use crate::integrations::snapshots::meeting::MeetingSlotsSnapshot;
+use crate::integrations::snapshots::skills::SkillTreeSnapshot;
+use crate::master_data::data_pack::MASTER_DATA;
+use crate::master_data::skill_graph::SKILL_GRAPH;
+use crate::master_data::skills::GLOBAL_SKILLS;
+use crate::integrations::snapshots::snapshots::SnapshotState;
+use crate::integrations::snapshots_emitter::sink::SnapshotSink;
 use crate::integrations::snapshots_emitter::snapshots_emitter::SnapshotEmitRegistry;
+use crate::sim::calendar::scheduler::MeetingRequest;
 use crate::sim::game_speed::components::GameSpeed;
 use crate::sim::new_game::new_game::{CompanyPreset, CompanyPresetStatic, StartingEmployeesConfig};
 use tauri::{AppHandle, State};
-use tracing::info;
+use tracing::{debug, info};
 use tracing_subscriber::fmt::format;
 
+/// Where the simulation sends its snapshots and events. There is none when the simulation runs headless
+/// without a snapshot file.
 #[derive(Clone)]
 pub struct AppContext {
-    pub app_handle: AppHandle,
+    pub sink: Option<Arc<dyn SnapshotSink + Send + Sync>>,
+}
+
+impl AppContext {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self::with_sink(Arc::new(app_handle))
+    }
+
+    pub fn with_sink(sink: Arc<dyn SnapshotSink + Send + Sync>) -> Self {
+        Self { sink: Some(sink) }
+    }
+
+    pub fn headless() -> Self {
+        Self { sink: None }
+    }
+
+    /// Notifies the UI, events are only logged without a sink.
+    pub fn emit(&self, event_type: AppEventType) {
+        match &self.sink {
+            Some(sink) => emit_app_event(sink.as_ref(), event_type),
+            None => debug!("No sink, not emitting {:?}", event_type),
+        }
+    }
 }
 #[tauri::command]
 pub fn stop_sim(queues: State<'_, Arc<UICommandQueues>>) {
@@ -28,8 +67,8 @@ pub fn resume_sim(queues: State<'_, Arc<UICommandQueues>>) {
 }
 
 #[tauri::command]
-pub fn new_sim(queues: State<'_, Arc<UICommandQueues>>, company: CompanyPreset, employee:StartingEmployeesConfig, slot_id: String) {
-    queues.control.push(SimManagerCommand::StartSim{company, employee, slot_id});
+pub fn new_sim(queues: State<'_, Arc<UICommandQueues>>, company: CompanyPreset, employee:StartingEmployeesConfig, slot_id: String, seed: Option<u64>) {
+    queues.control.push(SimManagerCommand::StartSim{company, employee, slot_id, seed});
 }
 
 #[tauri::command]
@@ -47,6 +86,58 @@ pub fn unassign_team( person_id: u32, queues: State<'_, Arc<UICommandQueues>>) {
     queues.runtime.push(SimCommand::TeamAssignment(TeamAssignmentCommand::UnassignTeam {person_id }));
 }
 
+#[tauri::command]
+pub fn list_offers(snapshot_state: State<'_, Arc<SnapshotState>>) -> Vec<OfferSnapshot> {
+    let mut offers: Vec<OfferSnapshot> = snapshot_state
+        .offers
+        .iter()
+        .map(|entry| entry.value().clone())
+        .collect();
+    offers.sort_by_key(|offer| offer.id);
+    offers
+}
+
+#[tauri::command]
+pub fn accept_offer(offer_id: u32, queues: State<'_, Arc<UICommandQueues>>) {
+    queues.runtime.push(SimCommand::Offer(OfferCommand::AcceptOffer { offer_id }));
+}
+
+#[tauri::command]
+pub fn decline_offer(offer_id: u32, queues: State<'_, Arc<UICommandQueues>>) {
+    queues.runtime.push(SimCommand::Offer(OfferCommand::DeclineOffer { offer_id }));
+}
+
+/// Looks for meeting slots that work for the attendees' calendars and the company's working hours.
+/// With `book` set the best slot is booked. The ranked slots can be read with `list_meeting_slots`.
+#[tauri::command]
+pub fn schedule_meeting(
+    request: MeetingRequest,
+    team_id: Option<u32>,
+    book: bool,
+    queues: State<'_, Arc<UICommandQueues>>,
+) {
+    queues.runtime.push(SimCommand::Calendar(CalendarCommand::ScheduleMeeting { request, team_id, book }));
+}
+
+#[tauri::command]
+pub fn list_meeting_slots(snapshot_state: State<'_, Arc<SnapshotState>>) -> MeetingSlotsSnapshot {
+    (**snapshot_state.meeting_slots.value.load_full()).clone()
+}
+
+/// The skills and how they build on each other, for the skill tree.
+#[tauri::command]
+pub fn get_skill_tree() -> SkillTreeSnapshot {
+    match (GLOBAL_SKILLS.get(), SKILL_GRAPH.get()) {
+        (Some(global_skills), Some(skill_graph)) => SkillTreeSnapshot::from((global_skills, skill_graph)),
+        _ => SkillTreeSnapshot::default(),
+    }
+}
+
+/// Problems found in the data pack at startup, for the designers.
+#[tauri::command]
+pub fn get_data_pack_errors() -> Vec<String> {
+    MASTER_DATA.get().map(|data| data.errors.clone()).unwrap_or_default()
+}
 
 #[tauri::command]
 pub fn refresh_data(app: AppHandle, emit_registry: State<'_, Arc<SnapshotEmitRegistry>>) {
@@ -65,9 +156,88 @@ pub fn load_game(slot_id: String , queues: State<'_, Arc<UICommandQueues>> ){
     queues.control.push(SimManagerCommand::LoadSim { slot_id });
 }
 
+/// The slot id comes from the frontend, so only a plain folder name inside the saves directory is accepted.
+fn save_slot_at(saves_dir: &SavesDirectory, slot_id: &str) -> Result<SaveSlot, SavesManagementError> {
+    if sanitize_foldername(slot_id) != slot_id || slot_id.is_empty() || slot_id == "." || slot_id == ".." {
+        return Err(SavesManagementError::InvalidSlotId(slot_id.to_string()));
+    }
+    Ok(SaveSlot { slot_id: slot_id.to_string(), path: saves_dir.0.join(slot_id), ..SaveSlot::default() })
+}
+
+#[tauri::command]
+pub fn list_checkpoints(slot_id: String, saves_dir_state: State<'_, Arc<SavesDirectory>>) -> Result<Vec<CheckpointInfo>, String> {
+    save_slot_at(&saves_dir_state, &slot_id).and_then(|slot| slot.list_checkpoints()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_checkpoint(slot_id: String, checkpoint_id: String, saves_dir_state: State<'_, Arc<SavesDirectory>>) -> Result<(), String> {
+    save_slot_at(&saves_dir_state, &slot_id)
+        .and_then(|slot| slot.delete_checkpoint(&checkpoint_id))
+        .map_err(|e| e.to_string())
+}
+
+/// Checkpoints the running game under `name`.
+#[tauri::command]
+pub fn create_checkpoint(name: String, queues: State<'_, Arc<UICommandQueues>>) {
+    info!("Create checkpoint called. {}", &name);
+    queues.control.push(SimManagerCommand::CreateCheckpoint { name });
+}
+
+/// Rewrites the slot from the checkpoint and loads it, like `load_game`.
+#[tauri::command]
+pub fn restore_checkpoint(slot_id: String, checkpoint_id: String, queues: State<'_, Arc<UICommandQueues>>) {
+    info!("Restore checkpoint called. {} {}", &slot_id, &checkpoint_id);
+    queues.control.push(SimManagerCommand::RestoreCheckpoint { slot_id, checkpoint_id });
+}
+
+/// Writes a slot to a single archive file, for sharing a save.
+#[tauri::command]
+pub fn export_save_slot(
+    slot_id: String,
+    path: String,
+    saves_dir_state: State<'_, Arc<SavesDirectory>>,
+    sim_manager: State<'_, Arc<SimManager>>,
+) -> Result<(), String> {
+    let path = std::path::Path::new(&path);
+    // The running game holds the slot's database open
+    let exported = sim_manager
+        .with_save_slot(|slot| (slot.slot_id == slot_id && slot.handle.is_some()).then(|| slot.export_archive(path)))
+        .flatten()
+        .unwrap_or_else(|| archive::export_save_slot(&saves_dir_state, &slot_id, path));
+    exported.map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// Creates a new slot from an archive and returns its id.
+#[tauri::command]
+pub fn import_save_slot(path: String, saves_dir_state: State<'_, Arc<SavesDirectory>>) -> Result<String, String> {
+    archive::import_save_slot(&saves_dir_state, std::path::Path::new(&path)).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn set_autosave_policy(policy: AutosavePolicy, queues: State<'_, Arc<UICommandQueues>>) {
+    queues.control.push(SimManagerCommand::SetAutosavePolicy(policy));
+}
+
 #[tauri::command]
 pub async fn exit_app(app_handle: tauri::AppHandle) {
     app_handle.exit(0);
 }
 // In your main.rs or setup function:
-// .invoke_handler(tauri::generate_handler![list_save_slots])
\ No newline at end of file
+// .invoke_handler(tauri::generate_handler![list_save_slots])
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slot_ids_outside_the_saves_directory_are_rejected() {
+        let saves_dir = SavesDirectory(std::path::PathBuf::from("saves"));
+        for slot_id in ["../x", "..", ".", "", "a/b"] {
+            assert!(
+                matches!(save_slot_at(&saves_dir, slot_id), Err(SavesManagementError::InvalidSlotId(_))),
+                "{slot_id:?} was accepted"
+            );
+        }
+        assert_eq!(save_slot_at(&saves_dir, "my_company").unwrap().path, saves_dir.0.join("my_company"));
+    }
+}
@@ -9,6 +9,8 @@ mod action_queues;
 mod config;
 mod constants;
 mod db;
+mod headless;
+mod save_tool;
 mod integrations;
 mod macros;
 mod master_data;
@@ -32,11 +34,12 @@ use crate::integrations::systems::{
     push_game_speed_snapshots_system, push_needs_to_integration_system,
     push_persons_to_integration_system, push_teams_to_integration_system, tick_needs_system,
 };
-use crate::integrations::ui::{assign_person_to_team, exit_app, list_save_slots, load_game, new_sim, new_team, refresh_data, resume_sim, stop_sim, unassign_team, AppContext};
+use crate::integrations::ui::{accept_offer, assign_person_to_team, decline_offer, list_offers, list_meeting_slots, schedule_meeting, get_skill_tree, get_data_pack_errors, exit_app, list_save_slots, load_game, list_checkpoints, create_checkpoint, restore_checkpoint, delete_checkpoint, set_autosave_policy, export_save_slot, import_save_slot, new_sim, new_team, refresh_data, resume_sim, stop_sim, unassign_team, AppContext};
+use crate::master_data::data_pack::load_master_data;
 use crate::sim::game_speed::components::{GameSpeed, GameSpeedManager};
 use crate::sim::person::components::{PersonId, ProfilePicture};
-use crate::sim::person::init::{emit_done_setup_event_system, generate_employees_system, init_company_system, load_global_skills_to_static, unset_first_run_flag_system, FirstRun};
-use crate::sim::utils::logging::init_logging;
+use crate::sim::person::init::{emit_done_setup_event_system, generate_employees_system, init_company_system, unset_first_run_flag_system, FirstRun};
+use crate::sim::utils::logging::{init_logging, init_logging_with_default};
 use crossbeam::queue::SegQueue;
 use dashmap::{DashMap, DashSet};
 use spin_sleep::{SpinSleeper, SpinStrategy};
@@ -46,7 +49,7 @@ use std::time::{Duration, Instant};
 use std::{fmt, thread};
 
 use tauri::{Emitter, Manager};
-use tracing::{debug, info, trace};
+use tracing::{debug, error, info, trace};
 
 use crate::integrations::queues::{
     handle_dispatch_queue_system, handle_sim_manager_dispatch_queue_system, QueueManager,
@@ -93,13 +96,45 @@ fn print_startup_banner() {
 
 
 fn main() {
+    match save_tool::SaveToolCommand::from_args(std::env::args().skip(1)) {
+        Ok(Some(command)) => {
+            init_logging_with_default("warn");
+            if let Err(err) = command.run_to_output() {
+                eprintln!("Save tool failed: {err}");
+                std::process::exit(1);
+            }
+            return;
+        }
+        Ok(None) => {}
+        Err(err) => {
+            eprintln!("{err}");
+            std::process::exit(2);
+        }
+    }
+    match headless::HeadlessOptions::from_args(std::env::args().skip(1)) {
+        Ok(Some(options)) => {
+            init_logging_with_default("warn");
+            let result = headless::run_headless(&options).and_then(|report| report.write_to(&options.output));
+            if let Err(err) = result {
+                eprintln!("Headless run failed: {err}");
+                std::process::exit(1);
+            }
+            eprintln!("Metrics written to {}", options.output.display());
+            return;
+        }
+        Ok(None) => {}
+        Err(err) => {
+            eprintln!("{err}");
+            std::process::exit(2);
+        }
+    }
+
     init_logging();
     print_startup_banner();
     info!("Starting...");
 
     debug!("Debug log is {ENABLED}. Logs will be verbose. Use {log_settings} environment variable for normal operations.",ENABLED= red(&bold("ENABLED")), log_settings= green(&italic("RUST_LOG=info")));
     
-    load_global_skills_to_static();
     let snapshot_state = SnapshotState::default();
     let ui_snapshot_state = Arc::new(snapshot_state);
     let sim_snapshot_state = Arc::clone(&ui_snapshot_state); // Clone for ECS thread
@@ -142,6 +177,11 @@ fn main() {
             info!("Tauri setup initiated.");
             let app_handle = app.handle().clone();
             let assets_path = app.path().resolve("assets", BaseDirectory::Resource)?;
+            let mods_path = app.path().resolve("mods", BaseDirectory::AppData)?;
+            if let Err(e) = load_master_data(&assets_path, &mods_path) {
+                error!("Invalid skill master data: {}", e);
+                panic!("Invalid skill master data: {}", e);
+            }
 
             // Resolve the saves directory path
             let saves_dir_path = app
@@ -203,6 +243,20 @@ fn main() {
             list_save_slots,
             exit_app,
             load_game,
+            list_checkpoints,
+            create_checkpoint,
+            restore_checkpoint,
+            delete_checkpoint,
+            set_autosave_policy,
+            export_save_slot,
+            import_save_slot,
+            list_offers,
+            accept_offer,
+            schedule_meeting,
+            list_meeting_slots,
+            get_skill_tree,
+            get_data_pack_errors,
+            decline_offer,
         ])
         .run(tauri::generate_context!())
         .expect("error running tauri app");
@@ -0,0 +1,554 @@
+use crate::master_data::skill_graph::build_skill_graph;
+use crate::master_data::skills::{default_skill_data, SkillData};
+use crate::sim::locations::locations::LOCATION_GRAPH;
+use crate::sim::new_game::new_game::{
+    default_company_presets, default_starting_employee_configs, CompanyPreset, StartingEmployeesConfig,
+};
+use crate::sim::person::init::load_global_skills_to_static;
+use crate::sim::talent_generation::talent_profile::{default_location_talent_entries, LocationTalentEntry, TalentProfile};
+use crate::utils::errors::{DataPackError, SkillGraphError};
+use bincode::{Decode, Encode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tracing::{error, info};
+
+/// Folder under the asset base path the base game pack is read from.
+pub const DATA_PACK_DIR: &str = "data";
+/// Id of the base game pack, the other packs are named after their folder.
+pub const BASE_PACK_ID: &str = "base";
+pub const SKILLS_FILE: &str = "skills";
+pub const COMPANY_PRESETS_FILE: &str = "company_presets";
+pub const STARTING_EMPLOYEES_FILE: &str = "starting_employees";
+pub const LOCATION_TALENT_PROFILES_FILE: &str = "location_talent_profiles";
+const PACK_FILES: [&str; 4] = [SKILLS_FILE, COMPANY_PRESETS_FILE, STARTING_EMPLOYEES_FILE, LOCATION_TALENT_PROFILES_FILE];
+
+pub static MASTER_DATA: OnceLock<MasterData> = OnceLock::new();
+
+/// Content the designers can tune without recompiling. Anything not in the data packs uses the built-in defaults.
+#[derive(Debug, Clone)]
+pub struct MasterData {
+    pub skills: Vec<SkillData>,
+    pub company_presets: Vec<CompanyPreset>,
+    pub starting_employee_configs: Vec<StartingEmployeesConfig>,
+    pub location_talent_profiles: Vec<LocationTalentEntry>,
+    /// The packs applied, in load order
+    pub packs: Vec<DataPackInfo>,
+    /// Problems found in the data packs, the affected files were skipped
+    pub errors: Vec<String>,
+}
+
+impl Default for MasterData {
+    fn default() -> Self {
+        Self {
+            skills: default_skill_data(),
+            company_presets: default_company_presets(),
+            starting_employee_configs: default_starting_employee_configs(),
+            location_talent_profiles: default_location_talent_entries(),
+            packs: vec![],
+            errors: vec![],
+        }
+    }
+}
+
+/// A folder of data files. Packs are applied in order, each on top of the ones before it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataPack {
+    pub id: String,
+    pub path: PathBuf,
+}
+
+impl DataPack {
+    pub fn base(asset_base_path: &Path) -> Self {
+        Self { id: BASE_PACK_ID.to_string(), path: asset_base_path.join(DATA_PACK_DIR) }
+    }
+}
+
+/// A pack a save was made with, the hash covers the contents of its data files.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
+pub struct DataPackInfo {
+    pub id: String,
+    pub hash: String,
+}
+
+/// Data pack entries are matched by this id when a later pack overrides or removes them.
+pub trait PackEntry {
+    fn pack_id(&self) -> &str;
+}
+
+impl PackEntry for SkillData {
+    fn pack_id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl PackEntry for CompanyPreset {
+    fn pack_id(&self) -> &str {
+        &self.name
+    }
+}
+
+impl PackEntry for StartingEmployeesConfig {
+    fn pack_id(&self) -> &str {
+        &self.name
+    }
+}
+
+impl PackEntry for LocationTalentEntry {
+    fn pack_id(&self) -> &str {
+        self.location.as_str()
+    }
+}
+
+/// Contents of a data pack file. A file holding just a list of entries is read as `entries`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PackPatch<T> {
+    /// Added, or replacing the entry with the same id
+    #[serde(default = "Vec::new")]
+    entries: Vec<T>,
+    /// Ids of entries to drop
+    #[serde(default)]
+    remove: Vec<String>,
+    /// Set for a list in the base pack, which replaces the built-in defaults
+    #[serde(skip)]
+    replace: bool,
+}
+
+impl<T: PackEntry> PackPatch<T> {
+    /// Applies the patch to `target`. Fails with the id of an entry to remove that is not there.
+    fn apply(self, target: &mut Vec<T>) -> Result<(), String> {
+        if self.replace {
+            *target = self.entries;
+            return Ok(());
+        }
+        for id in self.remove {
+            let before = target.len();
+            target.retain(|entry| entry.pack_id() != id);
+            if target.len() == before {
+                return Err(id);
+            }
+        }
+        for entry in self.entries {
+            match target.iter_mut().find(|existing| existing.pack_id() == entry.pack_id()) {
+                Some(existing) => *existing = entry,
+                None => target.push(entry),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 1-based line of the first occurrence of `needle`.
+fn line_of(contents: &str, needle: &str) -> Option<usize> {
+    contents.lines().position(|line| line.contains(needle)).map(|index| index + 1)
+}
+
+/// Reads `<name>.json` from `dir`. `None` when the data pack does not have the file.
+fn read_data_file<T: DeserializeOwned>(
+    dir: &Path,
+    name: &str,
+    is_base: bool,
+) -> Result<Option<(PathBuf, String, PackPatch<T>)>, DataPackError> {
+    let file = dir.join(format!("{name}.json"));
+    if !file.exists() {
+        let ron_file = dir.join(format!("{name}.ron"));
+        if ron_file.exists() {
+            return Err(DataPackError::UnsupportedFormat(ron_file));
+        }
+        return Ok(None);
+    }
+    let contents = fs::read_to_string(&file).map_err(|error| DataPackError::Io { file: file.clone(), error })?;
+    let parse_error = |e: serde_json::Error| {
+        let message = e.to_string();
+        // serde_json adds the position to the message, it is reported separately
+        let message = message.rsplit_once(" at line ").map_or(message.as_str(), |(message, _)| message).to_string();
+        DataPackError::Parse { file: file.clone(), line: e.line(), column: e.column(), message }
+    };
+    let patch = if contents.trim_start().starts_with('{') {
+        serde_json::from_str(&contents).map_err(parse_error)?
+    } else {
+        let entries = serde_json::from_str(&contents).map_err(parse_error)?;
+        PackPatch { entries, remove: vec![], replace: is_base }
+    };
+    Ok(Some((file, contents, patch)))
+}
+
+/// Hash of the data files of a pack, so saves can tell when a pack changed.
+fn pack_hash(dir: &Path) -> Result<String, DataPackError> {
+    let mut hasher = Sha256::new();
+    for name in PACK_FILES {
+        for extension in ["json", "ron"] {
+            let file = dir.join(format!("{name}.{extension}"));
+            if !file.exists() {
+                continue;
+            }
+            let contents = fs::read(&file).map_err(|error| DataPackError::Io { file: file.clone(), error })?;
+            hasher.update(name.as_bytes());
+            hasher.update((contents.len() as u64).to_le_bytes());
+            hasher.update(&contents);
+        }
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn validate_skills(file: &Path, contents: &str, skills: &[SkillData]) -> Result<(), DataPackError> {
+    let Err(e) = build_skill_graph(skills) else {
+        return Ok(());
+    };
+    let (skill, field) = match &e {
+        SkillGraphError::DuplicateSkill(skill) => (skill.as_str(), "id"),
+        SkillGraphError::InvalidField { skill, field, .. }
+        | SkillGraphError::UnknownLink { skill, field, .. }
+        | SkillGraphError::TierOrder { skill, field, .. } => (skill.as_str(), *field),
+        SkillGraphError::Cycle(skills) => (skills.first().map_or("", String::as_str), "feedforward"),
+    };
+    Err(DataPackError::Invalid {
+        file: file.to_path_buf(),
+        line: line_of(contents, &format!("\"{skill}\"")),
+        field: format!("{skill}.{field}"),
+        message: e.to_string(),
+    })
+}
+
+fn validate_company_presets(file: &Path, contents: &str, presets: &[CompanyPreset]) -> Result<(), DataPackError> {
+    let invalid = |field: String, line: Option<usize>, message: &str| DataPackError::Invalid {
+        file: file.to_path_buf(),
+        line,
+        field,
+        message: message.to_string(),
+    };
+    if presets.is_empty() {
+        return Err(invalid("[]".to_string(), None, "at least one company preset is needed"));
+    }
+    for (index, preset) in presets.iter().enumerate() {
+        if preset.name.trim().is_empty() {
+            return Err(invalid(format!("[{index}].name"), None, "name cannot be empty"));
+        }
+        if presets[..index].iter().any(|other| other.name == preset.name) {
+            let line = line_of(contents, &format!("\"{}\"", preset.name));
+            return Err(invalid(format!("[{index}].name"), line, "company preset names must be unique"));
+        }
+    }
+    Ok(())
+}
+
+fn validate_starting_employees(
+    file: &Path,
+    contents: &str,
+    configs: &[StartingEmployeesConfig],
+) -> Result<(), DataPackError> {
+    let invalid = |field: String, line: Option<usize>, message: &str| DataPackError::Invalid {
+        file: file.to_path_buf(),
+        line,
+        field,
+        message: message.to_string(),
+    };
+    if configs.is_empty() {
+        return Err(invalid("[]".to_string(), None, "at least one starting employee config is needed"));
+    }
+    for (index, config) in configs.iter().enumerate() {
+        if config.name.trim().is_empty() {
+            return Err(invalid(format!("[{index}].name"), None, "name cannot be empty"));
+        }
+        if config.config.iter().map(|(_, count)| *count as u32).sum::<u32>() == 0 {
+            let line = line_of(contents, &format!("\"{}\"", config.name));
+            return Err(invalid(format!("[{index}].config"), line, "needs at least one employee"));
+        }
+    }
+    Ok(())
+}
+
+fn validate_location_talent_profiles(
+    file: &Path,
+    contents: &str,
+    entries: &[LocationTalentEntry],
+) -> Result<(), DataPackError> {
+    for (index, entry) in entries.iter().enumerate() {
+        let line = line_of(contents, &format!("\"{}\"", entry.location.as_str()));
+        let invalid = |field: &str, message: &str| DataPackError::Invalid {
+            file: file.to_path_buf(),
+            line,
+            field: format!("[{index}].{field}"),
+            message: message.to_string(),
+        };
+        if !LOCATION_GRAPH.locations.contains_key(&entry.location) {
+            return Err(invalid("location", &format!("unknown location '{}'", entry.location.as_str())));
+        }
+        if entries[..index].iter().any(|other| other.location == entry.location) {
+            return Err(invalid("location", "a location can only have one talent profile"));
+        }
+        let TalentProfile::StatsSkew { boosted, suppressed } = &entry.profile;
+        if boosted.iter().chain(suppressed).any(|skew| !skew.weight.is_finite() || skew.weight < 0.0) {
+            return Err(invalid("profile", "weights cannot be negative"));
+        }
+    }
+    Ok(())
+}
+
+/// Reads a data pack file and applies it on top of `target`. Files with errors leave `target` untouched.
+fn load_into<T: DeserializeOwned + PackEntry + Clone>(
+    pack: &DataPack,
+    name: &str,
+    target: &mut Vec<T>,
+    errors: &mut Vec<String>,
+    validate: impl Fn(&Path, &str, &[T]) -> Result<(), DataPackError>,
+) {
+    let loaded = read_data_file::<T>(&pack.path, name, pack.id == BASE_PACK_ID).and_then(|loaded| {
+        let Some((file, contents, patch)) = loaded else {
+            return Ok(None);
+        };
+        let mut merged = target.clone();
+        patch.apply(&mut merged).map_err(|id| DataPackError::Invalid {
+            file: file.clone(),
+            line: line_of(&contents, &format!("\"{id}\"")),
+            field: "remove".to_string(),
+            message: format!("there is no entry '{id}' to remove"),
+        })?;
+        validate(&file, &contents, &merged)?;
+        Ok(Some((file, merged)))
+    });
+    match loaded {
+        Ok(Some((file, merged))) => {
+            info!("Loaded {}", file.display());
+            *target = merged;
+        }
+        Ok(None) => {}
+        Err(e) => {
+            error!("Invalid file in data pack '{}', skipping its {}: {}", pack.id, name, e);
+            errors.push(e.to_string());
+        }
+    }
+}
+
+/// The base game pack under the assets, then every folder in `mods_dir` in alphabetical order.
+pub fn discover_data_packs(asset_base_path: &Path, mods_dir: &Path) -> Vec<DataPack> {
+    let mut mods: Vec<DataPack> = fs::read_dir(mods_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| DataPack { id: entry.file_name().to_string_lossy().to_string(), path: entry.path() })
+        .collect();
+    mods.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let mut packs = vec![DataPack::base(asset_base_path)];
+    packs.extend(mods);
+    packs
+}
+
+/// Applies the packs in order on top of the built-in defaults.
+pub fn load_data_packs(packs: &[DataPack]) -> MasterData {
+    let mut data = MasterData::default();
+    let mut errors = Vec::new();
+
+    for pack in packs {
+        let hash = match pack_hash(&pack.path) {
+            Ok(hash) => hash,
+            Err(e) => {
+                error!("Skipping data pack '{}': {}", pack.id, e);
+                errors.push(e.to_string());
+                continue;
+            }
+        };
+        load_into(pack, SKILLS_FILE, &mut data.skills, &mut errors, validate_skills);
+        load_into(pack, COMPANY_PRESETS_FILE, &mut data.company_presets, &mut errors, validate_company_presets);
+        load_into(
+            pack,
+            STARTING_EMPLOYEES_FILE,
+            &mut data.starting_employee_configs,
+            &mut errors,
+            validate_starting_employees,
+        );
+        load_into(
+            pack,
+            LOCATION_TALENT_PROFILES_FILE,
+            &mut data.location_talent_profiles,
+            &mut errors,
+            validate_location_talent_profiles,
+        );
+        data.packs.push(DataPackInfo { id: pack.id.clone(), hash });
+    }
+
+    data.errors = errors;
+    data
+}
+
+/// Loads the data packs and builds the global skills from them. Fails when even the built-in skills are invalid.
+pub fn load_master_data(asset_base_path: &Path, mods_dir: &Path) -> Result<(), SkillGraphError> {
+    let packs = discover_data_packs(asset_base_path, mods_dir);
+    info!("Data packs: {:?}", packs.iter().map(|pack| pack.id.as_str()).collect::<Vec<_>>());
+    let data = load_data_packs(&packs);
+    load_global_skills_to_static(&data.skills)?;
+    if MASTER_DATA.set(data).is_err() {
+        error!("Master data was already loaded");
+    }
+    Ok(())
+}
+
+/// The packs currently applied, as recorded in the save metadata.
+pub fn active_data_packs() -> Vec<DataPackInfo> {
+    MASTER_DATA.get().map(|data| data.packs.clone()).unwrap_or_default()
+}
+
+/// Packs a save was made with that are not active. A save cannot be loaded without them.
+pub fn missing_data_packs(saved: &[DataPackInfo], active: &[DataPackInfo]) -> Vec<String> {
+    saved
+        .iter()
+        .filter(|pack| !active.iter().any(|active| active.id == pack.id))
+        .map(|pack| pack.id.clone())
+        .collect()
+}
+
+/// Packs a save was made with whose contents have changed since, the save still loads with the new data.
+pub fn changed_data_packs(saved: &[DataPackInfo], active: &[DataPackInfo]) -> Vec<String> {
+    saved
+        .iter()
+        .filter(|pack| active.iter().any(|active| active.id == pack.id && active.hash != pack.hash))
+        .map(|pack| pack.id.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::master_data::skills::SKILL_DEFS;
+    use crate::sim::person::spawner::TalentGrade;
+
+    fn pack(files: &[(&str, &str)]) -> tempfile::TempDir {
+        let assets = tempfile::tempdir().unwrap();
+        let dir = assets.path().join(DATA_PACK_DIR);
+        fs::create_dir_all(&dir).unwrap();
+        for (name, contents) in files {
+            fs::write(dir.join(name), contents).unwrap();
+        }
+        assets
+    }
+
+    #[test]
+    fn missing_data_pack_uses_the_defaults() {
+        let assets = tempfile::tempdir().unwrap();
+        let data = load_data_packs(&[DataPack::base(assets.path())]);
+        assert!(data.errors.is_empty());
+        assert_eq!(data.skills.len(), SKILL_DEFS.len());
+        assert_eq!(data.company_presets, default_company_presets());
+    }
+
+    #[test]
+    fn data_pack_files_replace_the_defaults() {
+        let assets = pack(&[
+            ("company_presets.json", r#"[{ "name": "Test Co", "slogan": "Testing." }]"#),
+            ("starting_employees.json", r#"[{ "name": "Duo", "config": [["Gifted", 2]] }]"#),
+        ]);
+        let data = load_data_packs(&[DataPack::base(assets.path())]);
+        assert!(data.errors.is_empty(), "{:?}", data.errors);
+        assert_eq!(data.company_presets, vec![CompanyPreset { name: "Test Co".to_string(), slogan: "Testing.".to_string() }]);
+        assert_eq!(data.starting_employee_configs[0].config, vec![(TalentGrade::Gifted, 2)]);
+        // Files not in the pack keep the defaults
+        assert_eq!(data.skills.len(), SKILL_DEFS.len());
+    }
+
+    #[test]
+    fn parse_errors_point_at_the_line_and_field() {
+        let assets = pack(&[("company_presets.json", "[\n  { \"name\": \"Test Co\",\n    \"motto\": \"Testing.\" }\n]")]);
+        let data = load_data_packs(&[DataPack::base(assets.path())]);
+        assert_eq!(data.company_presets, default_company_presets());
+        let error = &data.errors[0];
+        assert!(error.contains("company_presets.json:3:"), "{error}");
+        assert!(error.contains("motto"), "{error}");
+    }
+
+    #[test]
+    fn invalid_skills_fall_back_to_the_defaults() {
+        let skills = r#"[
+  { "id": "basics", "name": "Basics", "description": "", "domain": "Execution",
+    "related_stats": ["Focus"], "tier": "foundational", "feedforward": ["missing"] }
+]"#;
+        let assets = pack(&[("skills.json", skills)]);
+        let data = load_data_packs(&[DataPack::base(assets.path())]);
+        assert_eq!(data.skills.len(), SKILL_DEFS.len());
+        let error = &data.errors[0];
+        assert!(error.contains("skills.json:2: basics.feedforward"), "{error}");
+    }
+
+    #[test]
+    fn later_packs_add_override_and_remove_entries() {
+        let base = pack(&[("company_presets.json", r#"[{ "name": "A", "slogan": "a" }, { "name": "B", "slogan": "b" }]"#)]);
+        let mods = tempfile::tempdir().unwrap();
+        let first = mods.path().join("01_first");
+        let second = mods.path().join("02_second");
+        fs::create_dir_all(&first).unwrap();
+        fs::create_dir_all(&second).unwrap();
+        fs::write(first.join("company_presets.json"), r#"[{ "name": "B", "slogan": "better" }, { "name": "C", "slogan": "c" }]"#).unwrap();
+        fs::write(second.join("company_presets.json"), r#"{ "remove": ["A"] }"#).unwrap();
+
+        let packs = discover_data_packs(base.path(), mods.path());
+        assert_eq!(packs.iter().map(|pack| pack.id.as_str()).collect::<Vec<_>>(), ["base", "01_first", "02_second"]);
+        let data = load_data_packs(&packs);
+        assert!(data.errors.is_empty(), "{:?}", data.errors);
+        let presets: Vec<(&str, &str)> =
+            data.company_presets.iter().map(|preset| (preset.name.as_str(), preset.slogan.as_str())).collect();
+        assert_eq!(presets, [("B", "better"), ("C", "c")]);
+        assert_eq!(data.packs.len(), 3);
+    }
+
+    #[test]
+    fn mods_can_link_to_base_skills_and_replace_location_profiles() {
+        let mods = tempfile::tempdir().unwrap();
+        let dir = mods.path().join("extra");
+        fs::create_dir_all(&dir).unwrap();
+        let basics = &default_skill_data()[0];
+        let skill = format!(
+            r#"[{{ "id": "modded", "name": "Modded", "description": "", "domain": "Execution",
+                 "related_stats": ["Focus"], "tier": "{}", "feedback": ["{}"] }}]"#,
+            basics.tier, basics.id
+        );
+        fs::write(dir.join("skills.json"), skill).unwrap();
+        let profile = r#"[{ "location": "idea_hub", "profile": { "StatsSkew": { "boosted": [], "suppressed": [] } } }]"#;
+        fs::write(dir.join("location_talent_profiles.json"), profile).unwrap();
+
+        let data = load_data_packs(&[DataPack { id: "extra".to_string(), path: dir }]);
+        assert!(data.errors.is_empty(), "{:?}", data.errors);
+        assert_eq!(data.skills.len(), SKILL_DEFS.len() + 1);
+        let idea_hub = data.location_talent_profiles.iter().find(|entry| entry.location.as_str() == "idea_hub").unwrap();
+        assert_eq!(idea_hub.profile, TalentProfile::StatsSkew { boosted: vec![], suppressed: vec![] });
+        assert_eq!(data.location_talent_profiles.len(), default_location_talent_entries().len());
+    }
+
+    #[test]
+    fn removing_unknown_entries_is_reported() {
+        let assets = pack(&[("starting_employees.json", "{\n  \"remove\": [\"Nobody\"]\n}")]);
+        let data = load_data_packs(&[DataPack { id: "mod".to_string(), path: assets.path().join(DATA_PACK_DIR) }]);
+        assert_eq!(data.starting_employee_configs, default_starting_employee_configs());
+        assert!(data.errors[0].contains("starting_employees.json:2: remove"), "{}", data.errors[0]);
+    }
+
+    #[test]
+    fn saves_need_their_packs_but_not_the_same_contents() {
+        let assets = pack(&[("company_presets.json", r#"[{ "name": "A", "slogan": "a" }]"#)]);
+        let saved = load_data_packs(&[DataPack::base(assets.path())]).packs;
+        fs::write(assets.path().join(DATA_PACK_DIR).join("company_presets.json"), r#"[{ "name": "A", "slogan": "b" }]"#)
+            .unwrap();
+        let active = load_data_packs(&[DataPack::base(assets.path())]).packs;
+
+        assert!(missing_data_packs(&saved, &saved).is_empty());
+        assert!(changed_data_packs(&saved, &saved).is_empty());
+        // A changed pack is still there, the save loads with its new data
+        assert!(missing_data_packs(&saved, &active).is_empty());
+        assert_eq!(changed_data_packs(&saved, &active), ["base"]);
+        assert_eq!(missing_data_packs(&saved, &[]), ["base"]);
+        assert!(changed_data_packs(&saved, &[]).is_empty());
+        // Packs added since the save are fine
+        assert!(missing_data_packs(&[], &active).is_empty());
+    }
+
+    #[test]
+    fn ron_files_are_reported_as_unsupported() {
+        let assets = pack(&[("skills.ron", "[]")]);
+        let data = load_data_packs(&[DataPack::base(assets.path())]);
+        assert!(data.errors[0].contains("unsupported data pack format"));
+    }
+}
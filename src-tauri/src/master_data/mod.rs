@@ -1 +1,3 @@
 pub mod skills;
+pub mod skill_graph;
+pub mod data_pack;
@@ -0,0 +1,233 @@
+use crate::master_data::skills::SkillData;
+use crate::sim::person::skills::{Domain, GlobalSkill, SkillId, SkillLink, Tier};
+use crate::sim::person::stats::StatType;
+use crate::utils::errors::SkillGraphError;
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+/// Factor of the links between skills, the master data does not give one per link yet.
+pub const DEFAULT_LINK_FACTOR: u8 = 50;
+
+pub static SKILL_GRAPH: OnceLock<SkillGraph> = OnceLock::new();
+
+/// Feedforward links between skills. A skill is a prerequisite of the skills it feeds forward into.
+#[derive(Debug, Default, Clone)]
+pub struct SkillGraph {
+    prerequisites: HashMap<SkillId, Vec<SkillId>>,
+    unlocks: HashMap<SkillId, Vec<SkillId>>,
+}
+
+impl SkillGraph {
+    /// Skills feeding forward into `skill`.
+    pub fn prerequisites_of(&self, skill: &SkillId) -> &[SkillId] {
+        self.prerequisites.get(skill).map_or(&[], Vec::as_slice)
+    }
+
+    /// Skills `skill` feeds forward into.
+    pub fn unlocks(&self, skill: &SkillId) -> &[SkillId] {
+        self.unlocks.get(skill).map_or(&[], Vec::as_slice)
+    }
+
+    /// Every skill that has to come before `skill`, nearest first.
+    pub fn all_prerequisites_of(&self, skill: &SkillId) -> Vec<SkillId> {
+        let mut seen = HashSet::new();
+        let mut result = Vec::new();
+        let mut queue: Vec<SkillId> = self.prerequisites_of(skill).to_vec();
+        while !queue.is_empty() {
+            let mut next = Vec::new();
+            for prerequisite in queue {
+                if seen.insert(prerequisite.clone()) {
+                    next.extend(self.prerequisites_of(&prerequisite).iter().cloned());
+                    result.push(prerequisite);
+                }
+            }
+            queue = next;
+        }
+        result
+    }
+}
+
+fn resolve_links(
+    def: &SkillData,
+    feeds_forward: bool,
+    tiers: &HashMap<&str, Tier>,
+) -> Result<Vec<SkillLink>, SkillGraphError> {
+    let (field, targets) = if feeds_forward { ("feedforward", &def.feedforward) } else { ("feedback", &def.feedback) };
+    let tier = tiers[def.id.as_str()];
+    targets
+        .iter()
+        .map(|target| {
+            let target_tier = *tiers.get(target.as_str()).ok_or_else(|| SkillGraphError::UnknownLink {
+                skill: def.id.to_string(),
+                field,
+                target: target.to_string(),
+            })?;
+            // Feedforward builds up the tiers, feedback reinforces the tiers below
+            let in_order = if feeds_forward { target_tier >= tier } else { target_tier <= tier };
+            if !in_order {
+                return Err(SkillGraphError::TierOrder { skill: def.id.to_string(), field, target: target.to_string() });
+            }
+            Ok(SkillLink::new(SkillId(target.to_string()), DEFAULT_LINK_FACTOR))
+        })
+        .collect()
+}
+
+/// Depth first search for a feedforward cycle, returns the skills making up the first one found.
+fn find_cycle(unlocks: &HashMap<SkillId, Vec<SkillId>>) -> Option<Vec<String>> {
+    fn visit(
+        skill: &SkillId,
+        unlocks: &HashMap<SkillId, Vec<SkillId>>,
+        done: &mut HashSet<SkillId>,
+        path: &mut Vec<SkillId>,
+    ) -> Option<Vec<String>> {
+        if let Some(start) = path.iter().position(|on_path| on_path == skill) {
+            let mut cycle: Vec<String> = path[start..].iter().map(|skill| skill.0.clone()).collect();
+            cycle.push(skill.0.clone());
+            return Some(cycle);
+        }
+        if done.contains(skill) {
+            return None;
+        }
+        path.push(skill.clone());
+        for next in unlocks.get(skill).into_iter().flatten() {
+            if let Some(cycle) = visit(next, unlocks, done, path) {
+                return Some(cycle);
+            }
+        }
+        path.pop();
+        done.insert(skill.clone());
+        None
+    }
+
+    let mut skills: Vec<&SkillId> = unlocks.keys().collect();
+    skills.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut done = HashSet::new();
+    skills.into_iter().find_map(|skill| visit(skill, unlocks, &mut done, &mut Vec::new()))
+}
+
+/// Turns the skill definitions into [`GlobalSkill`]s with their links resolved, and the graph of those links.
+pub fn build_skill_graph(defs: &[SkillData]) -> Result<(HashMap<SkillId, GlobalSkill>, SkillGraph), SkillGraphError> {
+    let mut tiers: HashMap<&str, Tier> = HashMap::new();
+    for def in defs {
+        let invalid = |field: &'static str, value: &str| SkillGraphError::InvalidField {
+            skill: def.id.clone(),
+            field,
+            value: value.to_string(),
+        };
+        let tier = def.tier.parse::<Tier>().map_err(|_| invalid("tier", &def.tier))?;
+        if let Some(domain) = def.domain.split(',').find(|domain| domain.parse::<Domain>().is_err()) {
+            return Err(invalid("domain", domain));
+        }
+        if let Some(stat) = def.related_stats.iter().find(|stat| stat.parse::<StatType>().is_err()) {
+            return Err(invalid("related_stats", stat));
+        }
+        if tiers.insert(def.id.as_str(), tier).is_some() {
+            return Err(SkillGraphError::DuplicateSkill(def.id.to_string()));
+        }
+    }
+
+    let mut skills = HashMap::new();
+    let mut graph = SkillGraph::default();
+    for def in defs {
+        let mut skill = GlobalSkill::from(def);
+        skill.feedforward = resolve_links(def, true, &tiers)?;
+        skill.feedback = resolve_links(def, false, &tiers)?;
+
+        for link in &skill.feedforward {
+            graph.unlocks.entry(skill.id.clone()).or_default().push(link.target.clone());
+            graph.prerequisites.entry(link.target.clone()).or_default().push(skill.id.clone());
+        }
+        skills.insert(skill.id.clone(), skill);
+    }
+
+    if let Some(cycle) = find_cycle(&graph.unlocks) {
+        return Err(SkillGraphError::Cycle(cycle));
+    }
+    Ok((skills, graph))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::master_data::skills::{default_skill_data, SkillDef, SKILL_DEFS};
+
+    fn def(id: &'static str, tier: &'static str, feedforward: &'static [&'static str], feedback: &'static [&'static str]) -> SkillData {
+        SkillData::from(&SkillDef {
+            id,
+            name: id,
+            description: "",
+            domain: "Execution",
+            related_stats: &[],
+            tier,
+            feedforward,
+            feedback,
+        })
+    }
+
+    #[test]
+    fn built_in_skills_are_valid() {
+        let (skills, _) = build_skill_graph(&default_skill_data()).unwrap();
+        assert_eq!(skills.len(), SKILL_DEFS.len());
+    }
+
+    #[test]
+    fn links_resolve_into_a_queryable_graph() {
+        let defs = [
+            def("basics", "foundational", &["concepts"], &[]),
+            def("concepts", "conceptual", &["practice"], &["basics"]),
+            def("practice", "applied", &[], &[]),
+        ];
+        let (skills, graph) = build_skill_graph(&defs).unwrap();
+        let id = |id: &str| SkillId(id.to_string());
+
+        assert_eq!(skills[&id("concepts")].feedback[0].target, id("basics"));
+        assert_eq!(graph.prerequisites_of(&id("practice")), &[id("concepts")]);
+        assert_eq!(graph.unlocks(&id("basics")), &[id("concepts")]);
+        assert_eq!(graph.all_prerequisites_of(&id("practice")), vec![id("concepts"), id("basics")]);
+        assert!(graph.prerequisites_of(&id("basics")).is_empty());
+    }
+
+    #[test]
+    fn dangling_links_are_rejected() {
+        let defs = [def("basics", "foundational", &["missing"], &[])];
+        assert_eq!(
+            build_skill_graph(&defs).unwrap_err(),
+            SkillGraphError::UnknownLink { skill: "basics".to_string(), field: "feedforward", target: "missing".to_string() }
+        );
+    }
+
+    #[test]
+    fn links_must_follow_the_tier_order() {
+        let defs = [def("basics", "foundational", &[], &["practice"]), def("practice", "applied", &[], &[])];
+        assert!(matches!(build_skill_graph(&defs), Err(SkillGraphError::TierOrder { .. })));
+
+        let defs = [def("basics", "foundational", &[], &[]), def("practice", "applied", &["basics"], &[])];
+        assert!(matches!(build_skill_graph(&defs), Err(SkillGraphError::TierOrder { .. })));
+    }
+
+    #[test]
+    fn feedforward_cycles_are_rejected() {
+        let defs = [
+            def("a", "conceptual", &["b"], &[]),
+            def("b", "conceptual", &["c"], &[]),
+            def("c", "conceptual", &["a"], &[]),
+        ];
+        assert_eq!(
+            build_skill_graph(&defs).unwrap_err(),
+            SkillGraphError::Cycle(vec!["a".to_string(), "b".to_string(), "c".to_string(), "a".to_string()])
+        );
+    }
+
+    #[test]
+    fn bad_tiers_and_duplicates_are_rejected() {
+        let defs = [def("a", "expert", &[], &[])];
+        assert!(matches!(build_skill_graph(&defs), Err(SkillGraphError::InvalidField { field: "tier", .. })));
+
+        let defs = [def("a", "applied", &[], &[]), def("a", "applied", &[], &[])];
+        assert_eq!(build_skill_graph(&defs).unwrap_err(), SkillGraphError::DuplicateSkill("a".to_string()));
+
+        let mut defs = [def("a", "applied", &[], &[])];
+        defs[0].related_stats = vec!["Luck".to_string()];
+        assert!(matches!(build_skill_graph(&defs), Err(SkillGraphError::InvalidField { field: "related_stats", .. })));
+    }
+}
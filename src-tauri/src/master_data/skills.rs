@@ -1,4 +1,5 @@
 use crate::sim::person::skills::{GlobalSkill, SkillId};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::OnceLock;
 
@@ -13,6 +14,44 @@ pub struct SkillDef {
     pub feedforward: &'static [&'static str],
     pub feedback: &'static [&'static str],
 }
+/// Owned skill definition, as read from a data pack. The built-in [`SKILL_DEFS`] convert into it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SkillData {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    /// Comma separated list of domains
+    pub domain: String,
+    pub related_stats: Vec<String>,
+    pub tier: String,
+    #[serde(default)]
+    pub feedforward: Vec<String>,
+    #[serde(default)]
+    pub feedback: Vec<String>,
+}
+
+impl From<&SkillDef> for SkillData {
+    fn from(value: &SkillDef) -> Self {
+        let strings = |values: &[&str]| values.iter().map(|value| value.to_string()).collect();
+        Self {
+            id: value.id.to_string(),
+            name: value.name.to_string(),
+            description: value.description.to_string(),
+            domain: value.domain.to_string(),
+            related_stats: strings(value.related_stats),
+            tier: value.tier.to_string(),
+            feedforward: strings(value.feedforward),
+            feedback: strings(value.feedback),
+        }
+    }
+}
+
+/// The built-in skill definitions.
+pub fn default_skill_data() -> Vec<SkillData> {
+    SKILL_DEFS.iter().map(SkillData::from).collect()
+}
+
 pub static GLOBAL_SKILLS: OnceLock<HashMap<SkillId,GlobalSkill>> = OnceLock::new();
 pub const SKILL_DEFS: &[SkillDef] = &[
     SkillDef {
@@ -2,7 +2,10 @@ use std::sync::Arc;
 
 use legion::{Entity, Resources};
 
+use crate::db::checkpoints::AutosavePolicy;
 use crate::db::init::SaveSlot;
+use crate::sim::utils::random::SimRng;
+use crate::integrations::snapshots_emitter::delta::SnapshotDeltaEmitter;
 use crate::integrations::snapshots_emitter::snapshots_emitter::SnapshotEvent;
 use crate::{
     integrations::{
@@ -18,12 +21,19 @@ use crate::{
         ,
         systems::global::UsedProfilePictureRegistry,
         team::components::TeamId,
-        calendar::components::CalendarEventId,
+        calendar::components::{CalendarEventId, CalendarIndex},
+        project::{
+            functionality::FunctionalityId, project::ProjectId, requirement::RequirementId,
+            task::TaskId, customer::{CustomerId, OfferId},
+        },
     },
 };
 
 pub fn initialize_non_shared_resources(resources: &mut Resources) {
     resources.insert(UsedProfilePictureRegistry::default());
+    resources.insert(CalendarIndex::new());
+    resources.insert(SimRng::default());
+    resources.insert(AutosavePolicy::default());
     
     //registries
     resources.insert(Arc::new(Registry::<PersonId, Entity>::with_name(
@@ -37,11 +47,38 @@ pub fn initialize_non_shared_resources(resources: &mut Resources) {
     resources.insert(Arc::new(Registry::<CalendarEventId, Entity>::with_name(
         "Calendar Event registry",
     )));
+
+    resources.insert(Arc::new(Registry::<ProjectId, Entity>::with_name(
+        "Project registry",
+    )));
+
+    resources.insert(Arc::new(Registry::<RequirementId, Entity>::with_name(
+        "Requirement registry",
+    )));
+
+    resources.insert(Arc::new(Registry::<FunctionalityId, Entity>::with_name(
+        "Functionality registry",
+    )));
+
+    resources.insert(Arc::new(Registry::<TaskId, Entity>::with_name(
+        "Task registry",
+    )));
+
+    resources.insert(Arc::new(Registry::<CustomerId, Entity>::with_name(
+        "Customer registry",
+    )));
+
+    resources.insert(Arc::new(Registry::<OfferId, Entity>::with_name(
+        "Offer registry",
+    )));
     
     // resources.insert(Arc::new(DashMap::<&'static str, u64>::new()));//last update map
     resources.insert(SaveSlot::default());
 }
 
+/// Ticks between two full snapshots of the collections sent as deltas, one sim day
+const FULL_RESYNC_TICKS: u64 = 96;
+
 pub fn initialize_emit_registries(
     main_snapshot_state: &Arc<SnapshotState>,
 ) -> SnapshotEmitRegistry {
@@ -56,14 +93,15 @@ pub fn initialize_emit_registries(
         },
     };
 
-    let person_snapshots_emitter = SnapshotCollectionEmitter {
-        map: Arc::clone(&main_snapshot_state.persons),
-        config: SnapshotEmitterConfig {
+    let person_snapshots_emitter = SnapshotDeltaEmitter::new(
+        Arc::clone(&main_snapshot_state.persons),
+        SnapshotEmitterConfig {
             frequency: ExportFrequency::EveryTick,
             event_name: SnapshotEvent::Persons,
             last_sent_tick: Default::default(),
         },
-    };
+        FULL_RESYNC_TICKS,
+    );
 
     let debug_display_snapshots_emitter = SnapshotCollectionEmitter {
         map: Arc::clone(&main_snapshot_state.debug_display),
@@ -90,14 +128,15 @@ pub fn initialize_emit_registries(
         },
     };
 
-    let stress_snapshots_emitter = SnapshotCollectionEmitter {
-        map: Arc::clone(&main_snapshot_state.stress_level),
-        config: SnapshotEmitterConfig {
+    let stress_snapshots_emitter = SnapshotDeltaEmitter::new(
+        Arc::clone(&main_snapshot_state.stress_level),
+        SnapshotEmitterConfig {
             frequency: ExportFrequency::EveryTick,
             event_name: SnapshotEvent::Stress,
             last_sent_tick: Default::default(),
         },
-    };
+        FULL_RESYNC_TICKS,
+    );
 
     let stress_history_snapshots_emitter = SnapshotCollectionEmitter {
         map: Arc::clone(&main_snapshot_state.stress_history),
@@ -117,13 +156,57 @@ pub fn initialize_emit_registries(
         },
     };
 
-    let thoughts_emitter = SnapshotCollectionEmitter {
-        map: Arc::clone(&main_snapshot_state.thoughts),
-        config: SnapshotEmitterConfig {
+    let thoughts_emitter = SnapshotDeltaEmitter::new(
+        Arc::clone(&main_snapshot_state.thoughts),
+        SnapshotEmitterConfig {
             frequency: ExportFrequency::EveryTick,
             event_name: SnapshotEvent::Thoughts,
             last_sent_tick: Default::default(),
         },
+        FULL_RESYNC_TICKS,
+    );
+    let projects_emitter = SnapshotCollectionEmitter {
+        map: Arc::clone(&main_snapshot_state.projects),
+        config: SnapshotEmitterConfig {
+            frequency: ExportFrequency::EveryNTicks(4),
+            event_name: SnapshotEvent::Projects,
+            last_sent_tick: Default::default(),
+        },
+    };
+
+    let tasks_emitter = SnapshotCollectionEmitter {
+        map: Arc::clone(&main_snapshot_state.tasks),
+        config: SnapshotEmitterConfig {
+            frequency: ExportFrequency::EveryNTicks(4),
+            event_name: SnapshotEvent::Tasks,
+            last_sent_tick: Default::default(),
+        },
+    };
+
+    let customers_emitter = SnapshotCollectionEmitter {
+        map: Arc::clone(&main_snapshot_state.customers),
+        config: SnapshotEmitterConfig {
+            frequency: ExportFrequency::EveryNTicks(4),
+            event_name: SnapshotEvent::Customers,
+            last_sent_tick: Default::default(),
+        },
+    };
+
+    let offers_emitter = SnapshotCollectionEmitter {
+        map: Arc::clone(&main_snapshot_state.offers),
+        config: SnapshotEmitterConfig {
+            frequency: ExportFrequency::EveryNTicks(4),
+            event_name: SnapshotEvent::Offers,
+            last_sent_tick: Default::default(),
+        },
+    };
+    let meeting_slots_emitter = SnapshotFieldEmitter {
+        field: Arc::clone(&main_snapshot_state.meeting_slots),
+        config: SnapshotEmitterConfig {
+            frequency: ExportFrequency::EveryNTicks(4),
+            event_name: SnapshotEvent::MeetingSlots,
+            last_sent_tick: Default::default(),
+        },
     };
     snapshot_registry.register(company_snapshots_emitter);
     snapshot_registry.register(game_speed_snapshots_emitter);
@@ -134,6 +217,11 @@ pub fn initialize_emit_registries(
     snapshot_registry.register(stress_history_snapshots_emitter);
     snapshot_registry.register(working_hours_emitter);
     snapshot_registry.register(thoughts_emitter);
+    snapshot_registry.register(projects_emitter);
+    snapshot_registry.register(tasks_emitter);
+    snapshot_registry.register(customers_emitter);
+    snapshot_registry.register(offers_emitter);
+    snapshot_registry.register(meeting_slots_emitter);
 
 
     snapshot_registry
@@ -0,0 +1,113 @@
+use crate::db::inspect::{diff_saves, dump_save, open_save_db};
+use crate::utils::errors::SaveToolError;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+pub const USAGE: &str = "Usage: you-need-to-deliver --dump-save <save> [--out <file.json>]\n       \
+you-need-to-deliver --diff-save <old save> <new save> [--json] [--out <file>]\n\
+A save is a slot folder, a checkpoint folder or a gamestate.sled folder.";
+
+/// What the save tool was asked to do, read from the command line.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SaveToolCommand {
+    /// Every entry of the save as pretty JSON
+    Dump { save: PathBuf, output: Option<PathBuf> },
+    /// Field level differences, one per line or as a JSON list
+    Diff { old: PathBuf, new: PathBuf, json: bool, output: Option<PathBuf> },
+}
+
+impl SaveToolCommand {
+    /// `None` when neither `--dump-save` nor `--diff-save` is on the command line.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Option<Self>, SaveToolError> {
+        let mut args = args.into_iter();
+        let mut command = None;
+        let mut output = None;
+        let mut json = false;
+        while let Some(flag) = args.next() {
+            let mut value = || args.next().map(PathBuf::from).ok_or_else(|| SaveToolError::Args(format!("{flag} needs a value\n{USAGE}")));
+            match flag.as_str() {
+                "--dump-save" => command = Some(Self::Dump { save: value()?, output: None }),
+                "--diff-save" => {
+                    let old = value()?;
+                    command = Some(Self::Diff { old, new: value()?, json: false, output: None })
+                }
+                "--out" => output = Some(value()?),
+                "--json" => json = true,
+                _ if command.is_none() => return Ok(None),
+                _ => return Err(SaveToolError::Args(format!("Unknown argument '{flag}'\n{USAGE}"))),
+            }
+        }
+        Ok(command.map(|command| match command {
+            Self::Dump { save, .. } => Self::Dump { save, output },
+            Self::Diff { old, new, .. } => Self::Diff { old, new, json, output },
+        }))
+    }
+
+    pub fn output(&self) -> Option<&PathBuf> {
+        match self {
+            Self::Dump { output, .. } | Self::Diff { output, .. } => output.as_ref(),
+        }
+    }
+
+    pub fn run(&self, out: &mut dyn Write) -> Result<(), SaveToolError> {
+        match self {
+            Self::Dump { save, .. } => {
+                let dump = dump_save(&open_save_db(save)?)?;
+                serde_json::to_writer_pretty(&mut *out, &dump)?;
+                writeln!(out)?;
+            }
+            Self::Diff { old, new, json, .. } => {
+                let old = dump_save(&open_save_db(old)?)?;
+                let new = dump_save(&open_save_db(new)?)?;
+                let changes = diff_saves(&old, &new);
+                if *json {
+                    serde_json::to_writer_pretty(&mut *out, &changes)?;
+                    writeln!(out)?;
+                } else {
+                    for change in changes {
+                        writeln!(out, "{}", change)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs the command, writing to `--out` or to stdout.
+    pub fn run_to_output(&self) -> Result<(), SaveToolError> {
+        match self.output() {
+            Some(path) => {
+                let mut file = BufWriter::new(File::create(path)?);
+                self.run(&mut file)?;
+                file.flush()?;
+            }
+            None => self.run(&mut std::io::stdout().lock())?,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn commands_come_from_the_command_line() {
+        assert_eq!(SaveToolCommand::from_args(args("--headless --ticks 5")).unwrap(), None);
+        assert_eq!(
+            SaveToolCommand::from_args(args("--dump-save saves/a --out a.json")).unwrap(),
+            Some(SaveToolCommand::Dump { save: PathBuf::from("saves/a"), output: Some(PathBuf::from("a.json")) })
+        );
+        assert_eq!(
+            SaveToolCommand::from_args(args("--diff-save a b --json")).unwrap(),
+            Some(SaveToolCommand::Diff { old: PathBuf::from("a"), new: PathBuf::from("b"), json: true, output: None })
+        );
+        assert!(matches!(SaveToolCommand::from_args(args("--diff-save a")), Err(SaveToolError::Args(_))));
+        assert!(matches!(SaveToolCommand::from_args(args("--dump-save a --ticks 5")), Err(SaveToolError::Args(_))));
+    }
+}
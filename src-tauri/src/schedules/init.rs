@@ -1,15 +1,25 @@
+use crate::action_queues::calendar_manager::handle_calendar_queue_system;
 use crate::action_queues::game_speed_manager::handle_game_speed_manager_queue_system;
+use crate::action_queues::offer_manager::handle_offer_queue_system;
 use crate::action_queues::sim_manager::{handle_new_game_manager_queue_system, handle_sim_manager_queue_system, test_sim_manager_system};
 use crate::action_queues::team_manager::{handle_team_assignment_queue_system, handle_team_manager_queue_system};
 use crate::action_queues::thought_manager::handle_thought_command_queue_system;
 use crate::integrations::queues::{handle_dispatch_queue_system, handle_sim_manager_dispatch_queue_system};
 use crate::integrations::snapshots_emitter::snapshots_emitter::run_snapshot_emitters_system;
-use crate::integrations::systems::{push_company_to_integration_system, push_debug_displays_to_integration_system, push_game_speed_snapshots_system, push_needs_to_integration_system, push_persons_to_integration_system, push_stress_history_to_integration_system, push_stress_level_to_integration_system, push_teams_to_integration_system, push_thoughts_to_integration_system, tick_needs_system};
+use crate::integrations::systems::{push_company_to_integration_system, push_debug_displays_to_integration_system, push_game_speed_snapshots_system, push_needs_to_integration_system, push_persons_to_integration_system, push_stress_history_to_integration_system, push_stress_level_to_integration_system, push_teams_to_integration_system, push_thoughts_to_integration_system, push_projects_to_integration_system, push_tasks_to_integration_system, push_customers_to_integration_system, push_finances_to_integration_system, push_working_hours_to_integration_system, tick_needs_system};
 use crate::sim::action::action::{decide_action_system, execute_action_system};
 use crate::sim::ai::consideration::goal_selection_system;
-use crate::sim::calendar::systems::sync_registry_from_calendar_event_system;
+use crate::sim::ai::planner::plan_goals_system;
+use crate::sim::company::finance::{run_payroll_system, update_weekly_payroll_system};
+use crate::sim::company::work_hours::{apply_work_hour_policy_system, track_work_hours_system};
+use crate::sim::person::skill_progression::{decay_unused_skills_system, practice_skills_system};
+use crate::sim::calendar::meetings::{respond_to_invitations_system, start_calendar_events_system};
+use crate::sim::calendar::systems::{expand_recurring_events_system, sync_registry_from_calendar_event_system, update_calendar_index_system};
 use crate::sim::persistence::persistence::{save_game_state_system, sync_registry_from_person_system, sync_registry_from_team_system};
-use crate::sim::person::init::{emit_done_setup_event_system, generate_employees_system, init_company_system, unset_first_run_flag_system};
+use crate::sim::project::market::{init_customers_system, post_project_offers_system, sync_registry_from_customer_system, sync_registry_from_offer_system, settle_customer_projects_system};
+use crate::sim::project::systems::{sync_registry_from_functionality_system, sync_registry_from_project_system, sync_registry_from_requirement_system, sync_registry_from_task_system, update_project_status_system};
+use crate::sim::project::work::{assign_tasks_system, do_task_work_system, roll_up_project_progress_system, sync_task_facts_system};
+use crate::sim::person::init::{emit_done_setup_event_system, generate_employees_system, init_company_system, seed_sim_rng_system, unset_first_run_flag_system};
 use crate::sim::person::morale::{daily_stress_reset_system, update_stress_system};
 use crate::sim::systems::global::{increase_sim_tick_system, print_person_system};
 use crate::sim::systems::time_triggers::morning_thought_trigger_system;
@@ -37,7 +47,9 @@ pub struct GameSchedules {
 pub fn init_schedules() -> GameSchedules {
     // Startup schedule, runs once on startup. add run once systems here.
     let startup = Schedule::builder()
+        .add_system(seed_sim_rng_system())
         .add_system(init_company_system())
+        .add_system(init_customers_system())
         .flush()
         .add_system(generate_employees_system())
         .flush()
@@ -89,6 +101,8 @@ pub fn init_schedules() -> GameSchedules {
         .add_system(handle_game_speed_manager_queue_system())
         .add_system(handle_team_manager_queue_system())
         .add_system(handle_team_assignment_queue_system())
+        .add_system(handle_offer_queue_system())
+        .add_system(handle_calendar_queue_system())
         .build();
 
     // main sim
@@ -97,14 +111,35 @@ pub fn init_schedules() -> GameSchedules {
         .add_system(increase_sim_tick_system())
         .add_system(print_person_system())
         .add_system(morning_thought_trigger_system())
+        .add_system(expand_recurring_events_system())
+        .add_system(respond_to_invitations_system())
+        .add_system(assign_tasks_system())
+        .add_system(sync_task_facts_system())
         .add_system(goal_selection_system())
+        .add_system(plan_goals_system())
         .add_system(update_stress_system())
         .add_system(daily_stress_reset_system())
         .add_system(tick_needs_system())
         .flush()
+        .add_system(update_calendar_index_system())
         .add_system(decide_action_system())
+        .add_system(start_calendar_events_system())
         .flush()
         .add_system(execute_action_system())
+        .add_system(do_task_work_system())
+        .add_system(practice_skills_system())
+        .add_system(decay_unused_skills_system())
+        .add_system(roll_up_project_progress_system())
+        .flush()
+        .add_system(update_project_status_system())
+        .add_system(post_project_offers_system())
+        .flush()
+        .add_system(settle_customer_projects_system())
+        .add_system(update_weekly_payroll_system())
+        .add_system(apply_work_hour_policy_system())
+        .add_system(track_work_hours_system())
+        .flush()
+        .add_system(run_payroll_system())
         .add_system(test_sim_manager_system())
         .add_system(save_game_state_system())
         .build();
@@ -118,12 +153,17 @@ pub fn init_schedules() -> GameSchedules {
             .add_system(push_persons_to_integration_system())
             .add_system(push_game_speed_snapshots_system())
             .add_system(push_company_to_integration_system())
+            .add_system(push_finances_to_integration_system())
             .add_system(push_teams_to_integration_system())
             .add_system(push_needs_to_integration_system())
             .add_system(push_debug_displays_to_integration_system())
             .add_system(push_stress_level_to_integration_system())
             .add_system(push_stress_history_to_integration_system())
             .add_system(push_thoughts_to_integration_system())
+            .add_system(push_projects_to_integration_system())
+            .add_system(push_tasks_to_integration_system())
+            .add_system(push_customers_to_integration_system())
+            .add_system(push_working_hours_to_integration_system())
             .build();
     let post_integration = Schedule::builder()
         .add_system(run_snapshot_emitters_system())
@@ -135,6 +175,18 @@ pub fn init_schedules() -> GameSchedules {
         .add_system(sync_registry_from_team_system())
         .flush()
         .add_system(sync_registry_from_calendar_event_system())
+        .flush()
+        .add_system(sync_registry_from_project_system())
+        .flush()
+        .add_system(sync_registry_from_requirement_system())
+        .flush()
+        .add_system(sync_registry_from_functionality_system())
+        .flush()
+        .add_system(sync_registry_from_task_system())
+        .flush()
+        .add_system(sync_registry_from_customer_system())
+        .flush()
+        .add_system(sync_registry_from_offer_system())
         .build();
     
     GameSchedules {
@@ -1,15 +1,23 @@
 use crate::sim::ai::consideration::GoalName;
-use crate::sim::ai::goap::CurrentGoal;
+use crate::sim::ai::goap::{
+    get_all_planner_actions, CurrentGoal, CurrentPlan, EmployeeGameAction, EmployeeGoapFacts,
+};
+use crate::sim::ai::planner::{find_planner_action, PlannedGoal};
+use crate::sim::calendar::components::CalendarEventId;
 use crate::sim::person::components::Person;
 use crate::sim::person::needs::{Energy, Hunger};
 use crate::sim::project::project::ProjectId;
 use crate::sim::resources::global::TickCounter;
 use crate::sim::sim_date::sim_date::SimDate;
+use crate::sim::utils::debugging::DebugDisplayComponent;
+use bincode::{Decode, Encode};
 use legion::systems::CommandBuffer;
 use legion::{system, Entity};
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use tracing::{debug, trace};
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, Encode, Decode)]
 pub enum ActionType {
     GeneralWork,
     Work(ProjectId),
@@ -17,22 +25,96 @@ pub enum ActionType {
     Rest,
     Eat,
     Idle,
+    Travel,
+    Purchase,
+    Socialize,
+    Meeting(CalendarEventId),
 }
 
-#[derive(Debug)]
+impl From<&EmployeeGameAction> for ActionType {
+    fn from(value: &EmployeeGameAction) -> Self {
+        match value {
+            EmployeeGameAction::GoToDesk => ActionType::Travel,
+            EmployeeGameAction::GoToCafeteria => ActionType::Travel,
+            EmployeeGameAction::BuyFood => ActionType::Purchase,
+            EmployeeGameAction::EatFood => ActionType::Eat,
+            EmployeeGameAction::TakeBreak => ActionType::Rest,
+            EmployeeGameAction::CodeTask => ActionType::GeneralWork,
+            EmployeeGameAction::Socialize => ActionType::Socialize,
+        }
+    }
+}
+
+/// The plan step an [`ActionIntent`] is carrying out, and the goal the plan was made for.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode)]
+pub struct PlanStep {
+    pub action: EmployeeGameAction,
+    pub goal: GoalName,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode)]
 pub struct ActionIntent {
     pub current: ActionType,
     pub started_at: Option<SimDate>,
+    pub step: Option<PlanStep>,
+    /// Set for intents that run until a fixed time, like calendar events
+    pub until: Option<SimDate>,
 }
 impl From<ActionType> for ActionIntent {
     fn from(value: ActionType) -> Self {
         Self{
             current: value,
             started_at: None,
+            step: None,
+            until: None,
+        }
+    }
+}
+
+impl ActionIntent {
+    pub fn for_step(action: EmployeeGameAction, goal: GoalName, started_at: SimDate) -> Self {
+        Self {
+            current: ActionType::from(&action),
+            started_at: Some(started_at),
+            step: Some(PlanStep { action, goal }),
+            until: None,
+        }
+    }
+
+    /// An intent that takes the employee away from their plan for `duration_ticks`.
+    pub fn for_event(current: ActionType, started_at: SimDate, duration_ticks: u8) -> Self {
+        Self {
+            current,
+            started_at: Some(started_at),
+            step: None,
+            until: Some(SimDate::from(started_at.to_tick() + duration_ticks.max(1) as u64)),
+        }
+    }
+
+    /// Whether an intent with a fixed end time has reached it.
+    pub fn is_over(&self, now: &SimDate) -> bool {
+        self.until.is_some_and(|until| *now >= until)
+    }
+
+    /// Number of quarter ticks this intent has been running for, counting the starting tick.
+    pub fn elapsed_quarter_ticks(&self, now: &SimDate) -> u64 {
+        match self.started_at {
+            None => 0,
+            Some(started_at) => now.to_tick().saturating_sub(started_at.to_tick()) + 1,
+        }
+    }
+
+    /// Whether the current plan step has run for its full duration.
+    pub fn is_step_finished(&self, now: &SimDate) -> bool {
+        match &self.step {
+            None => false,
+            Some(step) => self.elapsed_quarter_ticks(now) >= step.action.duration_quarter_ticks(),
         }
     }
 }
 
+// Picks the next plan step for the employee. An in-flight step is kept until it finishes,
+// unless the goal it was planned for is no longer the current goal, in which case it is interrupted.
 #[system(for_each)]
 pub fn decide_action(
     #[resource] tick_counter: &Arc<TickCounter>,
@@ -40,28 +122,65 @@ pub fn decide_action(
     person: &Person,
     current_goal: &CurrentGoal,
     action: Option<&ActionIntent>,
-    hunger: &Hunger,
-    energy: &Energy,
+    plan: Option<&CurrentPlan>,
+    planned_goal: Option<&PlannedGoal>,
     cmd: &mut CommandBuffer,
 ){
     // info!("Decide action {}", person.name);
-    match current_goal.0 {
-        GoalName::Rest => {cmd.add_component(*entity,ActionIntent::from(ActionType::Rest));}
-        GoalName::Eat => {cmd.add_component(*entity,ActionIntent::from(ActionType::Eat));}
-        GoalName::DoNothing => { cmd.remove_component::<ActionIntent>(*entity);}
+    // Calendar events are not interrupted, execute_action ends them
+    if action.is_some_and(|a| a.until.is_some()) {
+        return;
+    }
+    if let Some(PlanStep { action: step_action, goal }) = action.and_then(|a| a.step.as_ref()) {
+        if *goal == current_goal.0 {
+            return;
+        }
+        debug!(
+            "{} interrupted {:?} for {:?} to pursue {:?}",
+            person.name, step_action, goal, current_goal.0
+        );
     }
 
+    // Wait for the planner to catch up with the current goal
+    let plan = match (plan, planned_goal) {
+        (Some(plan), Some(planned_goal)) if planned_goal.0 == current_goal.0 => plan,
+        _ => {
+            if action.is_some() {
+                cmd.remove_component::<ActionIntent>(*entity);
+            }
+            return;
+        }
+    };
 
+    match plan.0.first() {
+        None => {
+            if action.is_some() {
+                cmd.remove_component::<ActionIntent>(*entity);
+            }
+        }
+        Some(next) => {
+            trace!("{} starting {:?}", person.name, next);
+            cmd.add_component(
+                *entity,
+                ActionIntent::for_step(next.clone(), current_goal.0.clone(), tick_counter.current_date()),
+            );
+        }
+    }
 }
 
+// Advances the current plan step. Need effects are applied every tick the step runs, once the step
+// has run for its duration it either succeeds (effects applied to the GOAP facts) or fails (plan dropped).
 #[system(for_each)]
 pub fn execute_action(
     #[resource] tick_counter: &Arc<TickCounter>,
     entity: &Entity,
     person: &Person,
     action: &ActionIntent,
+    plan: Option<&mut CurrentPlan>,
+    goap_facts: Option<&mut EmployeeGoapFacts>,
     hunger: &mut Hunger,
     energy: &mut Energy,
+    debug_display: &mut DebugDisplayComponent,
     cmd: &mut CommandBuffer,
 ) {
     // info!("Executing {:?}", action);
@@ -76,5 +195,99 @@ pub fn execute_action(
         }
         ActionType::Idle => {
         }
+        ActionType::Travel => {}
+        ActionType::Purchase => {}
+        ActionType::Socialize => {}
+        ActionType::Meeting(_) => {}
+    }
+
+    let now = tick_counter.current_date();
+    if action.until.is_some() {
+        if action.is_over(&now) {
+            trace!("{} finished {:?}", person.name, action.current);
+            cmd.remove_component::<ActionIntent>(*entity);
+        }
+        return;
     }
-}
\ No newline at end of file
+    let Some(step) = &action.step else {
+        return;
+    };
+    debug_display.entries.push((
+        "Action".to_string(),
+        format!(
+            "{:?} {}/{}",
+            step.action,
+            action.elapsed_quarter_ticks(&now).min(step.action.duration_quarter_ticks()),
+            step.action.duration_quarter_ticks()
+        ),
+    ));
+
+    if !action.is_step_finished(&now) {
+        return;
+    }
+    cmd.remove_component::<ActionIntent>(*entity);
+
+    let (Some(plan), Some(facts)) = (plan, goap_facts) else {
+        return;
+    };
+    let planner_actions = get_all_planner_actions();
+    let succeeded = find_planner_action(&planner_actions, &step.action)
+        .is_some_and(|planner_action| (planner_action.check_planner_preconditions)(facts));
+
+    if !succeeded {
+        // The world changed under the step, drop the plan so the planner builds a new one
+        debug!("{} failed {:?}", person.name, step.action);
+        plan.0.clear();
+        return;
+    }
+
+    if let Some(planner_action) = find_planner_action(&planner_actions, &step.action) {
+        (planner_action.apply_planner_effects)(facts);
+    }
+    if plan.0.first() == Some(&step.action) {
+        plan.0.remove(0);
+    }
+    trace!("{} completed {:?}", person.name, step.action);
+
+    if plan.0.is_empty() {
+        // Plan done. Clearing the completion facts lets the planner pick the goal up again
+        // if it is still the most pressing one.
+        debug!("{} completed plan for {:?}", person.name, step.goal);
+        facts.clear_goal_completion();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn step_finishes_after_its_duration() {
+        let start = SimDate::from(100);
+        let intent = ActionIntent::for_step(EmployeeGameAction::GoToCafeteria, GoalName::Eat, start);
+        assert!(!intent.is_step_finished(&SimDate::from(100)));
+        assert!(intent.is_step_finished(&SimDate::from(101)));
+        assert_eq!(intent.elapsed_quarter_ticks(&SimDate::from(101)), 2);
+    }
+
+    #[test]
+    fn intent_without_step_never_finishes() {
+        let intent = ActionIntent::from(ActionType::Idle);
+        assert!(!intent.is_step_finished(&SimDate::from(10_000)));
+    }
+
+    #[test]
+    fn event_intent_ends_after_its_duration() {
+        let intent = ActionIntent::for_event(ActionType::Meeting(CalendarEventId::new(1)), SimDate::from(100), 4);
+        assert!(!intent.is_over(&SimDate::from(103)));
+        assert!(intent.is_over(&SimDate::from(104)));
+        assert!(!intent.is_step_finished(&SimDate::from(104)));
+    }
+
+    #[test]
+    fn plan_steps_map_to_action_types() {
+        assert_eq!(ActionType::from(&EmployeeGameAction::EatFood), ActionType::Eat);
+        assert_eq!(ActionType::from(&EmployeeGameAction::TakeBreak), ActionType::Rest);
+        assert_eq!(ActionType::from(&EmployeeGameAction::GoToDesk), ActionType::Travel);
+    }
+}
@@ -71,6 +71,16 @@ impl Consideration for HungerConsideration {
         }
     }
 }
+// Scores when there is an unfinished task assigned, slightly above idling so work is the default activity
+pub struct TaskProgressConsideration;
+impl Consideration for TaskProgressConsideration {
+    fn score(&self, context: &ScoreContext) -> f32 {
+        if !context.goap_facts.has_assigned_task || context.goap_facts.task_progress_u8 >= 100 {
+            return 0.0;
+        }
+        0.45 + 0.1 * (1.0 - context.goap_facts.task_progress_u8 as f32 / 100.0)
+    }
+}
 pub struct DefaultConsideration;
 impl Consideration for DefaultConsideration {
     fn score(&self, context: &ScoreContext) -> f32 { 0.4f32 }
@@ -121,6 +131,7 @@ pub enum GoalName {
     Eat,
     #[default]
     DoNothing,
+    Work,
     // Add other goal names here as you define them
     // CompleteAssignedTask,
     // Socialize,
@@ -166,7 +177,7 @@ pub fn get_all_goal_definitions() -> Vec<GoalDefinition> {
     vec![
         GoalDefinition {
             name: GoalName::Rest,
-            goap_target_facts: EmployeeGoapFacts::default(), // No specific fact change needed for rest (could be `at_home = true`)
+            goap_target_facts: EmployeeGoapFacts { has_rested: true, ..Default::default() }, // Goal is to take a break
             considerations: vec![
                 (Box::new(EnergyConsideration), 1.0), // High weight for low energy
                 // (Box::new(StressConsideration), 0.3), // Some weight for stress reduction
@@ -174,12 +185,19 @@ pub fn get_all_goal_definitions() -> Vec<GoalDefinition> {
         },
         GoalDefinition {
             name: GoalName::Eat,
-            goap_target_facts: EmployeeGoapFacts { has_food: false, ..Default::default() }, // Goal is to consume food
+            goap_target_facts: EmployeeGoapFacts { has_eaten: true, ..Default::default() }, // Goal is to consume food
             considerations: vec![
                 (Box::new(HungerConsideration), 1.0), // High weight for hunger
                 // (Box::new(EnergyConsideration), 0.2), // Some weight for energy gain
             ],
         },
+        GoalDefinition {
+            name: GoalName::Work,
+            goap_target_facts: EmployeeGoapFacts { has_worked: true, ..Default::default() }, // Goal is to put in a work session
+            considerations: vec![
+                (Box::new(TaskProgressConsideration), 1.0),
+            ],
+        },
         GoalDefinition {
             name: GoalName::DoNothing,
             goap_target_facts: EmployeeGoapFacts::default(), // No specific fact change needed for rest (could be `at_home = true`)
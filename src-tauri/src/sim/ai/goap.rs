@@ -8,12 +8,17 @@ use std::collections::HashMap;
 // --- Employee GOAP Facts (Legion Component) ---
 // This represents the employee's current beliefs about themselves and the world
 // that are RELEVANT FOR GOAP ACTION PRECONDITIONS AND EFFECTS.
-#[derive(Clone, Debug, PartialEq, Eq, Hash)] // Still needs Hash/Eq for A*
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Encode, Decode)] // Still needs Hash/Eq for A*
 pub struct EmployeeGoapFacts {
     pub at_desk: bool,
     pub has_assigned_task: bool,
     pub task_progress_u8: u8, // 0-100 for percentage
     pub has_food: bool,
+    // Goal completion facts. These only describe what happened since the current goal was
+    // adopted and are cleared by the planner whenever the goal changes.
+    pub has_eaten: bool,
+    pub has_rested: bool,
+    pub has_worked: bool,
     // Add other facts directly changed by actions relevant for GOAP:
     // pub has_fixed_bug: bool,
     // pub knows_new_skill: bool,
@@ -26,6 +31,9 @@ impl Default for EmployeeGoapFacts {
             has_assigned_task: false,
             task_progress_u8: 0,
             has_food: false,
+            has_eaten: false,
+            has_rested: false,
+            has_worked: false,
         }
     }
 }
@@ -48,9 +56,54 @@ impl EmployeeGoapFacts {
         if goal.has_food != EmployeeGoapFacts::default().has_food && self.has_food != goal.has_food {
             satisfied = false;
         }
+        if goal.has_eaten != EmployeeGoapFacts::default().has_eaten && self.has_eaten != goal.has_eaten {
+            satisfied = false;
+        }
+        if goal.has_rested != EmployeeGoapFacts::default().has_rested && self.has_rested != goal.has_rested {
+            satisfied = false;
+        }
+        if goal.has_worked != EmployeeGoapFacts::default().has_worked && self.has_worked != goal.has_worked {
+            satisfied = false;
+        }
         satisfied
     }
 
+    // Number of goal facts that are not yet satisfied. Used as the A* heuristic, every planner
+    // action changes at most one fact and costs at least 1 so this never overestimates.
+    pub fn unsatisfied_count(&self, goal: &EmployeeGoapFacts) -> u32 {
+        let default_state = EmployeeGoapFacts::default();
+        let mut count = 0;
+        if goal.at_desk != default_state.at_desk && self.at_desk != goal.at_desk {
+            count += 1;
+        }
+        if goal.has_assigned_task != default_state.has_assigned_task && self.has_assigned_task != goal.has_assigned_task {
+            count += 1;
+        }
+        if goal.task_progress_u8 != default_state.task_progress_u8 && self.task_progress_u8 < goal.task_progress_u8 {
+            count += 1;
+        }
+        if goal.has_food != default_state.has_food && self.has_food != goal.has_food {
+            count += 1;
+        }
+        if goal.has_eaten != default_state.has_eaten && self.has_eaten != goal.has_eaten {
+            count += 1;
+        }
+        if goal.has_rested != default_state.has_rested && self.has_rested != goal.has_rested {
+            count += 1;
+        }
+        if goal.has_worked != default_state.has_worked && self.has_worked != goal.has_worked {
+            count += 1;
+        }
+        count
+    }
+
+    // Clears the goal completion facts, called when a new goal is adopted.
+    pub fn clear_goal_completion(&mut self) {
+        self.has_eaten = false;
+        self.has_rested = false;
+        self.has_worked = false;
+    }
+
     // Helper to get all non-default facts as a HashMap (useful for debugging/display)
     pub fn get_active_facts(&self) -> HashMap<&'static str, String> {
         let default_state = EmployeeGoapFacts::default();
@@ -68,6 +121,15 @@ impl EmployeeGoapFacts {
         if self.has_food != default_state.has_food {
             active_facts.insert("has_food", self.has_food.to_string());
         }
+        if self.has_eaten != default_state.has_eaten {
+            active_facts.insert("has_eaten", self.has_eaten.to_string());
+        }
+        if self.has_rested != default_state.has_rested {
+            active_facts.insert("has_rested", self.has_rested.to_string());
+        }
+        if self.has_worked != default_state.has_worked {
+            active_facts.insert("has_worked", self.has_worked.to_string());
+        }
         active_facts
     }
 }
@@ -81,22 +143,37 @@ pub struct CurrentGoal(pub GoalName);
 
 // --- Current Plan (Legion Component) ---
 // A sequence of game actions to achieve the current goal.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, Encode, Decode)]
 pub struct CurrentPlan(pub Vec<EmployeeGameAction>);
 
 // --- Employee Game Actions (Concrete Actions the employee can perform) ---
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Encode, Decode)]
 pub enum EmployeeGameAction {
     GoToDesk,
     GoToCafeteria,
     BuyFood,
     EatFood,
     TakeBreak,
-    CodeTask(Entity), // Task entity ID
+    CodeTask, // Works on whichever task is assigned to the employee
     Socialize,
     // Add more specific game actions here
 }
 
+impl EmployeeGameAction {
+    // How long the action takes to carry out, in quarter ticks.
+    pub fn duration_quarter_ticks(&self) -> u64 {
+        match self {
+            EmployeeGameAction::GoToDesk => 2,
+            EmployeeGameAction::GoToCafeteria => 2,
+            EmployeeGameAction::BuyFood => 1,
+            EmployeeGameAction::EatFood => 4,
+            EmployeeGameAction::TakeBreak => 4,
+            EmployeeGameAction::CodeTask => 4,
+            EmployeeGameAction::Socialize => 2,
+        }
+    }
+}
+
 // --- Planner Action (Blueprint for GOAP planner) ---
 // Defines preconditions, effects, and the associated game action.
 pub struct PlannerAction {
@@ -136,23 +213,26 @@ pub fn get_all_planner_actions() -> Vec<PlannerAction> {
             name: "EatFood",
             cost: 1,
             check_planner_preconditions: |s| s.has_food,
-            apply_planner_effects: |s| s.has_food = false,
+            apply_planner_effects: |s| {
+                s.has_food = false;
+                s.has_eaten = true;
+            },
             game_action_type: EmployeeGameAction::EatFood,
         },
         PlannerAction {
             name: "TakeBreak",
             cost: 2,
             check_planner_preconditions: |s| s.at_desk, // Assume breaks happen at desk
-            apply_planner_effects: |_| {}, // No GOAP fact changes for a simple break
+            apply_planner_effects: |s| s.has_rested = true,
             game_action_type: EmployeeGameAction::TakeBreak,
         },
-        // PlannerAction {
-        //     name: "CodeTask",
-        //     cost: 5, // Cost varies by task, simplified for now
-        //     check_planner_preconditions: |s| s.at_desk && s.has_assigned_task && s.task_progress_u8 < 100,
-        //     apply_planner_effects: |s| s.task_progress_u8 = 100, // Completes task
-        //     game_action_type: EmployeeGameAction::CodeTask(Entity::new(0,0)), // Placeholder task entity
-        // },
+        PlannerAction {
+            name: "CodeTask",
+            cost: 4, // One work session, actual progress is simulated by the work system
+            check_planner_preconditions: |s| s.at_desk && s.has_assigned_task && s.task_progress_u8 < 100,
+            apply_planner_effects: |s| s.has_worked = true,
+            game_action_type: EmployeeGameAction::CodeTask,
+        },
         PlannerAction {
             name: "Socialize",
             cost: 3,
@@ -1,3 +1,4 @@
 pub mod goal;
 pub mod consideration;
-pub mod goap;
\ No newline at end of file
+pub mod goap;
+pub mod planner;
\ No newline at end of file
@@ -0,0 +1,480 @@
+use crate::sim::ai::consideration::{get_all_goal_definitions, GoalName};
+use crate::sim::ai::goap::{
+    get_all_planner_actions, CurrentGoal, CurrentPlan, EmployeeGameAction, EmployeeGoapFacts,
+    PlannerAction,
+};
+use crate::sim::globals::{
+    PLANNER_MAX_NODES_PER_SEARCH, PLANNER_MAX_PLAN_DEPTH, PLANNER_NODE_BUDGET_PER_TICK,
+};
+use crate::sim::person::components::Person;
+use crate::sim::utils::debugging::DebugDisplayComponent;
+use bincode::{Decode, Encode};
+use legion::systems::CommandBuffer;
+use legion::world::SubWorld;
+use legion::{system, Entity, IntoQuery};
+use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap};
+use tracing::{debug, trace, warn};
+
+// --- Planned Goal (Legion Component) ---
+// The goal the entity's CurrentPlan was built for. When it differs from CurrentGoal the plan is stale.
+#[derive(Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize, Encode, Decode)]
+pub struct PlannedGoal(pub GoalName);
+
+/// Result of a single A* search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanOutcome {
+    /// A plan reaching the goal was found. An empty plan means the goal is already satisfied.
+    Found {
+        actions: Vec<EmployeeGameAction>,
+        cost: u32,
+        nodes_expanded: usize,
+    },
+    /// The search space was exhausted without reaching the goal.
+    NoPlan { nodes_expanded: usize },
+    /// The node budget ran out before the search could finish.
+    BudgetExhausted { nodes_expanded: usize },
+}
+
+impl PlanOutcome {
+    pub fn nodes_expanded(&self) -> usize {
+        match self {
+            PlanOutcome::Found { nodes_expanded, .. } => *nodes_expanded,
+            PlanOutcome::NoPlan { nodes_expanded } => *nodes_expanded,
+            PlanOutcome::BudgetExhausted { nodes_expanded } => *nodes_expanded,
+        }
+    }
+}
+
+struct SearchNode {
+    facts: EmployeeGoapFacts,
+    parent: Option<usize>,
+    action_index: Option<usize>,
+    cost: u32,
+    depth: usize,
+}
+
+// Entry in the open set. Ordered by estimated total cost, then by cost so far (deeper first on ties),
+// then by insertion order so the search is deterministic.
+#[derive(PartialEq, Eq)]
+struct OpenEntry {
+    estimated_total: u32,
+    cost: u32,
+    sequence: usize,
+    node_index: usize,
+}
+
+impl Ord for OpenEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.estimated_total
+            .cmp(&other.estimated_total)
+            .then_with(|| other.cost.cmp(&self.cost))
+            .then_with(|| self.sequence.cmp(&other.sequence))
+    }
+}
+
+impl PartialOrd for OpenEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Runs an A* search from `start` to a state satisfying `goal` over the given planner actions.
+///
+/// `max_nodes` bounds the number of expanded nodes and `max_depth` the length of the plan.
+pub fn plan(
+    start: &EmployeeGoapFacts,
+    goal: &EmployeeGoapFacts,
+    actions: &[PlannerAction],
+    max_nodes: usize,
+    max_depth: usize,
+) -> PlanOutcome {
+    let mut nodes = vec![SearchNode {
+        facts: start.clone(),
+        parent: None,
+        action_index: None,
+        cost: 0,
+        depth: 0,
+    }];
+    let mut best_cost: HashMap<EmployeeGoapFacts, u32> = HashMap::new();
+    best_cost.insert(start.clone(), 0);
+
+    let mut open = BinaryHeap::new();
+    let mut sequence = 0;
+    open.push(Reverse(OpenEntry {
+        estimated_total: start.unsatisfied_count(goal),
+        cost: 0,
+        sequence,
+        node_index: 0,
+    }));
+
+    let mut nodes_expanded = 0;
+    while let Some(Reverse(entry)) = open.pop() {
+        let node_index = entry.node_index;
+        let (facts, cost, depth) = {
+            let node = &nodes[node_index];
+            (node.facts.clone(), node.cost, node.depth)
+        };
+
+        // Skip stale entries, a cheaper path to the same state was found after this one was queued
+        if best_cost.get(&facts).is_some_and(|best| *best < cost) {
+            continue;
+        }
+
+        if facts.satisfies_goal(goal) {
+            return PlanOutcome::Found {
+                actions: reconstruct_plan(&nodes, node_index, actions),
+                cost,
+                nodes_expanded,
+            };
+        }
+
+        if nodes_expanded >= max_nodes {
+            return PlanOutcome::BudgetExhausted { nodes_expanded };
+        }
+        nodes_expanded += 1;
+
+        if depth >= max_depth {
+            continue;
+        }
+
+        for (action_index, action) in actions.iter().enumerate() {
+            if !(action.check_planner_preconditions)(&facts) {
+                continue;
+            }
+            let mut next_facts = facts.clone();
+            (action.apply_planner_effects)(&mut next_facts);
+            if next_facts == facts {
+                // Action has no effect on the planning state, it can never bring us closer to the goal
+                continue;
+            }
+
+            let next_cost = cost + action.cost;
+            if best_cost
+                .get(&next_facts)
+                .is_some_and(|best| *best <= next_cost)
+            {
+                continue;
+            }
+            best_cost.insert(next_facts.clone(), next_cost);
+
+            let estimated_total = next_cost + next_facts.unsatisfied_count(goal);
+            nodes.push(SearchNode {
+                facts: next_facts,
+                parent: Some(node_index),
+                action_index: Some(action_index),
+                cost: next_cost,
+                depth: depth + 1,
+            });
+            sequence += 1;
+            open.push(Reverse(OpenEntry {
+                estimated_total,
+                cost: next_cost,
+                sequence,
+                node_index: nodes.len() - 1,
+            }));
+        }
+    }
+
+    PlanOutcome::NoPlan { nodes_expanded }
+}
+
+fn reconstruct_plan(
+    nodes: &[SearchNode],
+    goal_index: usize,
+    actions: &[PlannerAction],
+) -> Vec<EmployeeGameAction> {
+    let mut plan = Vec::new();
+    let mut current = Some(goal_index);
+    while let Some(index) = current {
+        let node = &nodes[index];
+        if let Some(action_index) = node.action_index {
+            plan.push(actions[action_index].game_action_type.clone());
+        }
+        current = node.parent;
+    }
+    plan.reverse();
+    plan
+}
+
+/// Checks that `plan` can still be executed from `facts` and still reaches `goal`.
+pub fn is_plan_valid(
+    facts: &EmployeeGoapFacts,
+    goal: &EmployeeGoapFacts,
+    plan: &[EmployeeGameAction],
+    actions: &[PlannerAction],
+) -> bool {
+    let mut simulated = facts.clone();
+    for step in plan {
+        let Some(action) = find_planner_action(actions, step) else {
+            return false;
+        };
+        if !(action.check_planner_preconditions)(&simulated) {
+            return false;
+        }
+        (action.apply_planner_effects)(&mut simulated);
+    }
+    simulated.satisfies_goal(goal)
+}
+
+/// Looks up the planner blueprint for a concrete game action.
+pub fn find_planner_action<'a>(
+    actions: &'a [PlannerAction],
+    game_action: &EmployeeGameAction,
+) -> Option<&'a PlannerAction> {
+    actions.iter().find(|action| {
+        std::mem::discriminant(&action.game_action_type) == std::mem::discriminant(game_action)
+    })
+}
+
+// --- GOAP Planning System ---
+// Builds a CurrentPlan for every employee whose goal changed or whose plan no longer holds.
+// Runs after goal selection, employees that do not fit in the per tick node budget are picked up next tick.
+#[system]
+#[read_component(Person)]
+#[read_component(CurrentGoal)]
+#[read_component(EmployeeGoapFacts)]
+#[read_component(CurrentPlan)]
+#[read_component(PlannedGoal)]
+#[write_component(DebugDisplayComponent)]
+pub fn plan_goals(world: &mut SubWorld, cmd: &mut CommandBuffer) {
+    let goal_definitions = get_all_goal_definitions();
+    let planner_actions = get_all_planner_actions();
+    let mut remaining_budget = PLANNER_NODE_BUDGET_PER_TICK;
+
+    let mut query = <(
+        Entity,
+        &Person,
+        &CurrentGoal,
+        Option<&EmployeeGoapFacts>,
+        Option<&CurrentPlan>,
+        Option<&PlannedGoal>,
+        &mut DebugDisplayComponent,
+    )>::query();
+
+    for (entity, person, current_goal, goap_facts, current_plan, planned_goal, debug_display) in
+        query.iter_mut(world)
+    {
+        let Some(goal_definition) = goal_definitions
+            .iter()
+            .find(|definition| definition.name == current_goal.0)
+        else {
+            warn!("No goal definition found for {:?}", current_goal.0);
+            continue;
+        };
+
+        let mut facts = goap_facts.cloned().unwrap_or_default();
+        let goal_changed = planned_goal.is_none_or(|planned| planned.0 != current_goal.0);
+        let plan_still_valid = !goal_changed
+            && current_plan.is_some_and(|plan| {
+                is_plan_valid(
+                    &facts,
+                    &goal_definition.goap_target_facts,
+                    &plan.0,
+                    &planner_actions,
+                )
+            });
+
+        if plan_still_valid {
+            continue;
+        }
+
+        if remaining_budget == 0 {
+            trace!("Planner budget exhausted, deferring {}", person.name);
+            continue;
+        }
+
+        if goal_changed {
+            facts.clear_goal_completion();
+        }
+
+        let outcome = plan(
+            &facts,
+            &goal_definition.goap_target_facts,
+            &planner_actions,
+            PLANNER_MAX_NODES_PER_SEARCH.min(remaining_budget),
+            PLANNER_MAX_PLAN_DEPTH,
+        );
+        remaining_budget = remaining_budget.saturating_sub(outcome.nodes_expanded());
+
+        match outcome {
+            PlanOutcome::Found { actions, cost, .. } => {
+                debug!(
+                    "Planned {:?} for {} (cost {}): {:?}",
+                    current_goal.0, person.name, cost, actions
+                );
+                debug_display
+                    .entries
+                    .push(("Plan".to_string(), format!("{:?}", actions)));
+                cmd.add_component(*entity, CurrentPlan(actions));
+                cmd.add_component(*entity, PlannedGoal(current_goal.0.clone()));
+            }
+            PlanOutcome::NoPlan { .. } => {
+                warn!("No plan found for {:?} for {}", current_goal.0, person.name);
+                cmd.add_component(*entity, CurrentPlan::default());
+                cmd.add_component(*entity, PlannedGoal(current_goal.0.clone()));
+            }
+            PlanOutcome::BudgetExhausted { .. } => {
+                // Leave the stale plan and PlannedGoal in place so the entity is retried next tick
+                trace!("Planner node budget ran out for {}", person.name);
+                continue;
+            }
+        }
+
+        if goal_changed || goap_facts.is_none() {
+            cmd.add_component(*entity, facts);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn goal_facts(goal: GoalName) -> EmployeeGoapFacts {
+        get_all_goal_definitions()
+            .into_iter()
+            .find(|definition| definition.name == goal)
+            .unwrap()
+            .goap_target_facts
+    }
+
+    fn run(start: &EmployeeGoapFacts, goal: &EmployeeGoapFacts) -> PlanOutcome {
+        plan(
+            start,
+            goal,
+            &get_all_planner_actions(),
+            PLANNER_MAX_NODES_PER_SEARCH,
+            PLANNER_MAX_PLAN_DEPTH,
+        )
+    }
+
+    #[test]
+    fn eat_from_desk_goes_to_cafeteria_buys_and_eats() {
+        let start = EmployeeGoapFacts {
+            at_desk: true,
+            ..Default::default()
+        };
+        match run(&start, &goal_facts(GoalName::Eat)) {
+            PlanOutcome::Found { actions, cost, .. } => {
+                assert_eq!(
+                    actions,
+                    vec![
+                        EmployeeGameAction::GoToCafeteria,
+                        EmployeeGameAction::BuyFood,
+                        EmployeeGameAction::EatFood,
+                    ]
+                );
+                assert_eq!(cost, 6);
+            }
+            other => panic!("Expected a plan, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn eat_with_food_in_hand_only_eats() {
+        let start = EmployeeGoapFacts {
+            at_desk: true,
+            has_food: true,
+            ..Default::default()
+        };
+        match run(&start, &goal_facts(GoalName::Eat)) {
+            PlanOutcome::Found { actions, .. } => {
+                assert_eq!(actions, vec![EmployeeGameAction::EatFood]);
+            }
+            other => panic!("Expected a plan, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rest_away_from_desk_goes_to_desk_first() {
+        let start = EmployeeGoapFacts::default();
+        match run(&start, &goal_facts(GoalName::Rest)) {
+            PlanOutcome::Found { actions, .. } => {
+                assert_eq!(
+                    actions,
+                    vec![EmployeeGameAction::GoToDesk, EmployeeGameAction::TakeBreak]
+                );
+            }
+            other => panic!("Expected a plan, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn satisfied_goal_yields_empty_plan() {
+        let start = EmployeeGoapFacts::default();
+        match run(&start, &goal_facts(GoalName::DoNothing)) {
+            PlanOutcome::Found { actions, cost, .. } => {
+                assert!(actions.is_empty());
+                assert_eq!(cost, 0);
+            }
+            other => panic!("Expected an empty plan, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn unreachable_goal_reports_no_plan() {
+        // No planner action can assign a task
+        let goal = EmployeeGoapFacts {
+            has_assigned_task: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            run(&EmployeeGoapFacts::default(), &goal),
+            PlanOutcome::NoPlan { .. }
+        ));
+    }
+
+    #[test]
+    fn node_budget_is_respected() {
+        let start = EmployeeGoapFacts {
+            at_desk: true,
+            ..Default::default()
+        };
+        let outcome = plan(
+            &start,
+            &goal_facts(GoalName::Eat),
+            &get_all_planner_actions(),
+            1,
+            PLANNER_MAX_PLAN_DEPTH,
+        );
+        assert_eq!(outcome, PlanOutcome::BudgetExhausted { nodes_expanded: 1 });
+    }
+
+    #[test]
+    fn depth_limit_prevents_long_plans() {
+        let start = EmployeeGoapFacts {
+            at_desk: true,
+            ..Default::default()
+        };
+        let outcome = plan(
+            &start,
+            &goal_facts(GoalName::Eat),
+            &get_all_planner_actions(),
+            PLANNER_MAX_NODES_PER_SEARCH,
+            2,
+        );
+        assert!(matches!(outcome, PlanOutcome::NoPlan { .. }));
+    }
+
+    #[test]
+    fn plan_is_invalidated_when_facts_change() {
+        let actions = get_all_planner_actions();
+        let goal = goal_facts(GoalName::Eat);
+        let plan = vec![
+            EmployeeGameAction::GoToCafeteria,
+            EmployeeGameAction::BuyFood,
+            EmployeeGameAction::EatFood,
+        ];
+        let at_desk = EmployeeGoapFacts {
+            at_desk: true,
+            ..Default::default()
+        };
+        assert!(is_plan_valid(&at_desk, &goal, &plan, &actions));
+
+        // Already away from the desk, GoToCafeteria precondition no longer holds
+        let away = EmployeeGoapFacts::default();
+        assert!(!is_plan_valid(&away, &goal, &plan, &actions));
+    }
+}
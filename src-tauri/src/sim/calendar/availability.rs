@@ -274,6 +274,11 @@ impl MonthlyAvailability {
 
 
 
+pub const ALL_WEEKDAYS: [Weekday; 7] = [
+    Weekday::Monday, Weekday::Tuesday, Weekday::Wednesday,
+    Weekday::Thursday, Weekday::Friday, Weekday::Saturday, Weekday::Sunday
+];
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Weekday {
     Monday = 1,
@@ -291,7 +296,7 @@ pub enum AvailabilityState {
     Busy,   // 1 in bitset
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode)]
 pub struct AvailabilityBitSet {
     // Each bit represents one 15-minute tick (0 = free, 1 = busy)
     // MAX_TICKS_IN_MONTH bits = 336 bytes per month (4 weeks * 7 days * 96 ticks)
@@ -2,6 +2,7 @@ use bincode::{Decode, Encode};
 use crate::sim::sim_date::sim_date::SimDate;
 use serde::{Deserialize, Serialize};
 use crate::sim::calendar::components::recurring_event_template::RecurringEventTemplateId;
+use super::recurrence_pattern::Recurrence;
 use super::event_details::EventDetails;
 use super::calendar_event_id::CalendarEventId;
 
@@ -11,6 +12,8 @@ pub struct CalendarEvent {
     pub start_time: SimDate,
     pub details: EventDetails,
     pub template_id: Option<RecurringEventTemplateId>,
+    /// Recurrence of the event itself, `None` for one-off events and occurrences expanded from a template
+    pub recurrence: Option<Recurrence>,
 }
 
 impl CalendarEvent {
@@ -20,12 +23,16 @@ impl CalendarEvent {
     }
     
     pub fn is_recurring(&self)->bool{
-        self.template_id.is_some()
+        self.template_id.is_some() || self.recurrence.is_some()
     }
 
     /// Get all occurrences of this event within a date range
     /// Returns the start time of each occurrence, not every tick where the event is active
     pub fn get_occurrences(&self, start_date: SimDate, end_date: SimDate) -> Vec<SimDate> {
+        if let Some(recurrence) = &self.recurrence {
+            return recurrence.occurrences(self.start_time, start_date, end_date);
+        }
+
         let mut occurrences = Vec::new();
 
         // Single occurrence - check if it falls within the range
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use bincode::{Encode, Decode};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Encode, Decode, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Encode, Decode, Default)]
 pub struct CalendarEventId(pub u64);
 
 impl CalendarEventId {
@@ -9,3 +9,10 @@ impl CalendarEventId {
         Self(id)
     }
 }
+
+// Ids are handed out by the u32 counter of the calendar event registry, so they always fit.
+impl From<CalendarEventId> for u32 {
+    fn from(value: CalendarEventId) -> Self {
+        value.0 as u32
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use crate::sim::person::components::PersonId;
 use crate::sim::calendar::availability::{MonthlyAvailability, YearMonth};
 use crate::sim::sim_date::sim_date::SimDate;
@@ -11,8 +11,6 @@ use super::calendar_event_id::CalendarEventId;
 pub struct CalendarIndex {
     // Map from year-week to events that occur on that week
     pub events_by_week: BTreeMap<(u16, u8), Vec<CalendarEventId>>,
-    // Map from absolute tick to events at that time
-    pub events_by_tick: BTreeMap<u64, Vec<CalendarEventId>>,
     // Map from participant to their events
     pub events_by_participant: HashMap<PersonId, Vec<CalendarEventId>>,
     // All events by ID for quick lookup
@@ -22,11 +20,16 @@ pub struct CalendarIndex {
     pub availability_matrix: HashMap<PersonId, MonthlyAvailability>,
     // Spatial index for range queries
     pub time_ranges: IntervalTree,
+    // Occurrences are indexed for the current and next month only
+    pub window_start: SimDate,
+    pub window_end: SimDate,
 }
 
 impl CalendarIndex {
     pub fn new() -> Self {
-        Self::default()
+        let mut index = Self::default();
+        index.rebuild(SimDate::default());
+        index
     }
 
     pub fn ensure_months_for_current_and_next(&mut self, current_date: SimDate) {
@@ -38,33 +41,75 @@ impl CalendarIndex {
         }
     }
 
+    /// Whether `date` falls in the indexed window.
+    pub fn covers(&self, date: &SimDate) -> bool {
+        self.window_start <= *date && *date <= self.window_end
+    }
+
+    /// First and last date of the month of `current_date` and the month after it.
+    pub fn window_for(current_date: SimDate) -> (SimDate, SimDate) {
+        let current_month = YearMonth::from(&current_date);
+        let after_next_month = current_month.next_month().next_month();
+        let start = SimDate::month_start(current_month.year, current_month.month);
+        let end = SimDate::from(SimDate::month_start(after_next_month.year, after_next_month.month).to_tick() - 1);
+        (start, end)
+    }
+
+    /// Moves the indexed window to the month of `current_date` and the month after it, then re-indexes every event.
+    pub fn rebuild(&mut self, current_date: SimDate) {
+        (self.window_start, self.window_end) = Self::window_for(current_date);
+
+        let events: Vec<CalendarEvent> = self.events.drain().map(|(_, event)| event).collect();
+        self.events_by_week.clear();
+        self.events_by_participant.clear();
+        self.availability_matrix.clear();
+        self.time_ranges.clear();
+        self.ensure_months_for_current_and_next(current_date);
+        for event in events {
+            self.add_event(event);
+        }
+    }
+
+    /// Brings the index in line with `events`: new events are added, changed events re-indexed
+    /// and events that no longer exist removed.
+    pub fn sync<'a>(&mut self, events: impl IntoIterator<Item = &'a CalendarEvent>) {
+        let mut seen = HashSet::new();
+        for event in events {
+            seen.insert(event.id);
+            let changed = match self.events.get(&event.id) {
+                Some(indexed) => {
+                    indexed.start_time != event.start_time
+                        || indexed.details.duration_ticks != event.details.duration_ticks
+                        || indexed.recurrence != event.recurrence
+                        || indexed.details.participants != event.details.participants
+                }
+                None => true,
+            };
+            if changed {
+                self.remove_event(event.id);
+                self.add_event(event.clone());
+            }
+        }
+
+        let removed: Vec<CalendarEventId> = self.events.keys().filter(|id| !seen.contains(*id)).copied().collect();
+        for event_id in removed {
+            self.remove_event(event_id);
+        }
+    }
+
     pub fn add_event(&mut self, event: CalendarEvent) {
         let event_id = event.id;
 
-        // Get all occurrences for the next year (reasonable window)
-        let current_date = SimDate::default(); // You might want to pass current date
-        let end_date = SimDate {
-            year: current_date.year + 1,
-            week: current_date.week,
-            day: current_date.day,
-            quarter_tick: current_date.quarter_tick,
-        };
-
-        let occurrences = event.get_occurrences(event.start_time, end_date);
+        let occurrences = event.get_occurrences(self.window_start, self.window_end);
 
         for occurrence in occurrences {
             // Index by week
             let week_key = (occurrence.year, occurrence.week);
             self.events_by_week.entry(week_key).or_default().push(event_id);
 
-            // Index by absolute tick
             let start_tick = occurrence.to_tick();
             let end_tick = start_tick + event.details.duration_ticks as u64;
 
-            for tick_offset in 0..event.details.duration_ticks as u64 {
-                self.events_by_tick.entry(start_tick + tick_offset).or_default().push(event_id);
-            }
-
             // Update availability matrix for all participants
             for participant in &event.details.participants {
                 let availability = self.availability_matrix.entry(participant.person_id).or_insert_with(MonthlyAvailability::new);
@@ -91,10 +136,6 @@ impl CalendarIndex {
                 week_events.retain(|id| id != &event_id);
             }
 
-            for tick_events in self.events_by_tick.values_mut() {
-                tick_events.retain(|id| id != &event_id);
-            }
-
             for participant_events in self.events_by_participant.values_mut() {
                 participant_events.retain(|id| id != &event_id);
             }
@@ -102,15 +143,7 @@ impl CalendarIndex {
             self.time_ranges.remove_event(event_id.0);
 
             // Update availability matrix (set back to free)
-            let current_date = SimDate::default(); // You might want to pass current date
-            let end_date = SimDate {
-                year: current_date.year + 1,
-                week: current_date.week,
-                day: current_date.day,
-                quarter_tick: current_date.quarter_tick,
-            };
-
-            let occurrences = event.get_occurrences(event.start_time, end_date);
+            let occurrences = event.get_occurrences(self.window_start, self.window_end);
 
             for occurrence in occurrences {
                 for participant in &event.details.participants {
@@ -190,14 +223,22 @@ impl CalendarIndex {
             .unwrap_or_default()
     }
 
+    /// Events with an occurrence overlapping `[start_tick, end_tick)`, each event once.
+    pub fn events_overlapping(&self, start_tick: u64, end_tick: u64) -> Vec<&CalendarEvent> {
+        let mut event_ids = self.time_ranges.query_overlapping(start_tick, end_tick);
+        event_ids.sort_unstable();
+        event_ids.dedup();
+        event_ids.into_iter()
+            .filter_map(|event_id| self.events.get(&CalendarEventId::new(event_id)))
+            .collect()
+    }
+
     pub fn events_at_time(&self, time: SimDate) -> Vec<&CalendarEvent> {
-        let tick = time.to_tick();
-        self.events_by_tick.get(&tick)
-            .map(|event_ids| {
-                event_ids.iter()
-                    .filter_map(|id| self.events.get(id))
-                    .collect()
-            })
-            .unwrap_or_default()
+        let mut event_ids = self.time_ranges.query_point(time.to_tick());
+        event_ids.sort_unstable();
+        event_ids.dedup();
+        event_ids.into_iter()
+            .filter_map(|event_id| self.events.get(&CalendarEventId::new(event_id)))
+            .collect()
     }
 }
@@ -1,21 +0,0 @@
-use legion::World;
-use crate::sim::sim_date::sim_date::SimDate;
-
-// ECS System for calendar management
-pub struct CalendarSystem;
-
-impl CalendarSystem {
-    pub fn update_calendar_indices(world: &mut World) {
-        // This would rebuild indices when calendars change
-        // Implementation depends on your change detection strategy
-    }
-
-    pub fn process_recurring_events(world: &mut World, current_date: SimDate) {
-        // Handle any recurring event logic that needs processing
-    }
-
-    pub fn advance_month(world: &mut World, new_current_date: SimDate) {
-        // Create bitsets for the new next month
-        // This would be called at the beginning of each new month
-    }
-}
@@ -31,6 +31,7 @@ pub fn schedule_meeting(
             priority: EventPriority::Normal,
         },
         template_id: None,
+        recurrence: None,
     };
 
     company_calendar.events.push(event);
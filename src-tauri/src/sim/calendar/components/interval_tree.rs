@@ -1,24 +1,353 @@
-// For complex range queries and overlaps
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+// For complex range queries and overlaps.
+// AVL tree ordered by (start, end, event_id), each node also keeps the largest end tick of its subtree
+// so overlap queries can skip whole subtrees. Intervals are half open: [start, end).
 #[derive(Debug, Default)]
 pub struct IntervalTree {
-    // Simplified interval tree - in practice you'd want a proper implementation
-    intervals: Vec<(u64, u64, u64)>, // (start_tick, end_tick, event_id)
+    root: Option<Box<Node>>,
+    // Intervals inserted for each event, used for deletion by event id
+    by_event: HashMap<u64, Vec<(u64, u64)>>,
+    len: usize,
+}
+
+#[derive(Debug)]
+struct Node {
+    start: u64,
+    end: u64,
+    event_id: u64,
+    max_end: u64,
+    height: u8,
+    left: Option<Box<Node>>,
+    right: Option<Box<Node>>,
+}
+
+impl Node {
+    fn new(start: u64, end: u64, event_id: u64) -> Self {
+        Self {
+            start,
+            end,
+            event_id,
+            max_end: end,
+            height: 1,
+            left: None,
+            right: None,
+        }
+    }
+
+    fn key(&self) -> (u64, u64, u64) {
+        (self.start, self.end, self.event_id)
+    }
+
+    fn balance(&self) -> i16 {
+        height(&self.left) as i16 - height(&self.right) as i16
+    }
+
+    fn update(&mut self) {
+        self.height = 1 + height(&self.left).max(height(&self.right));
+        self.max_end = self.end.max(max_end(&self.left)).max(max_end(&self.right));
+    }
+}
+
+fn height(node: &Option<Box<Node>>) -> u8 {
+    node.as_ref().map_or(0, |n| n.height)
+}
+
+fn max_end(node: &Option<Box<Node>>) -> u64 {
+    node.as_ref().map_or(0, |n| n.max_end)
+}
+
+fn rotate_right(mut node: Box<Node>) -> Box<Node> {
+    let mut pivot = node.left.take().expect("rotate_right needs a left child");
+    node.left = pivot.right.take();
+    node.update();
+    pivot.right = Some(node);
+    pivot.update();
+    pivot
+}
+
+fn rotate_left(mut node: Box<Node>) -> Box<Node> {
+    let mut pivot = node.right.take().expect("rotate_left needs a right child");
+    node.right = pivot.left.take();
+    node.update();
+    pivot.left = Some(node);
+    pivot.update();
+    pivot
+}
+
+fn rebalance(mut node: Box<Node>) -> Box<Node> {
+    node.update();
+    let balance = node.balance();
+    if balance > 1 {
+        if node.left.as_ref().is_some_and(|left| left.balance() < 0) {
+            node.left = node.left.take().map(rotate_left);
+        }
+        return rotate_right(node);
+    }
+    if balance < -1 {
+        if node.right.as_ref().is_some_and(|right| right.balance() > 0) {
+            node.right = node.right.take().map(rotate_right);
+        }
+        return rotate_left(node);
+    }
+    node
+}
+
+fn insert_node(node: Option<Box<Node>>, new: Box<Node>) -> Box<Node> {
+    let Some(mut node) = node else {
+        return new;
+    };
+    if new.key() < node.key() {
+        node.left = Some(insert_node(node.left.take(), new));
+    } else {
+        node.right = Some(insert_node(node.right.take(), new));
+    }
+    rebalance(node)
+}
+
+/// Detaches the leftmost node of the subtree, returns it together with what is left of the subtree.
+fn take_min(mut node: Box<Node>) -> (Box<Node>, Option<Box<Node>>) {
+    match node.left.take() {
+        None => {
+            let rest = node.right.take();
+            (node, rest)
+        }
+        Some(left) => {
+            let (min, rest) = take_min(left);
+            node.left = rest;
+            (min, Some(rebalance(node)))
+        }
+    }
+}
+
+/// Removes one node with the given key, returns the new subtree and whether a node was removed.
+fn remove_node(node: Option<Box<Node>>, key: (u64, u64, u64)) -> (Option<Box<Node>>, bool) {
+    let Some(mut node) = node else {
+        return (None, false);
+    };
+    let removed = match key.cmp(&node.key()) {
+        Ordering::Less => {
+            let (left, removed) = remove_node(node.left.take(), key);
+            node.left = left;
+            removed
+        }
+        Ordering::Greater => {
+            let (right, removed) = remove_node(node.right.take(), key);
+            node.right = right;
+            removed
+        }
+        Ordering::Equal => {
+            return match (node.left.take(), node.right.take()) {
+                (None, None) => (None, true),
+                (Some(child), None) | (None, Some(child)) => (Some(child), true),
+                (Some(left), Some(right)) => {
+                    let (mut successor, rest) = take_min(right);
+                    successor.left = Some(left);
+                    successor.right = rest;
+                    (Some(rebalance(successor)), true)
+                }
+            };
+        }
+    };
+    (Some(rebalance(node)), removed)
+}
+
+fn collect_overlapping(node: &Option<Box<Node>>, start: u64, end: u64, out: &mut Vec<u64>) {
+    let Some(node) = node else {
+        return;
+    };
+    // Nothing in this subtree ends after the query starts
+    if node.max_end <= start {
+        return;
+    }
+    collect_overlapping(&node.left, start, end, out);
+    // Everything to the right starts at or after this node, so past the query end there is nothing left to find
+    if node.start >= end {
+        return;
+    }
+    if node.end > start {
+        out.push(node.event_id);
+    }
+    collect_overlapping(&node.right, start, end, out);
 }
 
 impl IntervalTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
     pub fn insert(&mut self, start: u64, end: u64, event_id: u64) {
-        self.intervals.push((start, end, event_id));
-        // In a real implementation, you'd maintain a balanced tree structure
+        self.root = Some(insert_node(self.root.take(), Box::new(Node::new(start, end, event_id))));
+        self.by_event.entry(event_id).or_default().push((start, end));
+        self.len += 1;
     }
 
+    /// Ids of the events with an interval overlapping `[start, end)`, once per overlapping interval.
+    /// Runs in O(log n + k) for k results.
     pub fn query_overlapping(&self, start: u64, end: u64) -> Vec<u64> {
-        self.intervals.iter()
-            .filter(|(s, e, _)| *s < end && *e > start)
-            .map(|(_, _, id)| *id)
-            .collect()
+        let mut out = Vec::new();
+        collect_overlapping(&self.root, start, end, &mut out);
+        out
+    }
+
+    /// Ids of the events with an interval containing `tick`.
+    pub fn query_point(&self, tick: u64) -> Vec<u64> {
+        self.query_overlapping(tick, tick.saturating_add(1))
     }
 
+    /// Removes every interval of the event.
     pub fn remove_event(&mut self, event_id: u64) {
-        self.intervals.retain(|(_, _, id)| *id != event_id);
+        let Some(intervals) = self.by_event.remove(&event_id) else {
+            return;
+        };
+        for (start, end) in intervals {
+            let (root, removed) = remove_node(self.root.take(), (start, end, event_id));
+            self.root = root;
+            if removed {
+                self.len -= 1;
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    /// The original linear implementation, used as the reference.
+    #[derive(Default)]
+    struct NaiveIntervals {
+        intervals: Vec<(u64, u64, u64)>,
+    }
+
+    impl NaiveIntervals {
+        fn query_overlapping(&self, start: u64, end: u64) -> Vec<u64> {
+            self.intervals
+                .iter()
+                .filter(|(s, e, _)| *s < end && *e > start)
+                .map(|(_, _, id)| *id)
+                .collect()
+        }
+    }
+
+    fn sorted(mut ids: Vec<u64>) -> Vec<u64> {
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Checks the AVL balance and the max end augmentation, returns the height of the subtree.
+    fn check_invariants(node: &Option<Box<Node>>) -> u8 {
+        let Some(node) = node else {
+            return 0;
+        };
+        let left = check_invariants(&node.left);
+        let right = check_invariants(&node.right);
+        assert!((left as i16 - right as i16).abs() <= 1, "subtree is unbalanced");
+        assert_eq!(node.height, 1 + left.max(right));
+        assert_eq!(node.max_end, node.end.max(max_end(&node.left)).max(max_end(&node.right)));
+        if let Some(l) = &node.left {
+            assert!(l.key() <= node.key());
+        }
+        if let Some(r) = &node.right {
+            assert!(r.key() >= node.key());
+        }
+        node.height
+    }
+
+    #[test]
+    fn overlap_is_half_open() {
+        let mut tree = IntervalTree::new();
+        tree.insert(10, 20, 1);
+        tree.insert(20, 30, 2);
+        assert_eq!(tree.query_overlapping(15, 20), vec![1]);
+        assert_eq!(sorted(tree.query_overlapping(19, 21)), vec![1, 2]);
+        assert!(tree.query_overlapping(30, 40).is_empty());
+        assert_eq!(tree.query_point(20), vec![2]);
+        assert!(tree.query_point(9).is_empty());
+    }
+
+    #[test]
+    fn removing_an_event_removes_all_its_intervals() {
+        let mut tree = IntervalTree::new();
+        for week in 0..10 {
+            tree.insert(week * 672, week * 672 + 4, 7);
+            tree.insert(week * 672 + 2, week * 672 + 6, 8);
+        }
+        assert_eq!(tree.len(), 20);
+        tree.remove_event(7);
+        assert_eq!(tree.len(), 10);
+        assert!(tree.query_overlapping(0, 10 * 672).iter().all(|id| *id == 8));
+        tree.remove_event(7);
+        assert_eq!(tree.len(), 10);
+        check_invariants(&tree.root);
+    }
+
+    #[test]
+    fn matches_naive_implementation_on_random_operations() {
+        for seed in 0..20 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut tree = IntervalTree::new();
+            let mut naive = NaiveIntervals::default();
+
+            for _ in 0..400 {
+                match rng.random_range(0..10) {
+                    0..=5 => {
+                        let start = rng.random_range(0..2_000);
+                        let end = start + rng.random_range(0..100);
+                        let id = rng.random_range(0..60);
+                        tree.insert(start, end, id);
+                        naive.intervals.push((start, end, id));
+                    }
+                    6 => {
+                        let id = rng.random_range(0..60);
+                        tree.remove_event(id);
+                        naive.intervals.retain(|(_, _, i)| *i != id);
+                    }
+                    _ => {
+                        let start = rng.random_range(0..2_100);
+                        let end = start + rng.random_range(0..300);
+                        assert_eq!(
+                            sorted(tree.query_overlapping(start, end)),
+                            sorted(naive.query_overlapping(start, end)),
+                            "seed {seed}, query [{start}, {end})"
+                        );
+                        let tick = rng.random_range(0..2_100);
+                        assert_eq!(
+                            sorted(tree.query_point(tick)),
+                            sorted(naive.query_overlapping(tick, tick + 1)),
+                            "seed {seed}, point {tick}"
+                        );
+                    }
+                }
+                assert_eq!(tree.len(), naive.intervals.len());
+            }
+            check_invariants(&tree.root);
+        }
+    }
+
+    #[test]
+    fn stays_balanced_with_sorted_inserts() {
+        let mut tree = IntervalTree::new();
+        for i in 0..1_024 {
+            tree.insert(i, i + 1, i);
+        }
+        // A perfectly balanced tree of 1024 nodes has height 11, AVL allows ~1.44x that
+        assert!(check_invariants(&tree.root) <= 16);
+        assert_eq!(tree.query_point(512), vec![512]);
     }
 }
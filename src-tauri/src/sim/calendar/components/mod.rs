@@ -8,7 +8,6 @@ pub mod personal_calendar;
 pub mod company_calendar;
 pub mod interval_tree;
 pub mod calendar_index;
-pub mod calendar_system;
 pub mod helpers;
 mod sim_date_extensions;
 pub mod event_details;
@@ -16,7 +15,7 @@ pub mod recurring_event_template;
 pub mod calendar_event_id;
 pub mod calendar_event_registry;
 
-pub use recurrence_pattern::RecurrencePattern;
+pub use recurrence_pattern::{Recurrence, RecurrenceEnd, RecurrenceException, RecurrencePattern};
 pub use attendance_status::AttendanceStatus;
 pub use calendar_event_attendee::CalendarEventAttendee;
 pub use event_type::EventType;
@@ -26,7 +25,6 @@ pub use personal_calendar::PersonalCalendar;
 pub use company_calendar::CompanyCalendar;
 pub use interval_tree::IntervalTree;
 pub use calendar_index::CalendarIndex;
-pub use calendar_system::CalendarSystem;
 pub use helpers::schedule_meeting;
 pub use event_details::EventDetails;
 pub use recurring_event_template::RecurringEventTemplate;
@@ -76,4 +74,103 @@ mod tests {
         let start_of_month2 = SimDate { year: 1, week: 5, day: 1, quarter_tick: 1 };
         assert!(!availability.is_free(start_of_month2, 2)); // Remaining ticks from cross-month event
     }
+
+    fn weekly_standup(start_time: SimDate, recurrence: Option<Recurrence>) -> CalendarEvent {
+        CalendarEvent {
+            id: CalendarEventId::new(1),
+            start_time,
+            details: EventDetails {
+                title: "Standup".to_string(),
+                duration_ticks: 2,
+                participants: vec![CalendarEventAttendee {
+                    person_id: crate::sim::person::components::PersonId(7),
+                    status: AttendanceStatus::Accepted,
+                }],
+                event_type: EventType::Meeting,
+                priority: EventPriority::Normal,
+            },
+            template_id: None,
+            recurrence,
+        }
+    }
+
+    #[test]
+    fn registry_sync_hands_out_ids_after_the_loaded_events() {
+        use crate::sim::calendar::systems::sync_registry_from_calendar_event_system;
+        use legion::{Resources, Schedule, World};
+        use std::sync::Arc;
+
+        let first = SimDate { year: 1, week: 1, day: 1, quarter_tick: 37 };
+        let mut world = World::default();
+        for id in [1, 4] {
+            world.push((CalendarEvent { id: CalendarEventId::new(id), ..weekly_standup(first, None) },));
+        }
+        let registry = Arc::new(CalendarEventRegistry::new());
+        let mut resources = Resources::default();
+        resources.insert(Arc::clone(&registry));
+
+        Schedule::builder()
+            .add_system(sync_registry_from_calendar_event_system())
+            .build()
+            .execute(&mut world, &mut resources);
+
+        assert!(registry.get_entity_from_id(&CalendarEventId::new(4)).is_some());
+        assert_eq!(registry.generate_id(), 5);
+    }
+
+    #[test]
+    fn recurring_events_are_indexed_for_current_and_next_month() {
+        let first = SimDate { year: 1, week: 1, day: 1, quarter_tick: 37 };
+        let mut index = CalendarIndex::new();
+        index.add_event(weekly_standup(first, Some(Recurrence::new(RecurrencePattern::Weekly))));
+
+        // Eight weeks in the window, nothing from month 3 onwards
+        assert_eq!(index.time_ranges.len(), 8);
+        assert_eq!(index.events_at_time(SimDate { year: 1, week: 8, day: 1, quarter_tick: 38 }).len(), 1);
+        assert!(index.events_at_time(SimDate { year: 1, week: 9, day: 1, quarter_tick: 37 }).is_empty());
+
+        // Moving into month 2 brings month 3 into the window
+        index.rebuild(SimDate { year: 1, week: 5, day: 1, quarter_tick: 1 });
+        assert_eq!(index.time_ranges.len(), 8);
+        assert_eq!(index.events_at_time(SimDate { year: 1, week: 9, day: 1, quarter_tick: 37 }).len(), 1);
+        assert!(!index.is_person_free(
+            crate::sim::person::components::PersonId(7),
+            SimDate { year: 1, week: 12, day: 1, quarter_tick: 37 },
+            1
+        ));
+
+        index.sync(std::iter::empty());
+        assert!(index.time_ranges.is_empty());
+        assert!(index.events.is_empty());
+    }
+
+    #[test]
+    fn templates_expand_each_occurrence_once() {
+        let first = SimDate { year: 1, week: 1, day: 1, quarter_tick: 37 };
+        let standup = weekly_standup(first, None);
+        let mut template = RecurringEventTemplate {
+            id: recurring_event_template::RecurringEventTemplateId(3),
+            start_date: first,
+            recurrence: Recurrence::new(RecurrencePattern::Weekdays)
+                .with_exception(RecurrenceException::Skip(SimDate { year: 1, week: 1, day: 3, quarter_tick: 37 })),
+            details: standup.details,
+            expanded_until: None,
+        };
+        let mut next_id = 0;
+        let mut id = || {
+            next_id += 1;
+            CalendarEventId::new(next_id)
+        };
+
+        let (_, window_end) = CalendarIndex::window_for(first);
+        let events = template.expand_until(window_end, &mut id);
+        assert_eq!(events.len(), 8 * 5 - 1);
+        assert!(events.iter().all(|event| event.template_id == Some(template.id) && event.recurrence.is_none()));
+        assert!(template.expand_until(window_end, &mut id).is_empty());
+
+        let (_, next_window_end) = CalendarIndex::window_for(SimDate { year: 1, week: 5, day: 1, quarter_tick: 1 });
+        let events = template.expand_until(next_window_end, &mut id);
+        assert_eq!(events.len(), 4 * 5);
+        assert_eq!(events[0].start_time, SimDate { year: 1, week: 9, day: 1, quarter_tick: 37 });
+    }
 }
@@ -1,6 +1,10 @@
+use bincode::{Decode, Encode};
 use serde::{Deserialize, Serialize};
+use crate::sim::sim_date::sim_date::{SimDate, MAX_TICK};
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+const TICKS_PER_WEEK: u64 = 7 * SimDate::QUARTER_TICKS_PER_DAY;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Encode, Decode)]
 pub enum RecurrencePattern {
     None,
     Daily,
@@ -9,3 +13,217 @@ pub enum RecurrencePattern {
     Custom { days_of_week: Vec<u8> }, // 1=Monday, 7=Sunday
     EveryNWeeks { n: u8, day_of_week: u8 }, // Every N weeks on specific day
 }
+
+impl RecurrencePattern {
+    /// Whether a series starting at `first` has an occurrence on the day of `date`.
+    /// `date` is expected to be on or after `first`.
+    pub fn occurs_on(&self, first: &SimDate, date: &SimDate) -> bool {
+        match self {
+            RecurrencePattern::None => first.to_tick() / SimDate::QUARTER_TICKS_PER_DAY == date.to_tick() / SimDate::QUARTER_TICKS_PER_DAY,
+            RecurrencePattern::Daily => true,
+            RecurrencePattern::Weekly => date.day == first.day,
+            RecurrencePattern::Weekdays => date.day <= 5,
+            RecurrencePattern::Custom { days_of_week } => days_of_week.contains(&date.day),
+            RecurrencePattern::EveryNWeeks { n, day_of_week } => {
+                let weeks_since_first = date.to_tick() / TICKS_PER_WEEK - first.to_tick() / TICKS_PER_WEEK;
+                date.day == *day_of_week && weeks_since_first.is_multiple_of((*n).max(1) as u64)
+            }
+        }
+    }
+}
+
+/// When a recurring series stops.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, Encode, Decode)]
+pub enum RecurrenceEnd {
+    #[default]
+    Never,
+    /// No occurrences after this date
+    Until(SimDate),
+    /// Only the first N occurrences, skipped occurrences still count
+    Count(u32),
+}
+
+/// Change to a single occurrence of a series, identified by the date it would have started on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Encode, Decode)]
+pub enum RecurrenceException {
+    Skip(SimDate),
+    Move { from: SimDate, to: SimDate },
+}
+
+impl RecurrenceException {
+    pub fn original(&self) -> SimDate {
+        match self {
+            RecurrenceException::Skip(date) => *date,
+            RecurrenceException::Move { from, .. } => *from,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Encode, Decode)]
+pub struct Recurrence {
+    pub pattern: RecurrencePattern,
+    pub end: RecurrenceEnd,
+    pub exceptions: Vec<RecurrenceException>,
+}
+
+impl Recurrence {
+    pub fn new(pattern: RecurrencePattern) -> Self {
+        Self {
+            pattern,
+            end: RecurrenceEnd::Never,
+            exceptions: Vec::new(),
+        }
+    }
+
+    pub fn with_end(mut self, end: RecurrenceEnd) -> Self {
+        self.end = end;
+        self
+    }
+
+    pub fn with_exception(mut self, exception: RecurrenceException) -> Self {
+        self.exceptions.push(exception);
+        self
+    }
+
+    /// Start dates of the series from `first` up to `until` (inclusive) before exceptions are applied.
+    /// Every occurrence starts at the same time of day as `first`.
+    pub fn series(&self, first: SimDate, until: SimDate) -> Vec<SimDate> {
+        let last = match self.end {
+            RecurrenceEnd::Until(end) => end.min(until),
+            _ => until,
+        };
+        let last_tick = last.to_tick().min(MAX_TICK);
+
+        let mut dates = Vec::new();
+        let mut tick = first.to_tick();
+        while tick <= last_tick {
+            let date = SimDate::from(tick);
+            if self.pattern.occurs_on(&first, &date) {
+                if let RecurrenceEnd::Count(count) = self.end {
+                    if dates.len() as u32 >= count {
+                        break;
+                    }
+                }
+                dates.push(date);
+            }
+            if self.pattern == RecurrencePattern::None {
+                break;
+            }
+            tick += SimDate::QUARTER_TICKS_PER_DAY;
+        }
+        dates
+    }
+
+    /// Start dates of the occurrences between `from` and `to` (inclusive), with exceptions applied, oldest first.
+    pub fn occurrences(&self, first: SimDate, from: SimDate, to: SimDate) -> Vec<SimDate> {
+        // An occurrence moved into the range can originally start after it
+        let last_original = self
+            .exceptions
+            .iter()
+            .filter_map(|exception| match exception {
+                RecurrenceException::Move { from: original, .. } => Some(*original),
+                RecurrenceException::Skip(_) => None,
+            })
+            .fold(to, SimDate::max);
+
+        let mut dates: Vec<SimDate> = self
+            .series(first, last_original)
+            .into_iter()
+            .filter_map(|original| {
+                match self.exceptions.iter().find(|exception| exception.original() == original) {
+                    Some(RecurrenceException::Skip(_)) => None,
+                    Some(RecurrenceException::Move { to: moved, .. }) => Some(*moved),
+                    None => Some(original),
+                }
+            })
+            .filter(|date| *date >= from && *date <= to)
+            .collect();
+        dates.sort();
+        dates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: u16, week: u8, day: u8, quarter_tick: u8) -> SimDate {
+        SimDate { year, week, day, quarter_tick }
+    }
+
+    #[test]
+    fn weekdays_cross_month_boundary() {
+        let first = date(1, 4, 4, 37);
+        let recurrence = Recurrence::new(RecurrencePattern::Weekdays);
+        let dates = recurrence.occurrences(first, first, date(1, 5, 7, 96));
+        assert_eq!(
+            dates,
+            vec![
+                date(1, 4, 4, 37),
+                date(1, 4, 5, 37),
+                date(1, 5, 1, 37),
+                date(1, 5, 2, 37),
+                date(1, 5, 3, 37),
+                date(1, 5, 4, 37),
+                date(1, 5, 5, 37),
+            ]
+        );
+        assert_eq!(dates[2].month(), 2);
+    }
+
+    #[test]
+    fn every_n_weeks_crosses_year_boundary() {
+        let first = date(1, 51, 2, 41);
+        let recurrence = Recurrence::new(RecurrencePattern::EveryNWeeks { n: 2, day_of_week: 2 });
+        let dates = recurrence.occurrences(first, first, date(2, 4, 7, 96));
+        assert_eq!(dates, vec![date(1, 51, 2, 41), date(2, 1, 2, 41), date(2, 3, 2, 41)]);
+    }
+
+    #[test]
+    fn custom_days_only_match_listed_days() {
+        let first = date(1, 1, 1, 1);
+        let recurrence = Recurrence::new(RecurrencePattern::Custom { days_of_week: vec![2, 6] });
+        let dates = recurrence.occurrences(first, first, date(1, 2, 7, 96));
+        assert_eq!(dates.len(), 4);
+        assert!(dates.iter().all(|d| d.day == 2 || d.day == 6));
+    }
+
+    #[test]
+    fn end_conditions_stop_the_series() {
+        let first = date(1, 1, 1, 37);
+        let window_end = date(1, 10, 7, 96);
+
+        let until = Recurrence::new(RecurrencePattern::Weekly).with_end(RecurrenceEnd::Until(date(1, 3, 1, 37)));
+        assert_eq!(until.occurrences(first, first, window_end).len(), 3);
+
+        let count = Recurrence::new(RecurrencePattern::Daily).with_end(RecurrenceEnd::Count(4));
+        assert_eq!(count.occurrences(first, first, window_end).len(), 4);
+
+        // Occurrences before the range still use up the count
+        assert_eq!(count.occurrences(first, date(1, 1, 3, 1), window_end).len(), 2);
+    }
+
+    #[test]
+    fn exceptions_skip_and_move_single_occurrences() {
+        let first = date(1, 1, 1, 37);
+        let recurrence = Recurrence::new(RecurrencePattern::Weekly)
+            .with_exception(RecurrenceException::Skip(date(1, 2, 1, 37)))
+            .with_exception(RecurrenceException::Move { from: date(1, 3, 1, 37), to: date(1, 3, 2, 45) })
+            // Moved from outside the range into it
+            .with_exception(RecurrenceException::Move { from: date(1, 6, 1, 37), to: date(1, 4, 5, 37) });
+
+        let dates = recurrence.occurrences(first, first, date(1, 4, 7, 96));
+        assert_eq!(
+            dates,
+            vec![date(1, 1, 1, 37), date(1, 3, 2, 45), date(1, 4, 1, 37), date(1, 4, 5, 37)]
+        );
+    }
+
+    #[test]
+    fn single_events_occur_once() {
+        let first = date(1, 2, 3, 50);
+        let recurrence = Recurrence::new(RecurrencePattern::None);
+        assert_eq!(recurrence.occurrences(first, date(1, 1, 1, 1), date(1, 52, 7, 96)), vec![first]);
+        assert!(recurrence.occurrences(first, date(1, 3, 1, 1), date(1, 52, 7, 96)).is_empty());
+    }
+}
@@ -1,7 +1,7 @@
 use bincode::{Decode, Encode};
 use serde::{Deserialize, Serialize};
 use crate::sim::sim_date::sim_date::SimDate;
-use super::recurrence_pattern::RecurrencePattern;
+use super::recurrence_pattern::Recurrence;
 use super::event_details::EventDetails;
 use super::calendar_event::CalendarEvent;
 use super::calendar_event_id::CalendarEventId;
@@ -9,58 +9,49 @@ use super::calendar_event_id::CalendarEventId;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Hash, Encode, Decode)]
 pub struct RecurringEventTemplateId(pub u64);
-#[derive(Debug, Clone, Serialize, Deserialize)]
+
+/// A series of events that gets expanded into one concrete [`CalendarEvent`] per occurrence.
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode)]
 pub struct RecurringEventTemplate {
     pub id: RecurringEventTemplateId,
     pub start_date: SimDate,
-    pub end_date: SimDate,
-    pub recurrence: RecurrencePattern,
+    pub recurrence: Recurrence,
     pub details: EventDetails,
+    /// Occurrences up to this date have already been expanded into events
+    pub expanded_until: Option<SimDate>,
 }
 
 impl RecurringEventTemplate {
-    pub fn expand(&self, start_date: SimDate, end_date: SimDate) -> Vec<CalendarEvent> {
-        let mut occurrences = Vec::new();
-        let mut current_date = self.start_date;
-
-        while current_date <= self.end_date && current_date <= end_date {
-            if current_date >= start_date {
-                let event = CalendarEvent {
-                    id: CalendarEventId::new(rand::random()),
-                    start_time: current_date,
-                    details: self.details.clone(),
-                    template_id: Some(self.id),
-                };
-                occurrences.push(event);
-            }
+    /// Creates an event for every occurrence between `start_date` and `end_date` (inclusive).
+    pub fn expand<F>(&self, start_date: SimDate, end_date: SimDate, mut next_id: F) -> Vec<CalendarEvent>
+    where
+        F: FnMut() -> CalendarEventId,
+    {
+        self.recurrence
+            .occurrences(self.start_date, start_date, end_date)
+            .into_iter()
+            .map(|start_time| CalendarEvent {
+                id: next_id(),
+                start_time,
+                details: self.details.clone(),
+                template_id: Some(self.id),
+                recurrence: None,
+            })
+            .collect()
+    }
 
-            current_date = match self.recurrence {
-                RecurrencePattern::None => break,
-                RecurrencePattern::Daily => SimDate::from(current_date.to_tick() + 96),
-                RecurrencePattern::Weekly => SimDate::from(current_date.to_tick() + (7 * 96)),
-                RecurrencePattern::Weekdays => {
-                    let mut next_date = SimDate::from(current_date.to_tick() + 96);
-                    while next_date.day > 5 {
-                        next_date = SimDate::from(next_date.to_tick() + 96);
-                    }
-                    next_date
-                }
-                RecurrencePattern::Custom { ref days_of_week } => {
-                    let mut next_date = SimDate::from(current_date.to_tick() + 96);
-                    while !days_of_week.contains(&next_date.day) {
-                        next_date = SimDate::from(next_date.to_tick() + 96);
-                    }
-                    next_date
-                }
-                RecurrencePattern::EveryNWeeks { n, day_of_week } => {
-                    let mut next_date = SimDate::from(current_date.to_tick() + (n as u64 * 7 * 96));
-                    while next_date.day != day_of_week {
-                        next_date = SimDate::from(next_date.to_tick() + 96);
-                    }
-                    next_date
-                }
-            };
-        }
-        occurrences
+    /// Expands the occurrences not expanded yet, up to `end_date`.
+    pub fn expand_until<F>(&mut self, end_date: SimDate, next_id: F) -> Vec<CalendarEvent>
+    where
+        F: FnMut() -> CalendarEventId,
+    {
+        let start_date = match self.expanded_until {
+            Some(expanded_until) if expanded_until >= end_date => return Vec::new(),
+            Some(expanded_until) => SimDate::from(expanded_until.to_tick() + 1),
+            None => self.start_date,
+        };
+        let events = self.expand(start_date, end_date, next_id);
+        self.expanded_until = Some(end_date);
+        events
     }
 }
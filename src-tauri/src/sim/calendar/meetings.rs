@@ -0,0 +1,254 @@
+use crate::action_queues::thought_manager::ThoughtCommand;
+use crate::integrations::queues::QueueManager;
+use crate::sim::action::action::{ActionIntent, ActionType};
+use crate::sim::calendar::components::{AttendanceStatus, CalendarEvent, CalendarIndex, EventPriority, EventType};
+use crate::sim::globals::{
+    BASE_MEETING_DECLINE_CHANCE, MEETING_ENERGY_COST_PER_TICK, MEETING_RSVP_LEAD_TICKS, MEETING_STRESS_PER_HOUR,
+    TRAINING_EXPERIENCE_PER_HOUR, TRAINING_SKILLS_PER_SESSION,
+};
+use crate::sim::person::components::{Person, PersonId};
+use crate::sim::person::morale::StressLevel;
+use crate::sim::person::needs::Energy;
+use crate::sim::person::personality_matrix::PersonalityMatrix;
+use crate::master_data::skills::GLOBAL_SKILLS;
+use crate::sim::person::skill_progression::{SkillGrowthSource, SkillProgress};
+use crate::sim::person::skills::{SkillId, SkillSet};
+use crate::sim::person::stats::Stats;
+use crate::sim::person::thoughts::{EntityId, Thought, ThoughtContext};
+use crate::sim::project::task::Task;
+use crate::sim::registries::registry::Registry;
+use crate::sim::resources::global::{Dirty, TickCounter};
+use crate::sim::sim_date::sim_date::SimDate;
+use crate::sim::utils::random::{RngStream, SimRng};
+use legion::systems::CommandBuffer;
+use legion::world::SubWorld;
+use legion::{system, Entity, IntoQuery};
+use rand::Rng;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+/// Chance of an invitee declining. Unsociable and busy people decline more, important events less.
+pub fn decline_chance(sociability: i8, open_tasks: usize, stress: f32, priority: &EventPriority) -> f64 {
+    let priority_factor = match priority {
+        EventPriority::Low => 1.5,
+        EventPriority::Normal => 1.0,
+        EventPriority::High => 0.5,
+        // Nobody skips a critical event
+        EventPriority::Critical => return 0.0,
+    };
+    let unsociable = (-(sociability as f64) / 100.0).max(0.0) * 0.2;
+    let workload = open_tasks.min(5) as f64 * 0.04;
+    let stressed = (stress as f64 / 100.0).clamp(0.0, 1.0) * 0.15;
+    ((BASE_MEETING_DECLINE_CHANCE + unsociable + workload + stressed) * priority_factor).clamp(0.0, 0.9)
+}
+
+/// Stress from sitting through an event. Sociable people come out of it relaxed, which is negative stress.
+pub fn meeting_stress(sociability: i8, duration_ticks: u8) -> f32 {
+    -(sociability as f32 / 100.0) * MEETING_STRESS_PER_HOUR * duration_ticks as f32 / 4.0
+}
+
+/// Raw energy drained by an event, the more sociable the attendee the less it takes out of them.
+pub fn meeting_energy_cost(sociability: i8, duration_ticks: u8) -> f32 {
+    let drain = 1.0 - sociability as f32 / 200.0;
+    MEETING_ENERGY_COST_PER_TICK * drain * duration_ticks as f32
+}
+
+/// The weakest skills of the attendee, which a training session works on.
+pub fn training_focus(skill_set: &SkillSet) -> Vec<SkillId> {
+    let mut weakest: Vec<(&SkillId, &u32)> = skill_set.skills.iter().filter(|(_, value)| **value < 100).collect();
+    weakest.sort_by(|a, b| a.1.cmp(b.1).then_with(|| a.0 .0.cmp(&b.0 .0)));
+    weakest
+        .into_iter()
+        .take(TRAINING_SKILLS_PER_SESSION)
+        .map(|(skill, _)| skill.clone())
+        .collect()
+}
+
+/// Invitees answer pending invitations once the event is less than a day away.
+#[system]
+#[read_component(Person)]
+#[read_component(PersonalityMatrix)]
+#[read_component(StressLevel)]
+#[read_component(Task)]
+#[write_component(CalendarEvent)]
+pub fn respond_to_invitations(
+    #[resource] tick_counter: &Arc<TickCounter>,
+    #[resource] sim_rng: &mut SimRng,
+    world: &mut SubWorld,
+) {
+    let now = tick_counter.current_date();
+    let horizon = SimDate::from(now.to_tick() + MEETING_RSVP_LEAD_TICKS);
+
+    let mut open_tasks: HashMap<PersonId, usize> = HashMap::new();
+    for task in <&Task>::query().iter(world).filter(|task| !task.is_done()) {
+        if let Some(person_id) = task.assigned_to {
+            *open_tasks.entry(person_id).or_default() += 1;
+        }
+    }
+    let people: HashMap<PersonId, (String, i8, f32)> = <(&Person, &PersonalityMatrix, &StressLevel)>::query()
+        .iter(world)
+        .map(|(person, personality, stress)| {
+            (person.person_id, (person.name.clone(), personality.sociability, stress.current))
+        })
+        .collect();
+
+    let rng = sim_rng.stream(RngStream::Calendar);
+    for event in <&mut CalendarEvent>::query().iter_mut(world) {
+        if event.get_occurrences(now, horizon).is_empty() {
+            continue;
+        }
+        let priority = event.details.priority.clone();
+        for attendee in event.details.participants.iter_mut() {
+            if attendee.status != AttendanceStatus::Pending {
+                continue;
+            }
+            let Some((name, sociability, stress)) = people.get(&attendee.person_id) else {
+                continue;
+            };
+            let workload = open_tasks.get(&attendee.person_id).copied().unwrap_or_default();
+            let chance = decline_chance(*sociability, workload, *stress, &priority);
+            attendee.status = if rng.random_bool(chance) {
+                info!("{} declined '{}'", name, event.details.title);
+                AttendanceStatus::Declined
+            } else {
+                debug!("{} accepted '{}'", name, event.details.title);
+                AttendanceStatus::Accepted
+            };
+        }
+    }
+}
+
+/// Pulls the attendees of events starting this tick out of what they were doing.
+///
+/// Meetings and training cost energy and change stress depending on how sociable the attendee is,
+/// training is practice for their weakest skills. Attendees are left a thought about the event.
+#[system]
+#[read_component(PersonalityMatrix)]
+#[read_component(Stats)]
+#[write_component(Energy)]
+#[write_component(StressLevel)]
+#[write_component(SkillSet)]
+#[write_component(SkillProgress)]
+pub fn start_calendar_events(
+    #[resource] tick_counter: &Arc<TickCounter>,
+    #[resource] calendar_index: &CalendarIndex,
+    #[resource] person_registry: &Arc<Registry<PersonId, Entity>>,
+    #[resource] queue_manager: &QueueManager,
+    world: &mut SubWorld,
+    cmd: &mut CommandBuffer,
+) {
+    let now = tick_counter.current_date();
+    let now_tick = now.to_tick();
+
+    for event in calendar_index.events_overlapping(now_tick, now_tick + 1) {
+        if !event.get_occurrences(now, now).contains(&now) {
+            continue;
+        }
+        let current = match event.details.event_type {
+            // Time set aside for work, whatever the employee is doing already is fine
+            EventType::Task => continue,
+            EventType::Break => ActionType::Rest,
+            EventType::Meeting | EventType::Training | EventType::Custom(_) => ActionType::Meeting(event.id),
+        };
+        let duration = event.details.duration_ticks;
+        debug!("'{}' starts", event.details.title);
+
+        for attendee in &event.details.participants {
+            if attendee.status == AttendanceStatus::Declined {
+                continue;
+            }
+            let Some(entity) = person_registry.get_entity_from_id(&attendee.person_id) else {
+                warn!("Attendee {:?} of '{}' not found", attendee.person_id, event.details.title);
+                continue;
+            };
+            cmd.add_component(entity, ActionIntent::for_event(current.clone(), now, duration));
+            if current == ActionType::Rest {
+                continue;
+            }
+
+            let mut query = <(
+                &PersonalityMatrix,
+                &Stats,
+                &mut Energy,
+                &mut StressLevel,
+                &mut SkillSet,
+                Option<&mut SkillProgress>,
+            )>::query();
+            let Ok((personality, stats, energy, stress, skill_set, progress)) = query.get_mut(world, entity) else {
+                continue;
+            };
+            energy.level.decrease_raw(meeting_energy_cost(personality.sociability, duration));
+            let stress_change = meeting_stress(personality.sociability, duration);
+            if stress_change >= 0.0 {
+                stress.apply(stress_change);
+            } else {
+                stress.current = (stress.current + stress_change).max(0.0);
+            }
+
+            let mut context = vec![ThoughtContext::Meeting(EntityId(event.id.0))];
+            if let (EventType::Training, Some(progress), Some(global_skills)) =
+                (&event.details.event_type, progress, GLOBAL_SKILLS.get())
+            {
+                let experience = TRAINING_EXPERIENCE_PER_HOUR * duration as f32 / 4.0;
+                let focus = training_focus(skill_set);
+                for skill in &focus {
+                    progress.practice(skill_set, skill, experience, SkillGrowthSource::Training, stats, global_skills, now);
+                }
+                cmd.add_component(entity, Dirty);
+                context.extend(focus.into_iter().map(ThoughtContext::Skill));
+            }
+            let feeling = if stress_change > 0.0 {
+                format!("Another meeting... '{}' is draining.", event.details.title)
+            } else {
+                format!("Good to catch up with everyone at '{}'.", event.details.title)
+            };
+            context.push(ThoughtContext::Event(feeling));
+            queue_manager.thought_manager.queue.push(ThoughtCommand::AddThought {
+                person_id: attendee.person_id.0,
+                thought: Thought {
+                    sim_date: now,
+                    context: ThoughtContext::Multi(context.into_boxed_slice()),
+                },
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn busy_unsociable_people_decline_more() {
+        let outgoing = decline_chance(80, 0, 0.0, &EventPriority::Normal);
+        let withdrawn = decline_chance(-80, 0, 0.0, &EventPriority::Normal);
+        let swamped = decline_chance(-80, 5, 60.0, &EventPriority::Normal);
+        assert!(outgoing < withdrawn);
+        assert!(withdrawn < swamped);
+        assert!(decline_chance(-80, 5, 60.0, &EventPriority::High) < swamped);
+        assert_eq!(decline_chance(-100, 10, 100.0, &EventPriority::Critical), 0.0);
+    }
+
+    #[test]
+    fn sociability_decides_how_meetings_feel() {
+        assert!(meeting_stress(-60, 4) > 0.0);
+        assert!(meeting_stress(60, 4) < 0.0);
+        assert!(meeting_energy_cost(-60, 4) > meeting_energy_cost(60, 4));
+        assert!(meeting_energy_cost(100, 4) > 0.0);
+    }
+
+    #[test]
+    fn training_focuses_on_the_weakest_skills() {
+        let skill_set = SkillSet {
+            skills: HashMap::from([
+                (SkillId("a".to_string()), 10),
+                (SkillId("b".to_string()), 50),
+                (SkillId("c".to_string()), 5),
+                (SkillId("d".to_string()), 100),
+            ]),
+        };
+        let focus = training_focus(&skill_set);
+        assert_eq!(focus, vec![SkillId("c".to_string()), SkillId("a".to_string())]);
+    }
+}
@@ -1,4 +1,6 @@
 pub mod systems;
 
 pub mod components;
-pub mod availability;
\ No newline at end of file
+pub mod availability;
+pub mod scheduler;
+pub mod meetings;
@@ -0,0 +1,312 @@
+use crate::sim::calendar::components::{
+    AttendanceStatus, CalendarEvent, CalendarEventAttendee, CalendarEventId, CalendarIndex, EventDetails,
+    EventPriority, EventType, RecurrenceException,
+};
+use crate::sim::company::company::WorkHourPolicy;
+use crate::sim::person::components::PersonId;
+use crate::sim::sim_date::sim_date::SimDate;
+use serde::{Deserialize, Serialize};
+
+/// Score of a slot for every optional attendee that can make it.
+const OPTIONAL_ATTENDEE_SCORE: i32 = 10;
+/// Score of a slot for every event that has to be moved to make room.
+const BUMPED_EVENT_SCORE: i32 = -15;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeetingRequest {
+    pub title: String,
+    pub event_type: EventType,
+    pub required: Vec<PersonId>,
+    pub optional: Vec<PersonId>,
+    pub duration_ticks: u8,
+    /// Earliest and latest start of the meeting, both inclusive
+    pub window_start: SimDate,
+    pub window_end: SimDate,
+    /// Events of a lower priority may be moved to make room for the meeting
+    pub priority: EventPriority,
+}
+
+/// A start time that works for every required attendee.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeetingSlot {
+    pub start: SimDate,
+    pub available_optional: Vec<PersonId>,
+    /// Events of required attendees that would have to move
+    pub bumped: Vec<CalendarEventId>,
+    pub score: i32,
+}
+
+/// Whether `[start, start + duration_ticks)` is inside the policy's working hours on a working day.
+pub fn within_working_hours(policy: &WorkHourPolicy, start: SimDate, duration_ticks: u8) -> bool {
+    let last_tick = start.quarter_tick as u32 + duration_ticks.max(1) as u32 - 1;
+    policy.is_working_day(start.day)
+        && start.quarter_tick >= policy.start_quarter_tick()
+        && last_tick <= policy.end_quarter_tick() as u32
+}
+
+fn attends(event: &CalendarEvent, person: PersonId) -> bool {
+    event
+        .details
+        .participants
+        .iter()
+        .any(|attendee| attendee.person_id == person && attendee.status != AttendanceStatus::Declined)
+}
+
+/// Ranked start times for the meeting, best first. Slots never overlap each other.
+///
+/// Only the range covered by the [`CalendarIndex`] is searched. A slot is ruled out when a required attendee has an
+/// event of the same or a higher priority at that time. Lower priority events are listed as bumped.
+pub fn find_meeting_slots(
+    index: &CalendarIndex,
+    request: &MeetingRequest,
+    policy: &WorkHourPolicy,
+    max_slots: usize,
+) -> Vec<MeetingSlot> {
+    let duration = request.duration_ticks.max(1) as u64;
+    let first_tick = request.window_start.max(index.window_start).to_tick();
+    let last_tick = request.window_end.min(index.window_end).to_tick();
+
+    let mut candidates = Vec::new();
+    for tick in first_tick..=last_tick {
+        let start = SimDate::from(tick);
+        if !within_working_hours(policy, start, request.duration_ticks) {
+            continue;
+        }
+        let events = index.events_overlapping(tick, tick + duration);
+
+        let mut bumped = Vec::new();
+        let blocked = request.required.iter().any(|person| {
+            events.iter().filter(|event| attends(event, *person)).any(|event| {
+                if event.details.priority >= request.priority {
+                    return true;
+                }
+                if !bumped.contains(&event.id) {
+                    bumped.push(event.id);
+                }
+                false
+            })
+        });
+        if blocked {
+            continue;
+        }
+
+        let available_optional: Vec<PersonId> = request
+            .optional
+            .iter()
+            .copied()
+            .filter(|person| !events.iter().any(|event| attends(event, *person)))
+            .collect();
+        let score = available_optional.len() as i32 * OPTIONAL_ATTENDEE_SCORE + bumped.len() as i32 * BUMPED_EVENT_SCORE;
+        candidates.push(MeetingSlot { start, available_optional, bumped, score });
+    }
+
+    // Best score first, earlier slots win ties
+    candidates.sort_by(|a, b| b.score.cmp(&a.score).then(a.start.cmp(&b.start)));
+
+    let mut slots: Vec<MeetingSlot> = Vec::new();
+    for candidate in candidates {
+        if slots.len() >= max_slots {
+            break;
+        }
+        let start = candidate.start.to_tick();
+        let overlaps_chosen = slots.iter().any(|slot| {
+            let chosen = slot.start.to_tick();
+            start < chosen + duration && chosen < start + duration
+        });
+        if !overlaps_chosen {
+            slots.push(candidate);
+        }
+    }
+    slots
+}
+
+/// The meeting as a calendar event booked at `slot`. Optional attendees who are busy are left out.
+pub fn meeting_event(id: CalendarEventId, request: &MeetingRequest, slot: &MeetingSlot) -> CalendarEvent {
+    let participants = request
+        .required
+        .iter()
+        .chain(slot.available_optional.iter())
+        .map(|person_id| CalendarEventAttendee {
+            person_id: *person_id,
+            status: AttendanceStatus::Pending,
+        })
+        .collect();
+    CalendarEvent {
+        id,
+        start_time: slot.start,
+        details: EventDetails {
+            title: request.title.clone(),
+            duration_ticks: request.duration_ticks.max(1),
+            participants,
+            event_type: request.event_type.clone(),
+            priority: request.priority.clone(),
+        },
+        template_id: None,
+        recurrence: None,
+    }
+}
+
+/// Moves an event out of the way of a newly booked meeting. The index must already contain the meeting.
+///
+/// One-off events move to the next slot in working hours where all of their attendees are free.
+/// For events with a recurrence only the occurrence in the way is moved, or skipped when there is no free slot.
+/// Returns the updated event, `None` when a one-off event could not be placed anywhere.
+pub fn bump_event(
+    index: &CalendarIndex,
+    event: &CalendarEvent,
+    meeting_start: SimDate,
+    meeting_duration: u8,
+    policy: &WorkHourPolicy,
+) -> Option<CalendarEvent> {
+    let meeting_start_tick = meeting_start.to_tick();
+    let meeting_end_tick = meeting_start_tick + meeting_duration as u64;
+    let duration = event.details.duration_ticks;
+
+    let original = event
+        .get_occurrences(index.window_start, index.window_end)
+        .into_iter()
+        .find(|occurrence| {
+            let start = occurrence.to_tick();
+            start < meeting_end_tick && meeting_start_tick < start + duration as u64
+        })?;
+
+    let reschedule = MeetingRequest {
+        title: event.details.title.clone(),
+        event_type: event.details.event_type.clone(),
+        required: event
+            .details
+            .participants
+            .iter()
+            .filter(|attendee| attendee.status != AttendanceStatus::Declined)
+            .map(|attendee| attendee.person_id)
+            .collect(),
+        optional: vec![],
+        duration_ticks: duration,
+        window_start: SimDate::from(meeting_end_tick),
+        window_end: index.window_end,
+        // Lowest priority so the moved event does not push anything else around
+        priority: EventPriority::Low,
+    };
+    let new_start = find_meeting_slots(index, &reschedule, policy, usize::MAX)
+        .into_iter()
+        .map(|slot| slot.start)
+        .min();
+
+    let mut moved = event.clone();
+    match (&mut moved.recurrence, new_start) {
+        (Some(recurrence), Some(to)) => recurrence.exceptions.push(RecurrenceException::Move { from: original, to }),
+        (Some(recurrence), None) => recurrence.exceptions.push(RecurrenceException::Skip(original)),
+        (None, Some(to)) => moved.start_time = to,
+        (None, None) => return None,
+    }
+    Some(moved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::calendar::components::{Recurrence, RecurrencePattern};
+
+    fn date(week: u8, day: u8, quarter_tick: u8) -> SimDate {
+        SimDate { year: 1, week, day, quarter_tick }
+    }
+
+    fn event(id: u64, start_time: SimDate, duration_ticks: u8, people: &[u32], priority: EventPriority) -> CalendarEvent {
+        CalendarEvent {
+            id: CalendarEventId::new(id),
+            start_time,
+            details: EventDetails {
+                title: format!("Event {id}"),
+                duration_ticks,
+                participants: people
+                    .iter()
+                    .map(|id| CalendarEventAttendee { person_id: PersonId(*id), status: AttendanceStatus::Accepted })
+                    .collect(),
+                event_type: EventType::Meeting,
+                priority,
+            },
+            template_id: None,
+            recurrence: None,
+        }
+    }
+
+    fn request(required: &[u32], optional: &[u32], priority: EventPriority) -> MeetingRequest {
+        MeetingRequest {
+            title: "Planning".to_string(),
+            event_type: EventType::Meeting,
+            required: required.iter().map(|id| PersonId(*id)).collect(),
+            optional: optional.iter().map(|id| PersonId(*id)).collect(),
+            duration_ticks: 4,
+            window_start: date(1, 1, 1),
+            window_end: date(1, 1, 96),
+            priority,
+        }
+    }
+
+    #[test]
+    fn slots_respect_working_hours_and_busy_attendees() {
+        let policy = WorkHourPolicy::default();
+        let mut index = CalendarIndex::new();
+        // Person 1 is busy 9:00 - 11:00 on Monday
+        index.add_event(event(1, date(1, 1, 37), 8, &[1], EventPriority::Normal));
+
+        let slots = find_meeting_slots(&index, &request(&[1, 2], &[], EventPriority::Normal), &policy, 3);
+        assert_eq!(slots[0].start, date(1, 1, 45));
+        assert!(slots.iter().all(|slot| within_working_hours(&policy, slot.start, 4)));
+        assert!(slots.iter().all(|slot| slot.bumped.is_empty()));
+        // Slots do not overlap each other
+        assert_eq!(slots[1].start, date(1, 1, 49));
+    }
+
+    #[test]
+    fn optional_attendees_improve_the_rank() {
+        let policy = WorkHourPolicy::default();
+        let mut index = CalendarIndex::new();
+        // Optional person 3 is busy for the whole morning
+        index.add_event(event(1, date(1, 1, 37), 16, &[3], EventPriority::Normal));
+
+        let slots = find_meeting_slots(&index, &request(&[1], &[3], EventPriority::Normal), &policy, 2);
+        assert_eq!(slots[0].start, date(1, 1, 53));
+        assert_eq!(slots[0].available_optional, vec![PersonId(3)]);
+        assert_eq!(slots[1].start, date(1, 1, 57));
+    }
+
+    #[test]
+    fn higher_priority_meetings_bump_lower_priority_events() {
+        let policy = WorkHourPolicy::default();
+        let mut index = CalendarIndex::new();
+        // Person 1 is in low priority events the whole working day
+        index.add_event(event(1, date(1, 1, 37), 32, &[1], EventPriority::Low));
+
+        let normal = request(&[1], &[], EventPriority::Low);
+        assert!(find_meeting_slots(&index, &normal, &policy, 3).is_empty());
+
+        let urgent = request(&[1], &[], EventPriority::High);
+        let slots = find_meeting_slots(&index, &urgent, &policy, 3);
+        assert_eq!(slots[0].start, date(1, 1, 37));
+        assert_eq!(slots[0].bumped, vec![CalendarEventId::new(1)]);
+
+        let meeting = meeting_event(CalendarEventId::new(2), &urgent, &slots[0]);
+        index.add_event(meeting);
+        let moved = bump_event(&index, &index.events[&CalendarEventId::new(1)], slots[0].start, 4, &policy).unwrap();
+        // The whole next working day is free
+        assert_eq!(moved.start_time, date(1, 2, 37));
+    }
+
+    #[test]
+    fn bumping_a_series_moves_one_occurrence() {
+        let policy = WorkHourPolicy::default();
+        let mut index = CalendarIndex::new();
+        let mut standup = event(1, date(1, 1, 37), 2, &[1], EventPriority::Low);
+        standup.recurrence = Some(Recurrence::new(RecurrencePattern::Weekdays));
+        index.add_event(standup);
+        index.add_event(event(2, date(1, 2, 37), 4, &[1], EventPriority::High));
+
+        let moved = bump_event(&index, &index.events[&CalendarEventId::new(1)], date(1, 2, 37), 4, &policy).unwrap();
+        let recurrence = moved.recurrence.unwrap();
+        assert_eq!(
+            recurrence.exceptions,
+            vec![RecurrenceException::Move { from: date(1, 2, 37), to: date(1, 2, 41) }]
+        );
+    }
+}
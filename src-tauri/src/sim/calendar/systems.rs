@@ -1,8 +1,10 @@
 use std::sync::Arc;
 use legion::{system, IntoQuery, Entity, Query};
+use legion::systems::CommandBuffer;
 use legion::world::SubWorld;
-use crate::sim::calendar::components::{CalendarEvent, CalendarEventRegistry};
+use crate::sim::calendar::components::{CalendarEvent, CalendarEventId, CalendarEventRegistry, CalendarIndex, RecurringEventTemplate};
 use crate::sim::registries::registry::Registry;
+use crate::sim::resources::global::TickCounter;
 use crate::sim::team::components::{Team, TeamId};
 
 #[system]
@@ -10,13 +12,46 @@ use crate::sim::team::components::{Team, TeamId};
 #[read_component(Entity)]
 pub fn sync_registry_from_calendar_event(
     world: &mut SubWorld,
-    #[resource] calendar_event_registry: &mut CalendarEventRegistry,
+    #[resource] calendar_event_registry: &Arc<CalendarEventRegistry>,
 ) {
-    
-    calendar_event_registry.clear();
     let mut query = <(Entity, &CalendarEvent)>::query();
-    for (entity, calendar_event) in query.iter(world) {
-        calendar_event_registry.insert(calendar_event.id, *entity);
+    let events = query
+        .iter(world)
+        .map(|(entity, calendar_event)| (calendar_event.id, *entity));
+    calendar_event_registry.repopulate_from_entities(events);
+}
+
+/// Expands recurring event templates into concrete events up to the end of next month.
+#[system(for_each)]
+pub fn expand_recurring_events(
+    #[resource] tick_counter: &Arc<TickCounter>,
+    #[resource] calendar_event_registry: &Arc<CalendarEventRegistry>,
+    template: &mut RecurringEventTemplate,
+    cmd: &mut CommandBuffer,
+) {
+    let (_, window_end) = CalendarIndex::window_for(tick_counter.current_date());
+    let events = template.expand_until(window_end, || {
+        CalendarEventId::new(calendar_event_registry.generate_id() as u64)
+    });
+    for event in events {
+        let event_id = event.id;
+        let entity = cmd.push((event,));
+        calendar_event_registry.insert(event_id, entity);
     }
 }
 
+/// Keeps the [`CalendarIndex`] in line with the calendar events and moves its window along with the current month.
+#[system]
+#[read_component(CalendarEvent)]
+pub fn update_calendar_index(
+    #[resource] tick_counter: &Arc<TickCounter>,
+    #[resource] calendar_index: &mut CalendarIndex,
+    world: &SubWorld,
+    query: &mut Query<&CalendarEvent>,
+) {
+    let current_date = tick_counter.current_date();
+    if !calendar_index.covers(&current_date) {
+        calendar_index.rebuild(current_date);
+    }
+    calendar_index.sync(query.iter(world));
+}
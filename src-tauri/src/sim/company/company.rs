@@ -1,4 +1,5 @@
 use bincode::{Decode, Encode};
+use crate::sim::calendar::availability::{AvailabilityBitSet, Weekday, ALL_WEEKDAYS};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize, Encode, Decode)]
@@ -8,12 +9,73 @@ pub struct Company {
 }
 
 
+/// Working hours the company expects from its employees.
+/// Employees work `daily_required_hours` from `start_hour` on the first `required_days` days of the week.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode)]
 pub struct WorkHourPolicy{
-    daily_required_hours: u32,
-    weekly_required_hours: u32,
-    required_hours : u64,
-    required_days: u8,
-    
+    pub daily_required_hours: u32,
+    pub weekly_required_hours: u32,
+    pub required_days: u8,
+    pub start_hour: u8,
+}
+
+impl Default for WorkHourPolicy {
+    fn default() -> Self {
+        Self {
+            daily_required_hours: 8,
+            weekly_required_hours: 40,
+            required_days: 5,
+            start_hour: 9,
+        }
+    }
+}
+
+impl WorkHourPolicy {
+    pub fn is_working_day(&self, day: u8) -> bool {
+        day >= 1 && day <= self.required_days.min(7)
+    }
+
+    /// Working weekdays, counted from Monday.
+    pub fn working_weekdays(&self) -> Vec<Weekday> {
+        ALL_WEEKDAYS
+            .iter()
+            .copied()
+            .filter(|weekday| self.is_working_day(*weekday as u8))
+            .collect()
+    }
+
+    /// First quarter tick of the working day (1-based).
+    pub fn start_quarter_tick(&self) -> u8 {
+        self.start_hour.min(23) * 4 + 1
+    }
+
+    /// Last quarter tick of the working day (1-based, inclusive), capped at the end of the day.
+    pub fn end_quarter_tick(&self) -> u8 {
+        let end = self.start_quarter_tick() as u32 + self.daily_required_hours * 4 - 1;
+        end.min(96) as u8
+    }
+
+    pub fn daily_required_ticks(&self) -> u32 {
+        self.daily_required_hours * 4
+    }
+
+    pub fn weekly_required_ticks(&self) -> u32 {
+        self.weekly_required_hours * 4
+    }
+
+    /// Working hours bitset for a month, set bits are outside working hours.
+    pub fn working_hours_pattern(&self) -> AvailabilityBitSet {
+        let mut pattern = AvailabilityBitSet::new();
+        pattern.set_daily_busy(1, 96);
+        if self.daily_required_hours > 0 {
+            pattern.set_recurring_free(
+                self.start_quarter_tick(),
+                self.end_quarter_tick(),
+                &self.working_weekdays(),
+            );
+        }
+        pattern
+    }
 }
 
 pub struct PlayerControlled;
\ No newline at end of file
@@ -0,0 +1,239 @@
+use crate::integrations::events::AppEventType;
+use crate::integrations::ui::AppContext;
+use crate::sim::company::company::{Company, PlayerControlled};
+use crate::sim::globals::{PAYROLL_DAY, PAYROLL_QUARTER_TICK};
+use crate::sim::person::components::Person;
+use crate::sim::resources::global::TickCounter;
+use crate::sim::sim_date::sim_date::SimDate;
+use bincode::{Decode, Encode};
+use legion::world::SubWorld;
+use legion::{system, IntoQuery};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
+pub enum TransactionKind {
+    StartingCapital,
+    Payroll,
+    RecurringCost,
+    ProjectPayout,
+}
+
+/// A single movement of cash. Positive amounts are income, negative amounts are expenses.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode)]
+pub struct Transaction {
+    pub kind: TransactionKind,
+    pub amount: i64,
+    pub description: String,
+}
+
+/// Append-only record of every transaction, keyed by the date it happened on.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Encode, Decode)]
+pub struct Ledger {
+    entries: BTreeMap<SimDate, Vec<Transaction>>,
+}
+
+impl Ledger {
+    pub fn append(&mut self, date: SimDate, transaction: Transaction) {
+        self.entries.entry(date).or_default().push(transaction);
+    }
+
+    pub fn on(&self, date: &SimDate) -> &[Transaction] {
+        self.entries.get(date).map_or(&[], |transactions| transactions.as_slice())
+    }
+
+    /// Transactions between `from` and `to`, both inclusive, oldest first.
+    pub fn between(&self, from: SimDate, to: SimDate) -> impl Iterator<Item = (&SimDate, &Transaction)> {
+        self.entries
+            .range(from..=to)
+            .flat_map(|(date, transactions)| transactions.iter().map(move |t| (date, t)))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.values().map(|transactions| transactions.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// A cost the company pays every week regardless of headcount, e.g. office rent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode)]
+pub struct RecurringCost {
+    pub label: String,
+    pub weekly_amount: i64,
+}
+
+/// Cash and books of a company. Lives on the company entity next to [`Company`].
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode)]
+pub struct Finances {
+    pub cash: i64,
+    pub recurring_costs: Vec<RecurringCost>,
+    /// Salaries of the current employees, refreshed every tick
+    pub weekly_payroll: i64,
+    pub is_bankrupt: bool,
+    pub ledger: Ledger,
+}
+
+impl Finances {
+    pub fn new(starting_cash: i64, date: SimDate) -> Self {
+        let mut finances = Self {
+            cash: 0,
+            recurring_costs: vec![
+                RecurringCost { label: "Office rent".to_string(), weekly_amount: 2_000 },
+                RecurringCost { label: "Software licenses".to_string(), weekly_amount: 300 },
+            ],
+            weekly_payroll: 0,
+            is_bankrupt: false,
+            ledger: Ledger::default(),
+        };
+        finances.record(date, TransactionKind::StartingCapital, starting_cash, "Starting capital");
+        finances
+    }
+
+    /// Applies a transaction to the cash balance and appends it to the ledger.
+    pub fn record(&mut self, date: SimDate, kind: TransactionKind, amount: i64, description: impl Into<String>) {
+        self.cash += amount;
+        self.ledger.append(date, Transaction { kind, amount, description: description.into() });
+    }
+
+    pub fn weekly_recurring_costs(&self) -> i64 {
+        self.recurring_costs.iter().map(|cost| cost.weekly_amount).sum()
+    }
+
+    pub fn weekly_burn(&self) -> i64 {
+        self.weekly_payroll + self.weekly_recurring_costs()
+    }
+
+    /// Days until the cash runs out at the current burn rate, `None` when nothing is being spent.
+    pub fn runway_days(&self) -> Option<u32> {
+        let burn = self.weekly_burn();
+        if burn <= 0 {
+            return None;
+        }
+        if self.cash <= 0 {
+            return Some(0);
+        }
+        Some((self.cash * 7 / burn) as u32)
+    }
+
+    /// Updates the bankruptcy flag from the cash balance. Returns `true` when the company just went bankrupt.
+    pub fn check_bankruptcy(&mut self) -> bool {
+        let was_bankrupt = self.is_bankrupt;
+        self.is_bankrupt = self.cash < 0;
+        self.is_bankrupt && !was_bankrupt
+    }
+}
+
+// Keeps the weekly payroll in line with the employees on the books.
+#[system]
+#[read_component(Person)]
+#[read_component(PlayerControlled)]
+#[write_component(Finances)]
+pub fn update_weekly_payroll(world: &mut SubWorld) {
+    let payroll: i64 = <(&Person, &PlayerControlled)>::query()
+        .iter(world)
+        .map(|(person, _)| person.talent_grade.weekly_salary())
+        .sum();
+    for (finances, _) in <(&mut Finances, &PlayerControlled)>::query().iter_mut(world) {
+        finances.weekly_payroll = payroll;
+    }
+}
+
+/// Pays salaries and recurring costs once a week, then checks whether the company went bankrupt.
+#[system]
+#[read_component(Person)]
+#[read_component(Company)]
+#[read_component(PlayerControlled)]
+#[write_component(Finances)]
+pub fn run_payroll(
+    #[resource] tick_counter: &Arc<TickCounter>,
+    #[resource] app_context: &Arc<AppContext>,
+    world: &mut SubWorld,
+) {
+    let current_date = tick_counter.current_date();
+    if current_date.day != PAYROLL_DAY || current_date.quarter_tick != PAYROLL_QUARTER_TICK {
+        return;
+    }
+
+    let salaries: Vec<(String, i64)> = <(&Person, &PlayerControlled)>::query()
+        .iter(world)
+        .map(|(person, _)| (person.name.clone(), person.talent_grade.weekly_salary()))
+        .collect();
+
+    let company_name = <(&Company, &PlayerControlled)>::query()
+        .iter(world)
+        .next()
+        .map(|(company, _)| company.name.clone())
+        .unwrap_or_default();
+
+    for (finances, _) in <(&mut Finances, &PlayerControlled)>::query().iter_mut(world) {
+        for (name, salary) in &salaries {
+            finances.record(current_date, TransactionKind::Payroll, -salary, format!("Salary: {}", name));
+        }
+        for cost in finances.recurring_costs.clone() {
+            finances.record(current_date, TransactionKind::RecurringCost, -cost.weekly_amount, cost.label);
+        }
+        info!("Payroll done, balance is now {}", finances.cash);
+
+        if finances.check_bankruptcy() {
+            warn!("{} is bankrupt with a balance of {}", company_name, finances.cash);
+            app_context.emit(
+                AppEventType::Bankruptcy {
+                    company_name: company_name.clone(),
+                    balance: finances.cash,
+                },
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transactions_move_cash_and_are_kept_by_date() {
+        let start = SimDate::default();
+        let mut finances = Finances::new(10_000, start);
+        let later = SimDate::from(500);
+        finances.record(later, TransactionKind::Payroll, -3_000, "Salary: Alice");
+        finances.record(later, TransactionKind::ProjectPayout, 5_000, "Shop portal");
+
+        assert_eq!(finances.cash, 12_000);
+        assert_eq!(finances.ledger.len(), 3);
+        assert_eq!(finances.ledger.on(&later).len(), 2);
+        assert_eq!(finances.ledger.between(SimDate::from(1), later).count(), 2);
+        assert!(finances.ledger.on(&SimDate::from(42)).is_empty());
+    }
+
+    #[test]
+    fn runway_follows_the_burn_rate() {
+        let mut finances = Finances::new(14_000, SimDate::default());
+        finances.recurring_costs.clear();
+        assert_eq!(finances.runway_days(), None);
+
+        finances.weekly_payroll = 7_000;
+        assert_eq!(finances.runway_days(), Some(14));
+
+        finances.cash = -10;
+        assert_eq!(finances.runway_days(), Some(0));
+    }
+
+    #[test]
+    fn bankruptcy_is_reported_once() {
+        let mut finances = Finances::new(1_000, SimDate::default());
+        assert!(!finances.check_bankruptcy());
+        finances.record(SimDate::default(), TransactionKind::Payroll, -2_000, "Salary");
+        assert!(finances.check_bankruptcy());
+        assert!(!finances.check_bankruptcy());
+        assert!(finances.is_bankrupt);
+
+        finances.record(SimDate::default(), TransactionKind::ProjectPayout, 5_000, "Payout");
+        assert!(!finances.check_bankruptcy());
+        assert!(!finances.is_bankrupt);
+    }
+}
@@ -1 +1,3 @@
-pub mod company;
\ No newline at end of file
+pub mod company;
+pub mod finance;
+pub mod work_hours;
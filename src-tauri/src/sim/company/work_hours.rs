@@ -0,0 +1,292 @@
+use crate::action_queues::thought_manager::ThoughtCommand;
+use crate::integrations::events::AppEventType;
+use crate::integrations::queues::QueueManager;
+use crate::integrations::ui::AppContext;
+use crate::sim::action::action::{ActionIntent, ActionType};
+use crate::sim::calendar::availability::{MonthlyAvailability, YearMonth};
+use crate::sim::company::company::{PlayerControlled, WorkHourPolicy};
+use crate::sim::globals::{
+    DAILY_OVERTIME_TOLERANCE_HOURS, OVERWORK_STRESS_PER_HOUR, WEEKLY_HOURS_TOLERANCE,
+};
+use crate::sim::person::components::Person;
+use crate::sim::person::morale::StressLevel;
+use crate::sim::person::thoughts::{Thought, ThoughtContext};
+use crate::sim::resources::global::TickCounter;
+use crate::sim::sim_date::sim_date::SimDate;
+use crate::sim::sim_date::sim_day::SimDay;
+use bincode::{Decode, Encode};
+use legion::systems::CommandBuffer;
+use legion::world::SubWorld;
+use legion::{system, Entity, IntoQuery};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+/// Quarter ticks an employee has worked in the current day and week.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode)]
+pub struct WorkHours {
+    pub day: SimDay,
+    pub day_ticks: u32,
+    pub week_ticks: u32,
+    /// Quarter ticks worked outside working hours this week
+    pub overtime_ticks: u32,
+}
+
+impl WorkHours {
+    pub fn new(day: SimDay) -> Self {
+        Self {
+            day,
+            day_ticks: 0,
+            week_ticks: 0,
+            overtime_ticks: 0,
+        }
+    }
+
+    pub fn record_tick(&mut self, outside_working_hours: bool) {
+        self.day_ticks += 1;
+        self.week_ticks += 1;
+        if outside_working_hours {
+            self.overtime_ticks += 1;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HoursVerdict {
+    Over { ticks: u32 },
+    Under { ticks: u32 },
+    WithinPolicy,
+}
+
+/// Compares quarter ticks worked against the required amount, allowing `tolerance_ticks` either way.
+pub fn check_hours(worked_ticks: u32, required_ticks: u32, tolerance_ticks: u32) -> HoursVerdict {
+    if worked_ticks > required_ticks + tolerance_ticks {
+        HoursVerdict::Over { ticks: worked_ticks - required_ticks }
+    } else if worked_ticks + tolerance_ticks < required_ticks {
+        HoursVerdict::Under { ticks: required_ticks - worked_ticks }
+    } else {
+        HoursVerdict::WithinPolicy
+    }
+}
+
+fn hours(ticks: u32) -> f32 {
+    ticks as f32 / 4.0
+}
+
+fn company_policy(world: &SubWorld) -> WorkHourPolicy {
+    <(&WorkHourPolicy, &PlayerControlled)>::query()
+        .iter(world)
+        .next()
+        .map(|(policy, _)| policy.clone())
+        .unwrap_or_default()
+}
+
+fn add_thought(queue_manager: &QueueManager, person: &Person, date: SimDate, text: String) {
+    queue_manager.thought_manager.queue.push(ThoughtCommand::AddThought {
+        person_id: person.person_id.0,
+        thought: Thought {
+            sim_date: date,
+            context: ThoughtContext::Event(text),
+        },
+    });
+}
+
+/// Writes the company's working hours into each employee's availability for the current and next month.
+/// Employees without an availability calendar get one.
+#[system]
+#[read_component(WorkHourPolicy)]
+#[read_component(PlayerControlled)]
+#[read_component(Person)]
+#[write_component(MonthlyAvailability)]
+pub fn apply_work_hour_policy(
+    #[resource] tick_counter: &Arc<TickCounter>,
+    world: &mut SubWorld,
+    cmd: &mut CommandBuffer,
+) {
+    let pattern = company_policy(world).working_hours_pattern();
+    let current_month = YearMonth::from(&tick_counter.current_date());
+
+    let mut query = <(Entity, &Person, Option<&mut MonthlyAvailability>)>::query();
+    for (entity, _, availability) in query.iter_mut(world) {
+        let Some(availability) = availability else {
+            let mut availability = MonthlyAvailability::new();
+            for month in [current_month, current_month.next_month()] {
+                availability.get_or_create_month(month).working_hours = pattern.clone();
+            }
+            cmd.add_component(*entity, availability);
+            continue;
+        };
+        for month in [current_month, current_month.next_month()] {
+            let detail = availability.get_or_create_month(month);
+            if detail.working_hours != pattern {
+                detail.working_hours = pattern.clone();
+            }
+        }
+    }
+}
+
+/// Tracks the hours each employee works against the company's [`WorkHourPolicy`].
+///
+/// Long days and weeks add stress and leave the employee a thought about it, weeks short of
+/// the required hours are reported to the player.
+#[system]
+#[read_component(WorkHourPolicy)]
+#[read_component(PlayerControlled)]
+#[read_component(Person)]
+#[read_component(ActionIntent)]
+#[read_component(MonthlyAvailability)]
+#[write_component(StressLevel)]
+#[write_component(WorkHours)]
+pub fn track_work_hours(
+    #[resource] tick_counter: &Arc<TickCounter>,
+    #[resource] queue_manager: &QueueManager,
+    #[resource] app_context: &Arc<AppContext>,
+    world: &mut SubWorld,
+    cmd: &mut CommandBuffer,
+) {
+    let policy = company_policy(world);
+    let now = tick_counter.current_date();
+    let today = SimDay::from(&now);
+    let month_tick = now.tick_in_month();
+
+    let mut query = <(
+        Entity,
+        &Person,
+        Option<&ActionIntent>,
+        Option<&MonthlyAvailability>,
+        &mut StressLevel,
+        Option<&mut WorkHours>,
+    )>::query();
+    for (entity, person, intent, availability, stress, work_hours) in query.iter_mut(world) {
+        let Some(work_hours) = work_hours else {
+            cmd.add_component(*entity, WorkHours::new(today));
+            continue;
+        };
+
+        if work_hours.day != today {
+            let previous = work_hours.day;
+            if policy.is_working_day(previous.day) || work_hours.day_ticks > 0 {
+                let required = if policy.is_working_day(previous.day) {
+                    policy.daily_required_ticks()
+                } else {
+                    0
+                };
+                if let HoursVerdict::Over { ticks } =
+                    check_hours(work_hours.day_ticks, required, DAILY_OVERTIME_TOLERANCE_HOURS * 4)
+                {
+                    stress.apply(OVERWORK_STRESS_PER_HOUR * hours(ticks));
+                    add_thought(
+                        queue_manager,
+                        person,
+                        now,
+                        format!("Long day. I put in {} hours yesterday.", hours(work_hours.day_ticks)),
+                    );
+                }
+            }
+
+            if (previous.year, previous.week) != (today.year, today.week) {
+                let required = policy.weekly_required_ticks();
+                match check_hours(work_hours.week_ticks, required, WEEKLY_HOURS_TOLERANCE * 4) {
+                    HoursVerdict::Over { ticks } => {
+                        stress.apply(OVERWORK_STRESS_PER_HOUR * hours(ticks));
+                        add_thought(
+                            queue_manager,
+                            person,
+                            now,
+                            format!(
+                                "{} hours last week, {} of them after hours. That's a lot more than the {} I signed up for.",
+                                hours(work_hours.week_ticks),
+                                hours(work_hours.overtime_ticks),
+                                policy.weekly_required_hours
+                            ),
+                        );
+                    }
+                    HoursVerdict::Under { .. } => {
+                        warn!(
+                            "{} worked {} of the required {} hours last week",
+                            person.name,
+                            hours(work_hours.week_ticks),
+                            policy.weekly_required_hours
+                        );
+                        app_context.emit(
+                            AppEventType::ShowCustomNotification {
+                                notification_type: "warning".to_string(),
+                                title: "Missed hours".to_string(),
+                                message: format!(
+                                    "{} worked {} of the required {} hours last week.",
+                                    person.name,
+                                    hours(work_hours.week_ticks),
+                                    policy.weekly_required_hours
+                                ),
+                                context_id: Some(person.person_id.0.to_string()),
+                            },
+                        );
+                    }
+                    HoursVerdict::WithinPolicy => {}
+                }
+                work_hours.week_ticks = 0;
+                work_hours.overtime_ticks = 0;
+            }
+            work_hours.day = today;
+            work_hours.day_ticks = 0;
+        }
+
+        let working = intent.is_some_and(|intent| {
+            matches!(intent.current, ActionType::GeneralWork | ActionType::Work(_) | ActionType::Meeting(_))
+        });
+        if working {
+            let outside_working_hours = availability
+                .and_then(|availability| availability.get_month_for_date(&now))
+                .is_some_and(|detail| !detail.working_hours.is_free(month_tick, 1));
+            work_hours.record_tick(outside_working_hours);
+            debug!(
+                "{} worked {} hours today",
+                person.name,
+                hours(work_hours.day_ticks)
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::calendar::availability::AvailabilityBitSet;
+
+    #[test]
+    fn hours_are_checked_with_tolerance() {
+        assert_eq!(check_hours(32, 32, 4), HoursVerdict::WithinPolicy);
+        assert_eq!(check_hours(36, 32, 4), HoursVerdict::WithinPolicy);
+        assert_eq!(check_hours(40, 32, 4), HoursVerdict::Over { ticks: 8 });
+        assert_eq!(check_hours(20, 32, 4), HoursVerdict::Under { ticks: 12 });
+    }
+
+    #[test]
+    fn policy_pattern_marks_working_hours_free() {
+        let policy = WorkHourPolicy::default();
+        let pattern = policy.working_hours_pattern();
+        let nine_am = AvailabilityBitSet::time_to_day_tick(9, 0).unwrap();
+        let five_pm = AvailabilityBitSet::time_to_day_tick(17, 0).unwrap();
+
+        // Monday 9am - 5pm is inside working hours
+        let monday = AvailabilityBitSet::day_tick_to_month_tick(1, 1, nine_am).unwrap();
+        assert!(pattern.is_free(monday, 32));
+        let monday_evening = AvailabilityBitSet::day_tick_to_month_tick(1, 1, five_pm).unwrap();
+        assert!(!pattern.is_free(monday_evening, 1));
+
+        // Weekends are outside working hours
+        let saturday = AvailabilityBitSet::day_tick_to_month_tick(3, 6, nine_am).unwrap();
+        assert!(!pattern.is_free(saturday, 1));
+        assert_eq!(policy.weekly_required_ticks(), 5 * policy.daily_required_ticks());
+    }
+
+    #[test]
+    fn overtime_is_counted_separately() {
+        let mut work_hours = WorkHours::new(SimDay::from(SimDate::default()));
+        work_hours.record_tick(false);
+        work_hours.record_tick(true);
+        assert_eq!(work_hours.day_ticks, 2);
+        assert_eq!(work_hours.week_ticks, 2);
+        assert_eq!(work_hours.overtime_ticks, 1);
+    }
+}
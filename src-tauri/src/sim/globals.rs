@@ -6,3 +6,97 @@ pub const BASE_HUNGER_DECAY_PER_TICK: u8 = 2u8;
 
 /// Maximum number of thoughts stored in the [`Thoughts`] component
 pub const MAX_CURRENT_THOUGHTS: usize = 10;
+
+/// Maximum number of nodes a single GOAP search may expand before giving up
+pub const PLANNER_MAX_NODES_PER_SEARCH: usize = 256;
+
+/// Maximum length of a plan produced by the GOAP planner
+pub const PLANNER_MAX_PLAN_DEPTH: usize = 8;
+
+/// Total number of GOAP nodes that may be expanded across all employees in one sim tick.
+/// Employees that do not fit into the budget are replanned on a later tick.
+pub const PLANNER_NODE_BUDGET_PER_TICK: usize = 2048;
+
+/// Units of task work an average employee in good shape completes each sim tick
+pub const BASE_WORK_PER_TICK: f32 = 1.0;
+
+/// Number of customers generated when a new game starts
+pub const STARTING_CUSTOMER_COUNT: usize = 4;
+
+/// Maximum number of offers a customer keeps open at once
+pub const MAX_OPEN_OFFERS_PER_CUSTOMER: usize = 2;
+
+/// Daily chance of a customer with average reputation posting a new offer
+pub const BASE_DAILY_OFFER_CHANCE: f64 = 0.3;
+
+/// Number of days an offer stays open before it expires
+pub const OFFER_LIFETIME_DAYS: u64 = 7;
+
+/// Payout per unit of task work, before the customer's budget and reputation are applied
+pub const PAYOUT_PER_WORK_UNIT: f32 = 150.0;
+
+/// Starting cash of a new company
+pub const STARTING_CASH: i64 = 100_000;
+
+/// Day of the week payroll runs on
+pub const PAYROLL_DAY: u8 = 7;
+
+/// Quarter tick payroll runs at, 5pm
+pub const PAYROLL_QUARTER_TICK: u8 = 69;
+
+/// Hours an employee may work past the daily requirement before it starts to stress them
+pub const DAILY_OVERTIME_TOLERANCE_HOURS: u32 = 1;
+
+/// Hours an employee may be over or under the weekly requirement before it is flagged
+pub const WEEKLY_HOURS_TOLERANCE: u32 = 4;
+
+/// Stress applied for every hour worked past the tolerated overtime
+pub const OVERWORK_STRESS_PER_HOUR: f32 = 6.0;
+
+/// Number of candidate slots returned when scheduling a meeting
+pub const MAX_MEETING_SLOTS: usize = 5;
+
+/// Quarter ticks before an event that invitees answer their invitation
+pub const MEETING_RSVP_LEAD_TICKS: u64 = 96;
+
+/// Chance of an average invitee declining an event of normal priority
+pub const BASE_MEETING_DECLINE_CHANCE: f64 = 0.05;
+
+/// Raw energy an average attendee spends per quarter tick of a meeting
+pub const MEETING_ENERGY_COST_PER_TICK: f32 = 1500.0;
+
+/// Stress per hour of meeting for the least sociable attendee, the most sociable loses as much
+pub const MEETING_STRESS_PER_HOUR: f32 = 3.0;
+
+/// Skill experience gained per hour of training, a point takes 1.0
+pub const TRAINING_EXPERIENCE_PER_HOUR: f32 = 0.5;
+
+/// Number of the attendee's weakest skills a training session improves
+pub const TRAINING_SKILLS_PER_SESSION: usize = 2;
+
+/// Skill experience gained in each required skill per quarter tick of work on a task
+pub const WORK_EXPERIENCE_PER_TICK: f32 = 0.01;
+
+/// Extra experience per quarter tick from working next to someone much better at the skill
+pub const MENTORING_EXPERIENCE_PER_TICK: f32 = 0.01;
+
+/// Skill points a colleague on the same project needs over an employee to mentor them
+pub const MENTORING_SKILL_GAP: u32 = 15;
+
+/// Share of practice that carries over to skills the practiced skill feeds forward into
+pub const FEEDFORWARD_SHARE: f32 = 0.3;
+
+/// Share of practice that carries over to skills the practiced skill feeds back into
+pub const FEEDBACK_SHARE: f32 = 0.15;
+
+/// Days a skill can go unused before it starts to decay
+pub const SKILL_DECAY_GRACE_DAYS: u64 = 14;
+
+/// Skill experience lost per day by a skill past the grace period
+pub const SKILL_DECAY_PER_DAY: f32 = 0.1;
+
+/// Skills do not decay below this value
+pub const SKILL_DECAY_FLOOR: u32 = 10;
+
+/// Number of skill changes kept per employee
+pub const SKILL_HISTORY_LENGTH: usize = 50;
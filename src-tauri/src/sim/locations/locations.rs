@@ -1,12 +1,19 @@
 use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 
 // --- 1. LocationId Newtype ---
 // Provides type safety for location identifiers.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LocationId(String);
 
+impl LocationId {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
 impl Hash for LocationId {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.0.hash(state);
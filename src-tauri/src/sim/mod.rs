@@ -13,6 +13,6 @@ pub mod sim_date;
 pub mod systems;
 pub mod team;
 pub mod utils;
-mod locations;
-mod talent_generation;
+pub mod locations;
+pub mod talent_generation;
 pub mod persistence;
@@ -1,3 +1,4 @@
+use crate::master_data::data_pack::MASTER_DATA;
 use crate::sim::person::spawner::TalentGrade;
 use crate::sim::person::spawner::TalentGrade::{Apt, Basic, Brilliant, Exceptional, Gifted, Sharp};
 use serde::{Deserialize, Serialize};
@@ -10,7 +11,8 @@ pub struct NewGameConfig {
     initial_starting_employee_config: String,
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
 pub struct StartingEmployeesConfig {
     pub name: String,
     pub config: Vec<(TalentGrade, u16)>,
@@ -31,8 +33,8 @@ impl Default for StartingEmployeesConfig {
     }
 }
 
-#[tauri::command]
-pub fn get_starting_employee_configs() -> Vec<StartingEmployeesConfig> {
+/// The built-in starting employee configurations.
+pub fn default_starting_employee_configs() -> Vec<StartingEmployeesConfig> {
     vec![
 
         StartingEmployeesConfig {
@@ -61,9 +63,24 @@ pub fn get_starting_employee_configs() -> Vec<StartingEmployeesConfig> {
     ]
 }
 
+/// The built-in company presets.
+pub fn default_company_presets() -> Vec<CompanyPreset> {
+    COMPANY_PRESETS
+        .iter()
+        .map(|preset| CompanyPreset { name: preset.name.to_string(), slogan: preset.slogan.to_string() })
+        .collect()
+}
+
+#[tauri::command]
+pub fn get_starting_employee_configs() -> Vec<StartingEmployeesConfig> {
+    MASTER_DATA
+        .get()
+        .map_or_else(default_starting_employee_configs, |data| data.starting_employee_configs.clone())
+}
+
 #[tauri::command]
-pub fn get_company_presets() -> Vec<CompanyPresetStatic> {
-    COMPANY_PRESETS.to_vec()
+pub fn get_company_presets() -> Vec<CompanyPreset> {
+    MASTER_DATA.get().map_or_else(default_company_presets, |data| data.company_presets.clone())
 }
 
 
@@ -74,7 +91,8 @@ pub struct CompanyPresetStatic {
 }
 
 
-#[derive(Clone, Serialize, Deserialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
 pub struct CompanyPreset {
     pub name:String,
     pub slogan: String,
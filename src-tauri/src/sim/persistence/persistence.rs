@@ -1,17 +1,28 @@
 // Added for SavedEmployee
 use crate::action_queues::sim_manager::SimManager;
+use crate::db::checkpoints::{AutosavePolicy, CheckpointKind};
 use crate::db::constants::{db_keys, save_version};
-use crate::db::init::{SaveSlot, SaveSlotMetadata, SavesDirectory};
+use crate::db::init::{SaveBatch, SaveSlot, SaveSlotMetadata, SavesDirectory};
+use crate::master_data::data_pack::{active_data_packs, changed_data_packs, missing_data_packs};
+use crate::sim::utils::random::{SimRng, SimRngState};
 use crate::integrations::snapshots::{company, person, team};
 use crate::integrations::snapshots::snapshots::SnapshotState;
 use crate::integrations::snapshots_emitter::snapshots_emitter::SnapshotEmitRegistry;
-use crate::sim::ai::goap::CurrentGoal;
-use crate::sim::company::company::{Company, PlayerControlled};
+use crate::sim::action::action::ActionIntent;
+use crate::sim::ai::goap::{CurrentGoal, CurrentPlan, EmployeeGoapFacts};
+use crate::sim::ai::planner::PlannedGoal;
+use crate::sim::calendar::availability::MonthlyAvailability;
+use crate::sim::company::company::{Company, PlayerControlled, WorkHourPolicy};
+use crate::sim::company::finance::Finances;
+use crate::sim::company::work_hours::WorkHours;
+use crate::sim::globals::STARTING_CASH;
 use crate::sim::person::components::{Person, PersonId, ProfilePicture};
 use crate::sim::person::thoughts::{Thoughts, ArchivedThoughts};
 use crate::sim::person::morale::StressLevel;
 use crate::sim::person::needs::{Energy, Hunger};
 use crate::sim::person::personality_matrix::PersonalityMatrix;
+use crate::sim::person::init::FirstRun;
+use crate::sim::person::skill_progression::SkillProgress;
 use crate::sim::person::skills::SkillSet;
 use crate::sim::person::stats::Stats;
 use crate::sim::registries::registry::Registry;
@@ -19,8 +30,9 @@ use crate::sim::resources::global::TickCounter;
 use crate::sim::sim_date::sim_date::SimDate;
 use crate::sim::systems::global::UsedProfilePictureRegistry;
 use crate::sim::team::components::{Team, TeamId};
+use crate::sim::utils::debugging::DebugDisplayComponent;
 use crate::schedules::init::GameSchedules;
-use crate::utils::errors::LoadDataFromDBError;
+use crate::utils::errors::{LoadDataFromDBError, SaveDataToDBError};
 use crate::utils::errors::{SavesManagementError, SavesManagementError::TimeError};
 use bincode::error::EncodeError;
 use bincode::{encode_to_vec, Decode, Encode};
@@ -35,8 +47,13 @@ use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tauri::utils::acl::Commands;
-use tracing::{error, info, warn};
-use crate::sim::calendar::components::CalendarEvent;
+use tracing::{debug, error, info, warn};
+use crate::sim::calendar::components::{CalendarEvent, RecurringEventTemplate};
+use crate::sim::project::customer::Customer;
+use crate::sim::project::functionality::Functionality;
+use crate::sim::project::project::Project;
+use crate::sim::project::requirement::Requirement;
+use crate::sim::project::task::Task;
 // Added for logging
 
 /// Represents the data of an employee that can be saved or transferred.
@@ -55,6 +72,19 @@ pub struct SavedEmployee {
     pub archived_thoughts: ArchivedThoughts,
 }
 
+/// What an employee is doing and planning, saved next to their [`SavedEmployee`].
+/// Components the employee does not have yet stay `None`, saves from before it load with nobody mid-action.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Encode, Decode)]
+pub struct SavedBehaviour {
+    pub action_intent: Option<ActionIntent>,
+    pub current_plan: Option<CurrentPlan>,
+    pub planned_goal: Option<PlannedGoal>,
+    pub goap_facts: Option<EmployeeGoapFacts>,
+    pub monthly_availability: Option<MonthlyAvailability>,
+    pub work_hours: Option<WorkHours>,
+    pub debug_display: DebugDisplayComponent,
+}
+
 #[derive(Debug)]
 pub struct LoadGame{
     pub should_load: AtomicBool,
@@ -78,6 +108,8 @@ pub fn save_game_state(
     #[resource] sim_manager: &Arc<SimManager>,
     #[resource] saves_directory: &Arc<SavesDirectory>,
     #[resource] used_profile_pictures: &UsedProfilePictureRegistry,
+    #[resource] sim_rng: &mut SimRng,
+    #[resource] autosave_policy: &AutosavePolicy,
     query: &mut Query<(
         &Person,
         &Stats,
@@ -90,14 +122,34 @@ pub fn save_game_state(
         &StressLevel,
         Option<&Thoughts>,
         Option<&ArchivedThoughts>,
+        Option<&SkillProgress>,
         &PlayerControlled,
     )>,
-    company_query: &mut Query<(&Company, &PlayerControlled)>,
+    behaviour_query: &mut Query<(
+        &Person,
+        Option<&ActionIntent>,
+        Option<&CurrentPlan>,
+        Option<&PlannedGoal>,
+        Option<&EmployeeGoapFacts>,
+        Option<&MonthlyAvailability>,
+        Option<&WorkHours>,
+        Option<&DebugDisplayComponent>,
+        &PlayerControlled,
+    )>,
+    company_query: &mut Query<(&Company, Option<&Finances>, Option<&WorkHourPolicy>, &PlayerControlled)>,
     team_query: &mut Query<(&Team)>,
     calendar_event_query: &mut Query<(&CalendarEvent)>,
+    recurring_event_template_query: &mut Query<&RecurringEventTemplate>,
+    project_query: &mut Query<&Project>,
+    requirement_query: &mut Query<&Requirement>,
+    functionality_query: &mut Query<&Functionality>,
+    task_query: &mut Query<&Task>,
+    customer_query: &mut Query<&Customer>,
 ) {
+    // Every tick, saved or not, so runs with and without a save slot draw the same numbers
+    let rng_state = sim_rng.checkpoint();
     if !sim_manager.has_save_slot() {
-        warn!("No active save slot");
+        debug!("No active save slot");
         return;
     }
     if sim_manager
@@ -115,8 +167,10 @@ pub fn save_game_state(
     }
 
     
-    sim_manager.with_save_slot(|current_save| {
+    let saved = sim_manager.with_save_slot(|current_save| -> Result<(), SaveDataToDBError> {
         current_save.ensure_db_handle_is_open(saves_directory);
+        // Everything goes into one batch, so a crash never leaves half a save behind
+        let mut batch = SaveBatch::default();
 
         let mut employee_id_list: Vec<u32> = vec![]; 
         for (
@@ -131,6 +185,7 @@ pub fn save_game_state(
             stress_level,
             thoughts,
             archived_thoughts,
+            skill_progress,
             _player_controlled,
         ) in query.iter(world)
         {
@@ -148,30 +203,74 @@ pub fn save_game_state(
                 archived_thoughts: archived_thoughts.cloned().unwrap_or_default(),
             };
 
-            current_save.save_entry(
+            batch.insert(
                 format!("{}{}", db_keys::EMPLOYEE_PREFIX, person.person_id.0).as_str(),
                 &saved_employee,
-            );
+            )?;
+            if let Some(skill_progress) = skill_progress {
+                batch.insert(
+                    format!("{}{}", db_keys::SKILL_PROGRESS_PREFIX, person.person_id.0).as_str(),
+                    skill_progress,
+                )?;
+            }
             employee_id_list.push(person.person_id.0);
 
         }
-        current_save.save_entry(db_keys::EMPLOYEES_LIST, &employee_id_list );
+        for (person, action_intent, current_plan, planned_goal, goap_facts, monthly_availability, work_hours, debug_display, _player_controlled)
+            in behaviour_query.iter(world)
+        {
+            let saved_behaviour = SavedBehaviour {
+                action_intent: action_intent.cloned(),
+                current_plan: current_plan.cloned(),
+                planned_goal: planned_goal.cloned(),
+                goap_facts: goap_facts.cloned(),
+                monthly_availability: monthly_availability.cloned(),
+                work_hours: work_hours.cloned(),
+                debug_display: debug_display.cloned().unwrap_or_default(),
+            };
+            batch.insert(
+                format!("{}{}", db_keys::BEHAVIOUR_PREFIX, person.person_id.0).as_str(),
+                &saved_behaviour,
+            )?;
+        }
+        batch.insert(db_keys::EMPLOYEES_LIST, &employee_id_list )?;
         // Save the player-controlled company.
         // Currently, only the one player controlled company exists.
         //
         // Non player companies will be added later.
         // They will be stored using a different db key in future.
-        for (company, _player_controlled) in company_query.iter(world) {
-            current_save.save_entry(db_keys::COMPANY, company);
+        for (company, finances, work_hour_policy, _player_controlled) in company_query.iter(world) {
+            batch.insert(db_keys::COMPANY, company)?;
+            if let Some(finances) = finances {
+                batch.insert(db_keys::FINANCES, finances)?;
+            }
+            if let Some(work_hour_policy) = work_hour_policy {
+                batch.insert(db_keys::WORK_HOUR_POLICY, work_hour_policy)?;
+            }
         }
 
         let teams: Vec<Team> = team_query.iter(world).map(|t| t.clone()).collect();
-        current_save.save_entry(db_keys::TEAMS, &teams);
+        batch.insert(db_keys::TEAMS, &teams)?;
 
         let calendar_events: Vec<CalendarEvent> = calendar_event_query.iter(world).map(|t| t.clone()).collect();
-        current_save.save_entry(db_keys::CALENDAR_EVENTS, &calendar_events);
+        batch.insert(db_keys::CALENDAR_EVENTS, &calendar_events)?;
+        let recurring_event_templates: Vec<RecurringEventTemplate> = recurring_event_template_query.iter(world).cloned().collect();
+        batch.insert(db_keys::RECURRING_EVENT_TEMPLATES, &recurring_event_templates)?;
+
+        let projects: Vec<Project> = project_query.iter(world).cloned().collect();
+        batch.insert(db_keys::PROJECTS, &projects)?;
+        let requirements: Vec<Requirement> = requirement_query.iter(world).cloned().collect();
+        batch.insert(db_keys::REQUIREMENTS, &requirements)?;
+        let functionalities: Vec<Functionality> = functionality_query.iter(world).cloned().collect();
+        batch.insert(db_keys::FUNCTIONALITIES, &functionalities)?;
+        let tasks: Vec<Task> = task_query.iter(world).cloned().collect();
+        batch.insert(db_keys::TASKS, &tasks)?;
+
+        let customers: Vec<Customer> = customer_query.iter(world).cloned().collect();
+        batch.insert(db_keys::CUSTOMERS, &customers)?;
         
-        current_save.save_entry(db_keys::TICK_COUNTER, current_tick);
+        batch.insert(db_keys::TICK_COUNTER, current_tick)?;
+        batch.insert(db_keys::RNG_STATE, &rng_state)?;
 
         let metadata = SaveSlotMetadata {
             name: current_save.metadata.clone().unwrap().name.clone(),
@@ -182,11 +281,23 @@ pub fn save_game_state(
                 .duration_since(UNIX_EPOCH)
                 .unwrap()
                 .as_secs() as i64,
+            seed: rng_state.seed,
+            data_packs: active_data_packs(),
+            checksum: None,
         };
-        current_save.save_entry(db_keys::METADATA, &metadata);
-        
-        current_save.save_entry(db_keys::USED_PROFILE_PICTURES, used_profile_pictures);
-        
+        batch.insert(db_keys::USED_PROFILE_PICTURES, used_profile_pictures)?;
+        current_save.commit(batch, metadata)?;
+
+        if autosave_policy.is_due(current_tick.value()) {
+            match current_save.create_checkpoint(CheckpointKind::Autosave, "") {
+                Ok(_) => {
+                    if let Err(e) = current_save.prune_autosaves(autosave_policy.keep) {
+                        error!("Failed to prune autosaves: {}", e);
+                    }
+                }
+                Err(e) => error!("Autosave failed: {}", e),
+            }
+        }
         
         
         // match current_save.load_entry::<TickCounter>(db_keys::TICK_COUNTER){
@@ -196,8 +307,11 @@ pub fn save_game_state(
         //     Ok(None)=>{info!("Nothing in db for tick counter")}
         //     Err(e) => {error!("Error loading tick counter: {:?}", e);}
         // }
-        
+        Ok(())
     });
+    if let Some(Err(e)) = saved {
+        error!("Failed to save the game: {}", e);
+    }
 }
 
 pub fn load_game_state(
@@ -210,10 +324,9 @@ pub fn load_game_state(
     loop_snapshot_registry: &Arc<SnapshotEmitRegistry>,
     game_schedules: &mut GameSchedules,
 ) -> Result<(), SavesManagementError> {
-    use crate::integrations::events::{emit_app_event, AppEventType};
+    use crate::integrations::events::AppEventType;
     use crate::integrations::ui::AppContext;
     use crate::sim::resources::global::Dirty;
-    use crate::sim::utils::debugging::DebugDisplayComponent;
     use std::io;
 
     let saves_directory = resources
@@ -236,6 +349,24 @@ pub fn load_game_state(
     };
 
     save_slot.ensure_db_handle_is_open(&saves_directory)?;
+    save_slot.verify()?;
+
+    let metadata = save_slot.load_entry::<SaveSlotMetadata>(db_keys::METADATA)?;
+    let sim_rng = match save_slot.load_entry::<SimRngState>(db_keys::RNG_STATE) {
+        Ok(rng_state) => SimRng::from_state(&rng_state),
+        Err(LoadDataFromDBError::KeyNotFound(_)) => SimRng::new(metadata.seed),
+        Err(e) => return Err(e.into()),
+    };
+    let active_packs = active_data_packs();
+    let missing_packs = missing_data_packs(&metadata.data_packs, &active_packs);
+    if !missing_packs.is_empty() {
+        error!("Cannot load '{}', data packs missing: {:?}", slot, missing_packs);
+        return Err(SavesManagementError::MissingDataPacks(missing_packs));
+    }
+    let changed_packs = changed_data_packs(&metadata.data_packs, &active_packs);
+    if !changed_packs.is_empty() {
+        warn!("'{}' was saved with older versions of the data packs {:?}, loading it with the current data", slot, changed_packs);
+    }
 
     info!("Resetting the world...");
     world.clear();
@@ -245,7 +376,19 @@ pub fn load_game_state(
     for employee_id in employee_list {
         let employee = save_slot.load_entry::<SavedEmployee>(&format!("{}{}", db_keys::EMPLOYEE_PREFIX, employee_id))?;
         info!("Loading employees: {:?}", employee);
-        world.push((
+        let skill_progress = match save_slot
+            .load_entry::<SkillProgress>(&format!("{}{}", db_keys::SKILL_PROGRESS_PREFIX, employee_id))
+        {
+            Ok(skill_progress) => skill_progress,
+            Err(LoadDataFromDBError::KeyNotFound(_)) => SkillProgress::default(),
+            Err(e) => return Err(e.into()),
+        };
+        let behaviour = match save_slot.load_entry::<SavedBehaviour>(&format!("{}{}", db_keys::BEHAVIOUR_PREFIX, employee_id)) {
+            Ok(behaviour) => behaviour,
+            Err(LoadDataFromDBError::KeyNotFound(_)) => SavedBehaviour::default(),
+            Err(e) => return Err(e.into()),
+        };
+        let entity = world.push((
             employee.person,
             employee.stats,
             employee.profile_picture,
@@ -257,10 +400,30 @@ pub fn load_game_state(
             employee.current_goal,
             employee.thoughts,
             employee.archived_thoughts,
-            DebugDisplayComponent::default(),
+            skill_progress,
+            behaviour.debug_display,
             PlayerControlled,
             Dirty,
         ));
+        let mut entry = world.entry(entity).expect("Entity was just pushed");
+        if let Some(action_intent) = behaviour.action_intent {
+            entry.add_component(action_intent);
+        }
+        if let Some(current_plan) = behaviour.current_plan {
+            entry.add_component(current_plan);
+        }
+        if let Some(planned_goal) = behaviour.planned_goal {
+            entry.add_component(planned_goal);
+        }
+        if let Some(goap_facts) = behaviour.goap_facts {
+            entry.add_component(goap_facts);
+        }
+        if let Some(monthly_availability) = behaviour.monthly_availability {
+            entry.add_component(monthly_availability);
+        }
+        if let Some(work_hours) = behaviour.work_hours {
+            entry.add_component(work_hours);
+        }
     }
 
     info!("Loading teams...");
@@ -274,16 +437,49 @@ pub fn load_game_state(
     for calendar_event in calendar_events {
         world.push((calendar_event, Dirty));
     }
+    for template in load_list_or_empty::<RecurringEventTemplate>(&save_slot, db_keys::RECURRING_EVENT_TEMPLATES)? {
+        world.push((template,));
+    }
+
+    info!("Loading projects...");
+    for project in load_list_or_empty::<Project>(&save_slot, db_keys::PROJECTS)? {
+        world.push((project,));
+    }
+    for requirement in load_list_or_empty::<Requirement>(&save_slot, db_keys::REQUIREMENTS)? {
+        world.push((requirement,));
+    }
+    for functionality in load_list_or_empty::<Functionality>(&save_slot, db_keys::FUNCTIONALITIES)? {
+        world.push((functionality,));
+    }
+    for task in load_list_or_empty::<Task>(&save_slot, db_keys::TASKS)? {
+        world.push((task,));
+    }
+
+    info!("Loading customers...");
+    for customer in load_list_or_empty::<Customer>(&save_slot, db_keys::CUSTOMERS)? {
+        world.push((customer, Dirty));
+    }
 
     info!("Loading company...");
     let company = save_slot.load_entry::<Company>(db_keys::COMPANY)?;
-    world.push((company, PlayerControlled, Dirty));
+    let finances = match save_slot.load_entry::<Finances>(db_keys::FINANCES) {
+        Ok(finances) => finances,
+        Err(LoadDataFromDBError::KeyNotFound(_)) => Finances::new(STARTING_CASH, SimDate::default()),
+        Err(e) => return Err(e.into()),
+    };
+    let work_hour_policy = match save_slot.load_entry::<WorkHourPolicy>(db_keys::WORK_HOUR_POLICY) {
+        Ok(work_hour_policy) => work_hour_policy,
+        Err(LoadDataFromDBError::KeyNotFound(_)) => WorkHourPolicy::default(),
+        Err(e) => return Err(e.into()),
+    };
+    world.push((company, finances, work_hour_policy, PlayerControlled, Dirty));
 
     info!("Loading tick_counter...");
     let tick_counter = save_slot.load_entry::<TickCounter>(db_keys::TICK_COUNTER)?;
     loop_tick_counter.update_from(&tick_counter);
+    *loop_sim_manager.seed.write() = sim_rng.seed();
+    resources.insert(sim_rng);
 
-    let metadata = save_slot.load_entry::<SaveSlotMetadata>(db_keys::METADATA)?;
     save_slot.metadata = Some(metadata);
 
     info!("Load game {:?}.", loop_load_game);
@@ -291,6 +487,9 @@ pub fn load_game_state(
     *loop_load_game.slot_id.write() = None;
 
     *loop_sim_manager.save_slot.lock() = Some(save_slot);
+    if let Some(first_run) = resources.get::<Arc<FirstRun>>() {
+        first_run.mark_as_loaded();
+    }
 
     game_schedules.load_game_schedule.execute(world, resources);
 
@@ -301,18 +500,27 @@ pub fn load_game_state(
         .get::<Arc<AppContext>>()
         .ok_or_else(|| SavesManagementError::Io(io::Error::new(io::ErrorKind::NotFound, "AppContext resource not found")))?;
     info!("emit_done_setup_event");
-    emit_app_event(&app_context.app_handle, AppEventType::InitDone);
+    app_context.emit(AppEventType::InitDone);
 
     Ok(())
 }
 
-
-
+/// Loads a list saved under `key`, saves made before the list existed load as empty.
+fn load_list_or_empty<T: Decode<()>>(
+    save_slot: &SaveSlot,
+    key: &str,
+) -> Result<Vec<T>, LoadDataFromDBError> {
+    match save_slot.load_entry::<Vec<T>>(key) {
+        Ok(list) => Ok(list),
+        Err(LoadDataFromDBError::KeyNotFound(_)) => Ok(vec![]),
+        Err(e) => Err(e),
+    }
+}
 
 #[system]
 pub fn sync_registry_from_person(
     world: &SubWorld,
-    query: &mut Query<(&Person, &Entity)>,
+    query: &mut Query<(&Person, Entity)>,
     #[resource] person_registry: &Arc<Registry<PersonId, Entity>>,
 ) {
     info!("Syncing registry from person...");
@@ -325,7 +533,7 @@ pub fn sync_registry_from_person(
 #[system]
 pub fn sync_registry_from_team(
     world: &SubWorld,
-    query: &mut Query<(&Team, &Entity)>,
+    query: &mut Query<(&Team, Entity)>,
     #[resource] team_registry: &Arc<Registry<TeamId, Entity>>,
 ) {
     info!("Syncing registry from team...");
@@ -334,3 +542,61 @@ pub fn sync_registry_from_team(
         .map(|(team, entity)| (team.team_id, *entity));
     team_registry.repopulate_from_entities(x);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::constants::GAMESTATE_DB_FILENAME;
+    use crate::db::migrations::fixtures::{all_versions, write_save};
+    use crate::integrations::ui::AppContext;
+    use crate::resources::init::{initialize_emit_registries, initialize_non_shared_resources};
+    use crate::schedules::init::init_schedules;
+    use tempfile::tempdir;
+
+    #[test]
+    fn fixture_saves_of_every_version_load() {
+        for version in all_versions() {
+            let dir = tempdir().unwrap();
+            let db = sled::open(dir.path().join("fixture").join(GAMESTATE_DB_FILENAME)).unwrap();
+            write_save(&db, version);
+            drop(db);
+
+            let mut world = World::default();
+            let mut resources = Resources::default();
+            resources.insert(Arc::new(SavesDirectory(dir.path().to_path_buf())));
+            resources.insert(Arc::new(AppContext::headless()));
+            resources.insert(Arc::new(FirstRun::default()));
+            initialize_non_shared_resources(&mut resources);
+            let load_game = Arc::new(LoadGame::default());
+            *load_game.slot_id.write() = Some("fixture".to_string());
+            let tick_counter = Arc::new(TickCounter::default());
+            let sim_manager = Arc::new(SimManager::default());
+            let snapshot_state = Arc::new(SnapshotState::default());
+            let registry = Arc::new(initialize_emit_registries(&snapshot_state));
+
+            load_game_state(
+                &mut world,
+                &mut resources,
+                &load_game,
+                &tick_counter,
+                &sim_manager,
+                &snapshot_state,
+                &registry,
+                &mut init_schedules(),
+            )
+            .unwrap_or_else(|e| panic!("{version} save does not load: {e}"));
+
+            assert_eq!(tick_counter.value(), 100, "{version}");
+            let company = <&Company>::query().iter(&world).next().map(|company| company.name.clone());
+            assert_eq!(company.as_deref(), Some("Fixture"), "{version}");
+            let names: Vec<String> = <&Person>::query().iter(&world).map(|person| person.name.clone()).collect();
+            assert_eq!(names, ["Ada Fixture"], "{version}");
+            let titles: Vec<String> = <&CalendarEvent>::query().iter(&world).map(|event| event.details.title.clone()).collect();
+            assert_eq!(titles, ["Standup"], "{version}");
+            let metadata = sim_manager.with_save_slot(|slot| slot.metadata.clone()).flatten().unwrap();
+            assert_eq!(metadata.save_version, save_version::SAVE_VERSION);
+            // The startup schedule must not run over the loaded world
+            assert!(!resources.get::<Arc<FirstRun>>().unwrap().is_first_run(), "{version}");
+        }
+    }
+}
@@ -1,11 +1,16 @@
+use crate::sim::company::finance::Finances;
+use crate::sim::globals::STARTING_CASH;
+use crate::sim::sim_date::sim_date::SimDate;
 use crate::action_queues::game_speed_manager::GameSpeedManagerCommand::SetGameSpeed;
 use crate::action_queues::sim_manager::SimManager;
-use crate::integrations::events::{emit_app_event, AppEventType};
+use crate::integrations::events::AppEventType;
 use crate::integrations::snapshots::snapshots::SnapshotState;
 use crate::integrations::ui::AppContext;
-use crate::master_data::skills::{GLOBAL_SKILLS, SKILL_DEFS};
+use crate::master_data::skill_graph::{build_skill_graph, SKILL_GRAPH};
+use crate::master_data::skills::{SkillData, GLOBAL_SKILLS};
+use crate::utils::errors::SkillGraphError;
 use crate::sim::calendar::components::EventType;
-use crate::sim::company::company::{Company, PlayerControlled};
+use crate::sim::company::company::{Company, PlayerControlled, WorkHourPolicy};
 use crate::sim::new_game::new_game::StartingEmployeesConfig;
 use crate::sim::person::components::PersonId;
 use crate::sim::person::skills::ecs_components::{
@@ -13,7 +18,7 @@ use crate::sim::person::skills::ecs_components::{
     TierConceptual, TierFoundational,
 };
 use crate::sim::person::skills::{Domain, GlobalSkill, SkillId, SkillSet, Tier};
-use crate::sim::person::spawner::bounded_normal;
+use crate::sim::utils::random::{RngStream, SimRng};
 use crate::sim::person::stats::{StatType, Stats};
 use crate::sim::registries::registry::Registry;
 use crate::sim::resources::global::{AssetBasePath, Dirty};
@@ -49,6 +54,19 @@ impl FirstRun{
     pub fn mark_as_first_run(&self){
         self.first.store(true, Ordering::Relaxed);
     }
+
+    /// Keeps the startup schedule from seeding and populating a world that was loaded from a save.
+    pub fn mark_as_loaded(&self){
+        self.first.store(false, Ordering::Relaxed);
+    }
+}
+
+/// Seeds the random streams of a new game from the seed it was started with.
+#[system]
+pub fn seed_sim_rng(#[resource] sim_manager: &Arc<SimManager>, #[resource] sim_rng: &mut SimRng) {
+    let seed = *sim_manager.seed.read();
+    info!("Seeding the simulation with {}", seed);
+    *sim_rng = SimRng::new(seed);
 }
 
 #[system]
@@ -61,7 +79,7 @@ pub fn unset_first_run_flag(#[resource] first: &Arc<FirstRun>)
 pub fn emit_done_setup_event(#[resource] app_context: &Arc<AppContext>)
 {
     info!("emit_done_setup_event");
-    emit_app_event(&app_context.app_handle, AppEventType::InitDone)
+    app_context.emit(AppEventType::InitDone)
 }
 
 // Create a resource to trigger employee generation
@@ -76,6 +94,7 @@ pub fn generate_employees(
     #[resource] used_portrait: &UsedProfilePictureRegistry,
     #[resource] person_registry: &Arc<Registry<PersonId, Entity>>,
     #[resource] sim_manager: &Arc<SimManager>,
+    #[resource] sim_rng: &mut SimRng,
     world: &mut SubWorld,
 ) {
     use crate::sim::person::spawner::spawn_person;
@@ -98,13 +117,15 @@ pub fn generate_employees(
     //     (Exceptional, 0),
     // ];
     let mut q2 = <&GlobalSkill>::query().filter(component::<TierFoundational>()); //.filter(component::<DomainCoordination>());
-    let global_skills:Vec<_> = GLOBAL_SKILLS.get().unwrap().iter().filter(|(id, skill)|  skill.tier == Tier::Foundational ).map(|(id, skill)| skill).collect();
+    let mut global_skills:Vec<_> = GLOBAL_SKILLS.get().unwrap().iter().filter(|(id, skill)|  skill.tier == Tier::Foundational ).map(|(id, skill)| skill).collect();
+    // Same order every run, the skill values are drawn in this order
+    global_skills.sort_by(|a, b| a.id.0.cmp(&b.id.0));
     // let global_skills = q2.iter(world).cloned().collect::<Vec<_>>();
     let config = (*sim_manager).employees_preset.read().config.clone();
     for (grade, count) in  config {
         for _ in 0..count {
             let (id, entity, person, stats, profile_picture, personality, skillset) =
-                spawn_person(cmd, grade, asset_base_path, used_portrait, person_registry,&global_skills, 0);
+                spawn_person(cmd, sim_rng.stream(RngStream::Spawning), grade, asset_base_path, used_portrait, person_registry,&global_skills, 0);
         }
     }
     info!("Generated employees");
@@ -115,18 +136,14 @@ pub fn generate_employees(
 
 
 
-pub fn load_global_skills_to_static(
-) {
-    let mut global_skill_hashmap = HashMap::<SkillId, GlobalSkill>::new();
-    
-    for skill_def in SKILL_DEFS {
-        trace!("Single skill load {}", skill_def.id.to_string());
-        let mut global_skill = GlobalSkill::from(skill_def);
-        let id = global_skill.id.clone();
-        global_skill_hashmap.insert(id, global_skill);
-    }
-    GLOBAL_SKILLS.set(global_skill_hashmap);
-    
+/// Builds the global skills and the skill graph from the skill definitions.
+/// Fails on skill data that does not form a valid graph.
+pub fn load_global_skills_to_static(skills: &[SkillData]) -> Result<(), SkillGraphError> {
+    trace!("Loading {} skills", skills.len());
+    let (global_skills, skill_graph) = build_skill_graph(skills)?;
+    GLOBAL_SKILLS.set(global_skills);
+    SKILL_GRAPH.set(skill_graph);
+    Ok(())
 }
 
 
@@ -142,6 +159,8 @@ pub fn init_company(
         slogan: preset.slogan.clone(),
     };
 
-    cmd.push((new_company,PlayerControlled, Dirty));
+    let finances = Finances::new(STARTING_CASH, SimDate::default());
+
+    cmd.push((new_company, finances, WorkHourPolicy::default(), PlayerControlled, Dirty));
 }
 
@@ -2,6 +2,7 @@ pub mod components;
 pub mod init;
 pub mod personality_matrix;
 pub mod skills;
+pub mod skill_progression;
 pub mod spawner;
 pub mod stat_sculpter;
 pub mod stats;
@@ -1,7 +1,8 @@
 use crate::sim::resources::global::TickCounter;
 use bincode::{Decode, Encode};
 use legion::system;
-use rand::{rng, Rng};
+use rand::Rng;
+use crate::sim::utils::random::{RngStream, SimRng};
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use std::sync::Arc;
@@ -135,9 +136,10 @@ impl StressLevel {
 
 #[system(for_each)]
 pub fn update_stress(
+    #[resource] sim_rng: &mut SimRng,
     stress_level: &mut StressLevel
 ){
-    stress_level.apply( rng().random_range(0.001..0.8) )
+    stress_level.apply( sim_rng.stream(RngStream::Morale).random_range(0.001..0.8) )
 }
 
 #[system(for_each)]
@@ -0,0 +1,355 @@
+use crate::master_data::skills::GLOBAL_SKILLS;
+use crate::sim::action::action::{ActionIntent, ActionType};
+use crate::sim::globals::{
+    FEEDBACK_SHARE, FEEDFORWARD_SHARE, MENTORING_EXPERIENCE_PER_TICK, MENTORING_SKILL_GAP, SKILL_DECAY_FLOOR,
+    SKILL_DECAY_GRACE_DAYS, SKILL_DECAY_PER_DAY, SKILL_HISTORY_LENGTH, WORK_EXPERIENCE_PER_TICK,
+};
+use crate::sim::person::components::{Person, PersonId};
+use crate::sim::person::skills::{GlobalSkill, SkillId, SkillSet};
+use crate::sim::person::stats::Stats;
+use crate::sim::project::project::ProjectId;
+use crate::sim::project::task::Task;
+use crate::sim::resources::global::{Dirty, TickCounter};
+use crate::sim::sim_date::sim_date::SimDate;
+use bincode::{Decode, Encode};
+use legion::systems::CommandBuffer;
+use legion::world::SubWorld;
+use legion::{system, Entity, IntoQuery};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use tracing::debug;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
+pub enum SkillGrowthSource {
+    Work,
+    Training,
+    Mentoring,
+    /// Carried over from practicing a linked skill
+    Linked,
+    Decay,
+}
+
+/// A skill going up or down a point.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode)]
+pub struct SkillGrowthRecord {
+    pub date: SimDate,
+    pub skill: SkillId,
+    pub source: SkillGrowthSource,
+    /// Skill value after the change
+    pub value: u32,
+}
+
+/// Practice towards the next skill point of an employee, along with the history of their skill changes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Encode, Decode)]
+pub struct SkillProgress {
+    /// Experience towards the next point, negative while a skill decays towards losing one
+    pub experience: HashMap<SkillId, f32>,
+    pub last_practiced: HashMap<SkillId, SimDate>,
+    /// Most recent changes last
+    pub history: VecDeque<SkillGrowthRecord>,
+}
+
+/// 0.5 - 1.5 depending on the average of the stats related to the skill, 1.0 for an average (50) employee.
+pub fn stat_modifier(stats: &Stats, skill: Option<&GlobalSkill>) -> f32 {
+    let Some(skill) = skill.filter(|skill| !skill.related_stats.is_empty()) else {
+        return 1.0;
+    };
+    let total: u32 = skill.related_stats.iter().map(|stat| stats.get_stat(*stat) as u32).sum();
+    (total as f32 / skill.related_stats.len() as f32 / 50.0).clamp(0.5, 1.5)
+}
+
+/// Higher skills take more practice to improve.
+fn diminishing_returns(value: u32) -> f32 {
+    (1.0 - value as f32 / 110.0).max(0.1)
+}
+
+impl SkillProgress {
+    /// Practices `skill` for `amount` experience, scaled by the related stats and the current skill value.
+    /// Part of the gain carries over to the skills it is linked to in the skill graph.
+    /// Returns whether any skill value changed.
+    pub fn practice(
+        &mut self,
+        skill_set: &mut SkillSet,
+        skill: &SkillId,
+        amount: f32,
+        source: SkillGrowthSource,
+        stats: &Stats,
+        global_skills: &HashMap<SkillId, GlobalSkill>,
+        date: SimDate,
+    ) -> bool {
+        let global_skill = global_skills.get(skill);
+        let value = skill_set.skills.get(skill).copied().unwrap_or(0);
+        let gain = amount * stat_modifier(stats, global_skill) * diminishing_returns(value);
+        self.last_practiced.insert(skill.clone(), date);
+        let mut changed = self.add_experience(skill_set, skill, gain, source, date);
+
+        let Some(global_skill) = global_skill else {
+            return changed;
+        };
+        let links = global_skill
+            .feedforward
+            .iter()
+            .map(|link| (link, FEEDFORWARD_SHARE))
+            .chain(global_skill.feedback.iter().map(|link| (link, FEEDBACK_SHARE)));
+        for (link, share) in links {
+            // Linked practice only improves skills the employee already has
+            if !skill_set.skills.contains_key(&link.target) {
+                continue;
+            }
+            let linked_gain = gain * share * link.factor as f32 / 100.0;
+            changed |= self.add_experience(skill_set, &link.target, linked_gain, SkillGrowthSource::Linked, date);
+        }
+        changed
+    }
+
+    fn add_experience(
+        &mut self,
+        skill_set: &mut SkillSet,
+        skill: &SkillId,
+        amount: f32,
+        source: SkillGrowthSource,
+        date: SimDate,
+    ) -> bool {
+        let value = skill_set.skills.entry(skill.clone()).or_insert(0);
+        let experience = self.experience.entry(skill.clone()).or_insert(0.0);
+        *experience += amount;
+
+        let mut changed = false;
+        while *experience >= 1.0 && *value < 100 {
+            *experience -= 1.0;
+            *value += 1;
+            changed = true;
+            Self::record(&mut self.history, SkillGrowthRecord { date, skill: skill.clone(), source, value: *value });
+        }
+        if *value >= 100 {
+            *experience = 0.0;
+        }
+        changed
+    }
+
+    /// Skills not practiced for a while slowly lose experience, and points once a full point has been lost.
+    /// Returns whether any skill value changed.
+    pub fn decay(&mut self, skill_set: &mut SkillSet, date: SimDate) -> bool {
+        let mut changed = false;
+        for (skill, value) in skill_set.skills.iter_mut() {
+            // The clock starts the first time a skill is seen
+            let last_practiced = *self.last_practiced.entry(skill.clone()).or_insert(date);
+            let idle_days = date.to_tick().saturating_sub(last_practiced.to_tick()) / SimDate::QUARTER_TICKS_PER_DAY;
+            if idle_days <= SKILL_DECAY_GRACE_DAYS || *value <= SKILL_DECAY_FLOOR {
+                continue;
+            }
+            let experience = self.experience.entry(skill.clone()).or_insert(0.0);
+            *experience -= SKILL_DECAY_PER_DAY;
+            if *experience <= -1.0 {
+                *experience += 1.0;
+                *value -= 1;
+                changed = true;
+                Self::record(
+                    &mut self.history,
+                    SkillGrowthRecord { date, skill: skill.clone(), source: SkillGrowthSource::Decay, value: *value },
+                );
+            }
+        }
+        changed
+    }
+
+    fn record(history: &mut VecDeque<SkillGrowthRecord>, record: SkillGrowthRecord) {
+        history.push_back(record);
+        while history.len() > SKILL_HISTORY_LENGTH {
+            history.pop_front();
+        }
+    }
+}
+
+/// Employees working on a task practice the skills it requires. Working on the same project as someone
+/// much better at one of those skills teaches it faster.
+#[system]
+#[read_component(Person)]
+#[read_component(ActionIntent)]
+#[read_component(Task)]
+#[read_component(Stats)]
+#[write_component(SkillSet)]
+#[write_component(SkillProgress)]
+pub fn practice_skills(#[resource] tick_counter: &Arc<TickCounter>, world: &mut SubWorld, cmd: &mut CommandBuffer) {
+    let Some(global_skills) = GLOBAL_SKILLS.get() else {
+        return;
+    };
+    let now = tick_counter.current_date();
+
+    let mut assignments: HashMap<PersonId, (ProjectId, Vec<SkillId>)> = HashMap::new();
+    for task in <&Task>::query().iter(world).filter(|task| !task.is_done()) {
+        if let Some(person_id) = task.assigned_to {
+            assignments.entry(person_id).or_insert((task.project_id, task.required_skills.clone()));
+        }
+    }
+    if assignments.is_empty() {
+        return;
+    }
+
+    // Who is working on which project right now, and how good they are at it
+    let mut working: HashMap<PersonId, (ProjectId, Vec<SkillId>)> = HashMap::new();
+    let mut best_on_project: HashMap<(ProjectId, SkillId), u32> = HashMap::new();
+    for (person, intent, skill_set) in <(&Person, &ActionIntent, &SkillSet)>::query().iter(world) {
+        if !matches!(intent.current, ActionType::GeneralWork | ActionType::Work(_)) {
+            continue;
+        }
+        let Some((project_id, skills)) = assignments.remove(&person.person_id) else {
+            continue;
+        };
+        for skill in &skills {
+            let value = skill_set.skills.get(skill).copied().unwrap_or(0);
+            let best = best_on_project.entry((project_id, skill.clone())).or_insert(0);
+            *best = (*best).max(value);
+        }
+        working.insert(person.person_id, (project_id, skills));
+    }
+
+    let mut query = <(Entity, &Person, &Stats, &mut SkillSet, Option<&mut SkillProgress>)>::query();
+    for (entity, person, stats, skill_set, progress) in query.iter_mut(world) {
+        let Some((project_id, skills)) = working.get(&person.person_id) else {
+            continue;
+        };
+        let Some(progress) = progress else {
+            cmd.add_component(*entity, SkillProgress::default());
+            continue;
+        };
+        let mut changed = false;
+        for skill in skills {
+            changed |= progress.practice(
+                skill_set,
+                skill,
+                WORK_EXPERIENCE_PER_TICK,
+                SkillGrowthSource::Work,
+                stats,
+                global_skills,
+                now,
+            );
+            let value = skill_set.skills.get(skill).copied().unwrap_or(0);
+            let mentored = best_on_project
+                .get(&(*project_id, skill.clone()))
+                .is_some_and(|best| *best >= value + MENTORING_SKILL_GAP);
+            if mentored {
+                changed |= progress.practice(
+                    skill_set,
+                    skill,
+                    MENTORING_EXPERIENCE_PER_TICK,
+                    SkillGrowthSource::Mentoring,
+                    stats,
+                    global_skills,
+                    now,
+                );
+            }
+        }
+        if changed {
+            debug!("{} improved at {:?}", person.name, skills);
+            cmd.add_component(*entity, Dirty);
+        }
+    }
+}
+
+/// Decays unused skills once a day. Employees without [`SkillProgress`] get one.
+#[system(for_each)]
+pub fn decay_unused_skills(
+    #[resource] tick_counter: &Arc<TickCounter>,
+    entity: &Entity,
+    _person: &Person,
+    skill_set: &mut SkillSet,
+    progress: Option<&mut SkillProgress>,
+    cmd: &mut CommandBuffer,
+) {
+    let Some(progress) = progress else {
+        cmd.add_component(*entity, SkillProgress::default());
+        return;
+    };
+    let now = tick_counter.current_date();
+    if now.quarter_tick == 1 && progress.decay(skill_set, now) {
+        cmd.add_component(*entity, Dirty);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::person::skills::{Domain, SkillLink, Tier};
+    use crate::sim::person::stats::StatsConfig;
+
+    fn skill(id: &str, feedforward: Vec<SkillLink>) -> GlobalSkill {
+        GlobalSkill {
+            id: SkillId(id.to_string()),
+            slug: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            tier: Tier::Foundational,
+            domain: vec![Domain::Execution],
+            feedforward,
+            feedback: vec![],
+            related_stats: vec![],
+        }
+    }
+
+    fn id(id: &str) -> SkillId {
+        SkillId(id.to_string())
+    }
+
+    // Test skills have no related stats, so the values do not matter
+    fn stats() -> Stats {
+        StatsConfig::default().into()
+    }
+
+    #[test]
+    fn practice_levels_up_and_records_history() {
+        let global_skills = HashMap::from([(id("a"), skill("a", vec![]))]);
+        let mut skill_set = SkillSet { skills: HashMap::from([(id("a"), 0)]) };
+        let mut progress = SkillProgress::default();
+
+        assert!(!progress.practice(&mut skill_set, &id("a"), 0.5, SkillGrowthSource::Work, &stats(), &global_skills, SimDate::default()));
+        assert!(progress.practice(&mut skill_set, &id("a"), 0.5, SkillGrowthSource::Work, &stats(), &global_skills, SimDate::default()));
+        assert_eq!(skill_set.skills[&id("a")], 1);
+        assert_eq!(progress.history.len(), 1);
+        assert_eq!(progress.history[0].source, SkillGrowthSource::Work);
+    }
+
+    #[test]
+    fn higher_skills_grow_slower() {
+        let global_skills = HashMap::from([(id("a"), skill("a", vec![])), (id("b"), skill("b", vec![]))]);
+        let mut skill_set = SkillSet { skills: HashMap::from([(id("a"), 10), (id("b"), 90)]) };
+        let mut progress = SkillProgress::default();
+        for skill in [id("a"), id("b")] {
+            progress.practice(&mut skill_set, &skill, 0.5, SkillGrowthSource::Work, &stats(), &global_skills, SimDate::default());
+        }
+        assert!(progress.experience[&id("a")] > progress.experience[&id("b")]);
+    }
+
+    #[test]
+    fn practice_carries_over_to_linked_skills() {
+        let global_skills = HashMap::from([
+            (id("a"), skill("a", vec![SkillLink::new(id("b"), 100), SkillLink::new(id("c"), 100)])),
+            (id("b"), skill("b", vec![])),
+        ]);
+        let mut skill_set = SkillSet { skills: HashMap::from([(id("a"), 0), (id("b"), 0)]) };
+        let mut progress = SkillProgress::default();
+        progress.practice(&mut skill_set, &id("a"), 0.5, SkillGrowthSource::Work, &stats(), &global_skills, SimDate::default());
+
+        assert!(progress.experience[&id("b")] > 0.0);
+        assert!(progress.experience[&id("b")] < progress.experience[&id("a")]);
+        // Skills the employee does not have are not picked up through links
+        assert!(!skill_set.skills.contains_key(&id("c")));
+    }
+
+    #[test]
+    fn unused_skills_decay_after_the_grace_period() {
+        let mut skill_set = SkillSet { skills: HashMap::from([(id("a"), 50)]) };
+        let mut progress = SkillProgress::default();
+        let start = SimDate::from(1);
+        progress.decay(&mut skill_set, start);
+
+        let mut day = 1;
+        while skill_set.skills[&id("a")] == 50 {
+            progress.decay(&mut skill_set, SimDate::from(1 + day * SimDate::QUARTER_TICKS_PER_DAY));
+            day += 1;
+        }
+        assert!(day > SKILL_DECAY_GRACE_DAYS);
+        assert_eq!(progress.history.back().unwrap().source, SkillGrowthSource::Decay);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::master_data::skills::SkillDef;
+use crate::master_data::skills::SkillData;
 use crate::sim::person::stats::StatType;
 use bincode::{Decode, Encode};
 use serde::{Deserialize, Serialize};
@@ -10,16 +10,8 @@ use tracing::{error, warn};
 #[derive(Clone, Debug, Serialize, Deserialize, Hash, Eq, PartialEq, Encode, Decode)]
 pub struct SkillId(pub String);
 
-struct SkillRequirement {
-    direct_requirement: Vec<SkillLink>,
-    indirect_requirement: Vec<SkillLink>,
-}
-
-struct SkillBoost {
-    direct_boost: Vec<SkillLink>,
-    indirect_boost: Vec<SkillLink>,
-}
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Skill tiers, in the order skills build on each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Tier {
     Foundational,
     Conceptual,
@@ -116,13 +108,13 @@ impl fmt::Display for GlobalSkill {
     }
 }
 
-impl From<&SkillDef> for GlobalSkill {
-    fn from(value: &SkillDef) -> Self {
+impl From<&SkillData> for GlobalSkill {
+    fn from(value: &SkillData) -> Self {
         Self {
-            id: SkillId(value.id.parse().unwrap()),
-            slug: value.id.parse().unwrap(),
-            name: value.name.parse().unwrap(),
-            description: value.description.parse().unwrap(),
+            id: SkillId(value.id.clone()),
+            slug: value.id.clone(),
+            name: value.name.clone(),
+            description: value.description.clone(),
             tier: value.tier.parse().unwrap(),
             domain: value.domain.split(",").fold(Vec::<Domain>::new(),|mut acc,s|{ acc.push(s.parse().unwrap_or_else(|_| { error!("Failed to parse into domain: {}. Error:", s );Domain::Execution})); acc } ),// .map(|val|{ value}) .parse().unwrap_or_else(|_| { error!("{}", value.domain );Domain::Execution}),
             feedforward: vec![],
@@ -142,10 +134,17 @@ impl From<&SkillDef> for GlobalSkill {
 }
 
 
-#[derive(Clone)]
+/// Link to another skill in the skill graph. Practice of a skill carries over to the linked skill by `factor` percent.
+#[derive(Clone, Debug)]
 pub struct SkillLink {
-    target: SkillId,
-    factor: u8,
+    pub target: SkillId,
+    pub factor: u8,
+}
+
+impl SkillLink {
+    pub fn new(target: SkillId, factor: u8) -> Self {
+        Self { target, factor }
+    }
 }
 
 struct SkillThreshold {
@@ -20,7 +20,7 @@ use legion::systems::CommandBuffer;
 use legion::Entity;
 use rand::prelude::*;
 use rand::seq::IteratorRandom;
-use rand::{rng, Rng};
+use rand::Rng;
 use rand_distr::{Distribution, Normal};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -35,19 +35,18 @@ use crate::sim::calendar::availability::{MonthlyAvailability, YearMonth};
 use crate::sim::person::thoughts::{ArchivedThoughts, Thoughts};
 use crate::sim::sim_date::sim_date::SimDate;
 
-pub fn bounded_normal(mean: f64, std_dev: f64, min: i16, max: i16) -> i16 {
+pub fn bounded_normal(rng: &mut impl Rng, mean: f64, std_dev: f64, min: i16, max: i16) -> i16 {
     let normal = Normal::new(mean, std_dev).unwrap();
-    let mut rng = rng();
 
     for _ in 0..10 {
-        let sample = normal.sample(&mut rng).round();
+        let sample = normal.sample(rng).round();
         if sample >= min.into() && sample <= max.into() {
             return sample as i16;
         }
     }
 
     // Escape hatch: clamp after 10 failed tries
-    let fallback = normal.sample(&mut rng).round();
+    let fallback = normal.sample(rng).round();
     return fallback.clamp(min.into(), max.into()) as i16;
 }
 
@@ -74,6 +73,18 @@ impl TalentGrade {
             TalentGrade::Exceptional => (93.0, 4.0), // Near-perfect, but not boringly maxed
         }
     }
+
+    /// Weekly salary an employee of this grade is paid.
+    pub fn weekly_salary(self) -> i64 {
+        match self {
+            TalentGrade::Basic => 800,
+            TalentGrade::Apt => 1_000,
+            TalentGrade::Sharp => 1_300,
+            TalentGrade::Gifted => 1_700,
+            TalentGrade::Brilliant => 2_200,
+            TalentGrade::Exceptional => 3_000,
+        }
+    }
 }
 impl Display for TalentGrade {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
@@ -88,7 +99,7 @@ impl Display for TalentGrade {
         write!(f, "{}", label)
     }
 }
-pub fn get_random_line_from_file(asset_path: AssetBasePath) -> Option<String> {
+pub fn get_random_line_from_file(rng: &mut impl Rng, asset_path: AssetBasePath) -> Option<String> {
     // Build full path to names/first.txt
     let base_path = Path::new(&asset_path.0);
     let file_path = base_path.join("names").join("first.txt");
@@ -99,8 +110,7 @@ pub fn get_random_line_from_file(asset_path: AssetBasePath) -> Option<String> {
     let reader = BufReader::new(file);
 
     // Choose a random line using iterator sampling
-    let mut rng = rng();
-    let line = reader.lines().filter_map(Result::ok).choose(&mut rng);
+    let line = reader.lines().filter_map(Result::ok).choose(rng);
 
     if line.is_none() {
         error!("File is empty or unreadable!");
@@ -131,12 +141,13 @@ impl NameDictionary {
     }
 }
 
-pub fn generate_full_name(gender: &Gender, asset_path: &AssetBasePath) -> Option<String> {
-    let first = generate_name_part(gender, &NameParts::First, &asset_path)?;
-    let last = generate_name_part(gender, &NameParts::Last, &asset_path)?;
+pub fn generate_full_name(rng: &mut impl Rng, gender: &Gender, asset_path: &AssetBasePath) -> Option<String> {
+    let first = generate_name_part(rng, gender, &NameParts::First, &asset_path)?;
+    let last = generate_name_part(rng, gender, &NameParts::Last, &asset_path)?;
     Some(format!("{} {}", first, last))
 }
 fn generate_name_part(
+    rng: &mut impl Rng,
     gender: &Gender,
     part: &NameParts,
     asset_path: &AssetBasePath,
@@ -161,9 +172,8 @@ fn generate_name_part(
         .ok()?;
 
     let reader = BufReader::new(file);
-    let mut rng = rng();
 
-    let line = reader.lines().filter_map(Result::ok).choose(&mut rng);
+    let line = reader.lines().filter_map(Result::ok).choose(rng);
 
     if line.is_none() {
         eprintln!("No lines found in file: {:?}", file_path);
@@ -172,8 +182,7 @@ fn generate_name_part(
     line
 }
 
-pub fn random_gender() -> Gender {
-    let mut rng = rand::rng();
+pub fn random_gender(rng: &mut impl Rng) -> Gender {
     if rng.random_bool(0.5) {
         Gender::Male
     } else {
@@ -181,12 +190,12 @@ pub fn random_gender() -> Gender {
     }
 }
 
-fn generate_stats(tier: TalentGrade) -> Stats {
+fn generate_stats(rng: &mut impl Rng, tier: TalentGrade) -> Stats {
     let (mean, std_dev) = tier.stat_distribution();
 
     macro_rules! gen {
         () => {{
-            let val = bounded_normal(mean, std_dev, 0, 100) as u16;
+            let val = bounded_normal(rng, mean, std_dev, 0, 100) as u16;
             (val, val as u32 * 1000)
         }};
     }
@@ -217,28 +226,29 @@ fn generate_stats(tier: TalentGrade) -> Stats {
     let mut stats = config.into();
     let mut choices = (1..=4).collect::<Vec<_>>();
 
-    match choices.choose(&mut rng()) {
+    match choices.choose(rng) {
         None => {}
         Some(&1) => {
-            sculpt_monofocus(&mut stats);
+            sculpt_monofocus(rng, &mut stats);
         }
         Some(&2) => {
-            sculpt_axis_bias(&mut stats);
+            sculpt_axis_bias(rng, &mut stats);
         }
         Some(&3) => {
-            sculpt_blindspot(&mut stats);
+            sculpt_blindspot(rng, &mut stats);
         }
         Some(&4) => {
-            sculpt_contrasting_pair(&mut stats);
+            sculpt_contrasting_pair(rng, &mut stats);
         }
         _ => {}
     }
 
-    sculpt_blindspot(&mut stats);
+    sculpt_blindspot(rng, &mut stats);
     stats
 }
 
 fn generate_profile_picture(
+    rng: &mut impl Rng,
     gender: Gender,
     used_picture_registry: &UsedProfilePictureRegistry,
 ) -> ProfilePicture {
@@ -252,7 +262,6 @@ fn generate_profile_picture(
 
     const MAX_ATTEMPTS: usize = 10;
 
-    let mut rng = rand::rng();
     let mut profile_picture = ProfilePicture::default();
     for _ in 0..MAX_ATTEMPTS {
         let random_category = if rng.random_range(0..100) < 70 {
@@ -279,13 +288,13 @@ fn generate_profile_picture(
     profile_picture
 }
 
-fn generate_personality_matrix() -> PersonalityMatrix {
+fn generate_personality_matrix(rng: &mut impl Rng) -> PersonalityMatrix {
     let matrix = PersonalityMatrix {
-        assertiveness: bounded_normal(0f64, 40f64, -100, 100) as i8,
-        structure_preference: bounded_normal(0f64, 40f64, -100, 100) as i8,
-        openness: bounded_normal(0f64, 40f64, -100, 100) as i8,
-        sociability: bounded_normal(0f64, 40f64, -100, 100) as i8,
-        influence: bounded_normal(0f64, 40f64, -100, 100) as i8,
+        assertiveness: bounded_normal(rng, 0f64, 40f64, -100, 100) as i8,
+        structure_preference: bounded_normal(rng, 0f64, 40f64, -100, 100) as i8,
+        openness: bounded_normal(rng, 0f64, 40f64, -100, 100) as i8,
+        sociability: bounded_normal(rng, 0f64, 40f64, -100, 100) as i8,
+        influence: bounded_normal(rng, 0f64, 40f64, -100, 100) as i8,
     };
     // info!("Generated someone that is {},{}", matrix.describe_axis(PersonalityAxis::Assertiveness), matrix.assertiveness);
     // info!("Generated someone that is {},{}", matrix.describe_axis(PersonalityAxis::StructurePreference), matrix.structure_preference);
@@ -296,9 +305,10 @@ fn generate_personality_matrix() -> PersonalityMatrix {
     return matrix;
 }
 
-#[tracing::instrument(level = "trace", skip(cmd, asset_path))]
+#[tracing::instrument(level = "trace", skip(cmd, rng, asset_path))]
 pub fn spawn_person(
     cmd: &mut CommandBuffer,
+    rng: &mut impl Rng,
     tier: TalentGrade,
     asset_path: &AssetBasePath,
     used_portraits: &UsedProfilePictureRegistry,
@@ -318,10 +328,10 @@ pub fn spawn_person(
     let id = PersonId(person_registry.generate_id());
 
     // let peron_id = person_registry.generate_id();
-    let gender = random_gender();
+    let gender = random_gender(rng);
     let person = Person {
         gender: gender,
-        name: generate_full_name(&gender, &asset_path).expect("Cannot generate full name"),
+        name: generate_full_name(rng, &gender, &asset_path).expect("Cannot generate full name"),
         person_id: id.clone(),
         team: None,
         talent_grade: tier,
@@ -330,10 +340,10 @@ pub fn spawn_person(
     let person_clone = person.clone();
     trace!("Created person {}", person.name);
     trace!("{:?}", person);
-    let profile_picture = generate_profile_picture(gender, used_portraits);
-    let stats = generate_stats(tier);
-    let personality_matrix = generate_personality_matrix();
-    let skillset = assign_skills(&stats, &global_skills);
+    let profile_picture = generate_profile_picture(rng, gender, used_portraits);
+    let stats = generate_stats(rng, tier);
+    let personality_matrix = generate_personality_matrix(rng);
+    let skillset = assign_skills(rng, &stats, &global_skills);
     let mut monthly_availability = MonthlyAvailability::default();
     monthly_availability.get_or_create_month_for_date(&SimDate::from(current_tick));
     let entity = cmd.push((
@@ -366,13 +376,13 @@ pub fn spawn_person(
     )
 }
 
-fn assign_skills(stats: &Stats, all_skills: &Vec<&GlobalSkill>) -> SkillSet {
+fn assign_skills(rng: &mut impl Rng, stats: &Stats, all_skills: &Vec<&GlobalSkill>) -> SkillSet {
     let mut skills = HashMap::new();
 
     for skill in all_skills {
         let tier = determine_skill_tier(stats, &skill.related_stats);
         if let Some((mean, stddev, min)) = skill_value_by_tier(tier) {
-            let value = bounded_normal(mean as f64, stddev as f64, min as i16, 100) as u32;
+            let value = bounded_normal(rng, mean as f64, stddev as f64, min as i16, 100) as u32;
             skills.insert(skill.id.clone(), value);
         }
     }
@@ -404,3 +414,24 @@ fn skill_value_by_tier(tier: u8) -> Option<(u32, u32, u32)> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use strum::IntoEnumIterator;
+
+    fn generate(seed: u64) -> (Vec<u16>, String) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let stats = generate_stats(&mut rng, TalentGrade::Sharp);
+        let personality = generate_personality_matrix(&mut rng);
+        (StatType::iter().map(|stat| stats.get_stat(stat)).collect(), format!("{:?}", personality))
+    }
+
+    #[test]
+    fn same_seed_generates_the_same_person() {
+        assert_eq!(generate(11), generate(11));
+        assert_ne!(generate(11), generate(12));
+    }
+}
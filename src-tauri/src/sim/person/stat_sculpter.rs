@@ -1,7 +1,7 @@
 use crate::sim::person::stats::{StatGroup, StatType, Stats};
 use crate::sim::utils::random::random_variation;
 use rand::prelude::*;
-use rand::{rng, Rng};
+use rand::Rng;
 use std::cmp::min;
 use strum::IntoEnumIterator;
 use tracing::{debug, info};
@@ -12,26 +12,26 @@ use tracing::{debug, info};
 ///
 /// - Pick 1 stat randomly → +X (e.g. +10)
 /// - Randomly reduce other stats until budget is balanced
-pub fn sculpt_monofocus(stats: &mut Stats) {
+pub fn sculpt_monofocus(rng: &mut impl Rng, stats: &mut Stats) {
     /// Settings
     const AMOUNT: i32 = 20;
 
     let total_points = stats.total(); // track before mutation
 
-    let high_stat = random_stat();
+    let high_stat = random_stat(rng);
     stats.adjust(high_stat, AMOUNT as f32);
 
     let mut reduction = AMOUNT;
     let mut others = StatType::iter()
         .filter(|s| *s != high_stat)
         .collect::<Vec<_>>();
-    others.shuffle(&mut rng());
+    others.shuffle(rng);
 
     for stat in others {
         if reduction <= 0 {
             break;
         }
-        let delta = random_variation(min(reduction, 3), 1);
+        let delta = random_variation(rng, min(reduction, 3), 1);
         if stats.get_stat(stat) >= (delta + 10) as u16 {
             // avoid floor
             stats.adjust(stat, -delta as f32);
@@ -39,11 +39,11 @@ pub fn sculpt_monofocus(stats: &mut Stats) {
         }
     }
 
-    stats.normalize_to(total_points); // rebalance if needed
+    stats.normalize_to(rng, total_points); // rebalance if needed
 }
 
-fn random_stat() -> StatType {
-    StatType::iter().choose(&mut rng()).unwrap()
+fn random_stat(rng: &mut impl Rng) -> StatType {
+    StatType::iter().choose(rng).unwrap()
     // return rng().random_range(StatType)
 }
 
@@ -54,14 +54,14 @@ fn random_stat() -> StatType {
 /// - Pick 1 group randomly (Cognition, Perception, etc.)
 /// - Boost each stat in the group by +3–5
 /// - Reduce others proportionally, skipping floors
-pub fn sculpt_axis_bias(stats: &mut Stats) {
+pub fn sculpt_axis_bias(rng: &mut impl Rng, stats: &mut Stats) {
     let total_points = stats.total();
 
     let base_modifier = 12;
     let variable = 5;
 
-    let boost_amount = random_variation(base_modifier, variable);
-    let group = random_stat_group(); // returns Vec<StatType>
+    let boost_amount = random_variation(rng, base_modifier, variable);
+    let group = random_stat_group(rng); // returns Vec<StatType>
 
     for stat in group.members() {
         stats.adjust(stat, boost_amount as f32);
@@ -71,7 +71,7 @@ pub fn sculpt_axis_bias(stats: &mut Stats) {
     let mut others = StatType::iter()
         .filter(|s| !group.members().contains(s))
         .collect::<Vec<_>>();
-    others.shuffle(&mut rng());
+    others.shuffle(rng);
 
     for stat in others {
         if reduction <= 0 {
@@ -85,11 +85,11 @@ pub fn sculpt_axis_bias(stats: &mut Stats) {
         }
     }
 
-    stats.normalize_to(total_points);
+    stats.normalize_to(rng, total_points);
 }
 
-pub fn random_stat_group() -> StatGroup {
-    StatGroup::iter().choose(&mut rng()).unwrap()
+pub fn random_stat_group(rng: &mut impl Rng) -> StatGroup {
+    StatGroup::iter().choose(rng).unwrap()
 }
 
 /// **Category:** 6 – Negative Space
@@ -98,13 +98,13 @@ pub fn random_stat_group() -> StatGroup {
 ///
 /// - Choose 1 stat randomly → set to low (e.g. 15)
 /// - Redistribute excess across others
-pub fn sculpt_blindspot(stats: &mut Stats) {
+pub fn sculpt_blindspot(rng: &mut impl Rng, stats: &mut Stats) {
     let total_points = stats.total();
 
     let random_high = stats.stat_filter(55, |v, t| v >= t);
-    let mut low_stat = random_stat();
+    let mut low_stat = random_stat(rng);
     if !random_high.is_empty() {
-        low_stat = random_high.iter().choose(&mut rng()).unwrap().clone();
+        low_stat = random_high.iter().choose(rng).unwrap().clone();
     }
 
     let current = stats.get_stat(low_stat);
@@ -115,7 +115,7 @@ pub fn sculpt_blindspot(stats: &mut Stats) {
         let mut others = StatType::iter()
             .filter(|s| *s != low_stat)
             .collect::<Vec<_>>();
-        others.shuffle(&mut rng());
+        others.shuffle(rng);
         for stat in others {
             if diff <= 0 {
                 break;
@@ -126,7 +126,7 @@ pub fn sculpt_blindspot(stats: &mut Stats) {
         }
     }
 
-    stats.normalize_to(total_points);
+    stats.normalize_to(rng, total_points);
 }
 ///
 /// **Category:** 3 – Asymmetry
@@ -135,19 +135,19 @@ pub fn sculpt_blindspot(stats: &mut Stats) {
 ///
 /// - Pick two unrelated stats randomly (not same group)
 /// - Push one up, one down
-pub fn sculpt_contrasting_pair(stats: &mut Stats) {
+pub fn sculpt_contrasting_pair(rng: &mut impl Rng, stats: &mut Stats) {
     let amount = 12f32;
     let total_points = stats.total();
 
-    let a = random_stat();
-    let mut b = random_stat();
+    let a = random_stat(rng);
+    let mut b = random_stat(rng);
     while same_group(a, b) || a == b {
-        b = random_stat();
+        b = random_stat(rng);
     }
 
     stats.adjust(a, amount);
     stats.adjust(b, -amount);
-    stats.normalize_to(total_points);
+    stats.normalize_to(rng, total_points);
 }
 
 pub fn same_group(stat1: StatType, stat2: StatType) -> bool {
@@ -2,7 +2,6 @@ use bincode::{Decode, Encode};
 use rand::prelude::SliceRandom;
 use rand::Rng;
 use crate::utils::discreet_float::DiscreteFloat33;
-// Removed unused 'rng' import, will use thread_rng
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 use strum_macros::EnumIter;
@@ -117,12 +116,11 @@ impl Stats {
     ///
     /// # Example
     /// ```
-    /// stats.normalize_to(60);
+    /// stats.normalize_to(&mut rng, 60);
     /// ```
-    pub fn normalize_to(&mut self, target: u16) {
+    pub fn normalize_to(&mut self, rng: &mut impl Rng, target: u16) {
         use StatType::*;
 
-        let mut rng = rand::rng();
         let mut stat_pool = vec![
             Judgement,
             Creativity,
@@ -137,7 +135,7 @@ impl Stats {
         ];
 
         while self.total() < target {
-            stat_pool.shuffle(&mut rng);
+            stat_pool.shuffle(rng);
             for stat in &stat_pool {
                 let increment = if rng.random_bool(0.5) { 1 } else { 2 };
                 let current = self.get_stat(*stat);
@@ -410,6 +408,8 @@ impl Stats {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
 
     fn sample_config() -> StatsConfig {
         StatsConfig {
@@ -470,7 +470,7 @@ mod tests {
     #[test]
     fn normalize_to_reaches_target_total() {
         let mut stats: Stats = StatsConfig::default().into();
-        stats.normalize_to(10);
+        stats.normalize_to(&mut StdRng::seed_from_u64(1), 10);
         assert!(stats.total() >= 10);
     }
 
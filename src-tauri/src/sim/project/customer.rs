@@ -1,13 +1,223 @@
-// #[derive(Debug, Serialize, Deserialize, Encode, Decode, Clone)]
-// pub struct Customer {
-//     id: CustomerId,
-//     name: String,
-//     industry: IndustryType,           // e.g., Retail, Finance, Manufacturing
-//     reputation: u8,                   // Optional: affects future trust or budget
-//     projects: Vec<ProjectOffer>,     // List of projects currently offered
-// }
-// 
-// #[derive(Debug, Serialize, Deserialize, Encode, Decode, Clone)]
-// pub struct IndustryType{
-//     
-// }
\ No newline at end of file
+use crate::sim::project::project::{ProjectId, ProjectStatus};
+use crate::sim::project::utils::NewProject;
+use crate::sim::sim_date::sim_date::SimDate;
+use bincode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter};
+
+/// Reputation customers start out with, on a 0 - 100 scale.
+pub const STARTING_REPUTATION: u8 = 50;
+pub const MAX_REPUTATION: u8 = 100;
+
+/// Share of the payout customers still pay for a project that was delivered after its deadline.
+pub const LATE_DELIVERY_PAYOUT_RATIO: f32 = 0.8;
+
+#[derive(Debug, Serialize, Deserialize, Encode, Decode, Clone)]
+pub struct Customer {
+    pub id: CustomerId,
+    pub name: String,
+    pub industry: IndustryType,
+    pub reputation: u8,                // How much the customer trusts the company, affects offers and payouts
+    pub budget: u32,                   // Upper bound of a single project payout
+    pub offers: Vec<ProjectOffer>,     // Offers currently open to the company
+    pub projects: Vec<CustomerProject>, // Accepted offers that have not reached a final status yet
+}
+
+impl Customer {
+    pub fn adjust_reputation(&mut self, delta: i16) {
+        self.reputation = (self.reputation as i16 + delta).clamp(0, MAX_REPUTATION as i16) as u8;
+    }
+
+    pub fn take_offer(&mut self, offer_id: OfferId) -> Option<ProjectOffer> {
+        let index = self.offers.iter().position(|offer| offer.id == offer_id)?;
+        Some(self.offers.remove(index))
+    }
+
+    /// Applies the status changes of the customer's projects to its reputation and collects
+    /// the payouts of delivered projects. Projects that reached a final status stop being tracked.
+    pub fn record_project_statuses<F>(&mut self, mut status_of: F) -> ProjectStatusUpdate
+    where
+        F: FnMut(ProjectId) -> Option<ProjectStatus>,
+    {
+        let mut update = ProjectStatusUpdate::default();
+        for project in self.projects.iter_mut() {
+            let Some(status) = status_of(project.project_id) else {
+                continue;
+            };
+            if status == project.last_status {
+                continue;
+            }
+            update.reputation_change += reputation_change(project.last_status, status);
+            if status == ProjectStatus::Delivered {
+                let payout = if project.last_status == ProjectStatus::Overdue {
+                    (project.payout as f32 * LATE_DELIVERY_PAYOUT_RATIO) as u32
+                } else {
+                    project.payout
+                };
+                update.payouts.push((project.project_id, payout));
+            }
+            project.last_status = status;
+        }
+        self.projects.retain(|project| !project.last_status.is_final());
+        self.adjust_reputation(update.reputation_change);
+        update
+    }
+}
+
+/// Outcome of [`Customer::record_project_statuses`].
+#[derive(Debug, Default, PartialEq)]
+pub struct ProjectStatusUpdate {
+    pub reputation_change: i16,
+    /// Payouts owed for projects that were just delivered
+    pub payouts: Vec<(ProjectId, u32)>,
+}
+
+/// Reputation change when a customer's project moves from `previous` to `next`.
+pub fn reputation_change(previous: ProjectStatus, next: ProjectStatus) -> i16 {
+    match (previous, next) {
+        (ProjectStatus::Overdue, ProjectStatus::Delivered) => 2,
+        (_, ProjectStatus::Delivered) => 8,
+        (_, ProjectStatus::Overdue) => -5,
+        (_, ProjectStatus::Cancelled) => -10,
+        _ => 0,
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Encode, Decode, Clone, Copy, PartialEq, Eq, Hash, Display, EnumIter)]
+pub enum IndustryType {
+    Retail,
+    Finance,
+    Manufacturing,
+    Healthcare,
+    Logistics,
+    Media,
+}
+
+impl IndustryType {
+    /// Range customers in the industry budget a single project for.
+    pub fn budget_range(&self) -> (u32, u32) {
+        match self {
+            IndustryType::Retail => (20_000, 60_000),
+            IndustryType::Finance => (60_000, 200_000),
+            IndustryType::Manufacturing => (40_000, 120_000),
+            IndustryType::Healthcare => (50_000, 150_000),
+            IndustryType::Logistics => (30_000, 90_000),
+            IndustryType::Media => (15_000, 50_000),
+        }
+    }
+}
+
+/// A project a customer is offering to the company.
+/// The deadline is counted from the day the offer is accepted.
+#[derive(Debug, Serialize, Deserialize, Encode, Decode, Clone)]
+pub struct ProjectOffer {
+    pub id: OfferId,
+    pub customer_id: CustomerId,
+    pub project: NewProject,
+    pub payout: u32,
+    pub duration_days: u32,
+    pub posted_at: SimDate,
+    pub expires_at: SimDate,
+}
+
+impl ProjectOffer {
+    pub fn total_work(&self) -> f32 {
+        self.project.total_work()
+    }
+
+    pub fn is_expired(&self, now: &SimDate) -> bool {
+        *now > self.expires_at
+    }
+}
+
+/// An accepted offer, tracked until the project reaches a final status.
+#[derive(Debug, Serialize, Deserialize, Encode, Decode, Clone)]
+pub struct CustomerProject {
+    pub project_id: ProjectId,
+    pub payout: u32,
+    pub last_status: ProjectStatus,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Encode, Decode)]
+pub struct CustomerId(pub u32);
+impl From<CustomerId> for u32 {
+    fn from(value: CustomerId) -> Self {
+        value.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Encode, Decode)]
+pub struct OfferId(pub u32);
+impl From<OfferId> for u32 {
+    fn from(value: OfferId) -> Self {
+        value.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn customer() -> Customer {
+        Customer {
+            id: CustomerId(1),
+            name: "Acme".to_string(),
+            industry: IndustryType::Retail,
+            reputation: STARTING_REPUTATION,
+            budget: 50_000,
+            offers: vec![],
+            projects: vec![
+                CustomerProject { project_id: ProjectId(1), payout: 1_000, last_status: ProjectStatus::Active },
+                CustomerProject { project_id: ProjectId(2), payout: 1_000, last_status: ProjectStatus::Active },
+            ],
+        }
+    }
+
+    #[test]
+    fn on_time_delivery_beats_late_delivery() {
+        let on_time = reputation_change(ProjectStatus::Active, ProjectStatus::Delivered);
+        let late = reputation_change(ProjectStatus::Active, ProjectStatus::Overdue)
+            + reputation_change(ProjectStatus::Overdue, ProjectStatus::Delivered);
+        assert!(on_time > 0);
+        assert!(late < on_time);
+        assert!(reputation_change(ProjectStatus::Active, ProjectStatus::Cancelled) < late);
+    }
+
+    #[test]
+    fn final_projects_stop_being_tracked() {
+        let mut customer = customer();
+        let statuses = HashMap::from([
+            (ProjectId(1), ProjectStatus::Delivered),
+            (ProjectId(2), ProjectStatus::Overdue),
+        ]);
+        let update = customer.record_project_statuses(|id| statuses.get(&id).copied());
+        assert_eq!(update.reputation_change, 8 - 5);
+        assert_eq!(update.payouts, vec![(ProjectId(1), 1_000)]);
+        assert_eq!(customer.reputation, STARTING_REPUTATION + 3);
+        assert_eq!(customer.projects.len(), 1);
+        assert_eq!(customer.projects[0].last_status, ProjectStatus::Overdue);
+
+        // Unchanged statuses do not count twice
+        let update = customer.record_project_statuses(|id| statuses.get(&id).copied());
+        assert_eq!(update, ProjectStatusUpdate::default());
+    }
+
+    #[test]
+    fn late_delivery_pays_less() {
+        let mut customer = customer();
+        customer.projects[0].last_status = ProjectStatus::Overdue;
+        let update = customer.record_project_statuses(|_| Some(ProjectStatus::Delivered));
+        assert_eq!(update.payouts, vec![(ProjectId(1), 800), (ProjectId(2), 1_000)]);
+        assert!(customer.projects.is_empty());
+    }
+
+    #[test]
+    fn reputation_is_clamped() {
+        let mut customer = customer();
+        customer.adjust_reputation(500);
+        assert_eq!(customer.reputation, MAX_REPUTATION);
+        customer.adjust_reputation(-500);
+        assert_eq!(customer.reputation, 0);
+    }
+}
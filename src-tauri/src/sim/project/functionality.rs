@@ -1,14 +1,23 @@
+use crate::sim::project::project::ProjectId;
 use crate::sim::project::requirement::RequirementId;
 use crate::sim::project::task::TaskId;
+use bincode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode)]
 pub struct Functionality {
     pub id: FunctionalityId,
+    pub project_id: ProjectId,
     pub label: String,
     pub related_requirements: Vec<RequirementId>,
     pub task_ids: Vec<TaskId>,
     pub progress: f32, // [0.0 - 1.0]
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct FunctionalityId(pub u32);
\ No newline at end of file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Encode, Decode)]
+pub struct FunctionalityId(pub u32);
+impl From<FunctionalityId> for u32 {
+    fn from(value: FunctionalityId) -> Self {
+        value.0
+    }
+}
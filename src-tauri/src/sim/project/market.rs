@@ -0,0 +1,339 @@
+use crate::master_data::skills::GLOBAL_SKILLS;
+use crate::sim::company::company::PlayerControlled;
+use crate::sim::company::finance::{Finances, TransactionKind};
+use crate::sim::globals::{
+    BASE_DAILY_OFFER_CHANCE, BASE_WORK_PER_TICK, MAX_OPEN_OFFERS_PER_CUSTOMER, OFFER_LIFETIME_DAYS,
+    PAYOUT_PER_WORK_UNIT, STARTING_CUSTOMER_COUNT,
+};
+use crate::sim::person::skills::SkillId;
+use crate::sim::project::customer::{
+    Customer, CustomerId, IndustryType, OfferId, ProjectOffer, STARTING_REPUTATION,
+};
+use crate::sim::project::project::{Project, ProjectId, ProjectStatus};
+use crate::sim::project::utils::{NewFunctionality, NewProject, NewRequirement, NewTask};
+use crate::sim::registries::registry::Registry;
+use crate::sim::resources::global::{Dirty, TickCounter};
+use crate::sim::sim_date::sim_date::SimDate;
+use crate::sim::utils::random::{RngStream, SimRng};
+use legion::systems::CommandBuffer;
+use legion::world::SubWorld;
+use legion::{system, Entity, IntoQuery, Query};
+use rand::prelude::IndexedRandom;
+use rand::seq::IteratorRandom;
+use rand::Rng;
+use std::collections::HashMap;
+use std::sync::Arc;
+use strum::IntoEnumIterator;
+use tracing::{debug, info};
+
+// Customers post their offers at 8am
+const OFFER_POSTING_QUARTER_TICK: u8 = 33;
+
+// Quarter ticks of work an employee is expected to put in each day, used to size deadlines
+const WORK_QUARTER_TICKS_PER_DAY: f32 = 32.0;
+
+const NAME_PREFIXES: [&str; 10] = [
+    "Blue", "Summit", "Northwind", "Apex", "Harbor", "Evergreen", "Silver", "Ironclad", "Bright",
+    "Crescent",
+];
+const NAME_SUFFIXES: [&str; 8] = [
+    "Holdings", "Group", "Partners", "Industries", "Co.", "Labs", "Systems", "Ventures",
+];
+const PRODUCTS: [&str; 8] = [
+    "Portal", "Mobile App", "Dashboard", "Billing Platform", "Inventory System", "Booking Engine",
+    "Reporting Suite", "Customer CRM",
+];
+const REQUIREMENTS: [&str; 8] = [
+    "User accounts", "Payments", "Reporting", "Search", "Notifications", "Data import",
+    "Access control", "Audit trail",
+];
+const FUNCTIONALITIES: [&str; 8] = [
+    "API", "Data model", "UI screens", "Validation", "Integration", "Background jobs",
+    "Test coverage", "Migration",
+];
+
+pub fn generate_customer(rng: &mut impl Rng, id: CustomerId) -> Customer {
+    let industry = IndustryType::iter().choose(rng).unwrap_or(IndustryType::Retail);
+    let (min_budget, max_budget) = industry.budget_range();
+    Customer {
+        id,
+        name: format!(
+            "{} {}",
+            NAME_PREFIXES.choose(rng).unwrap(),
+            NAME_SUFFIXES.choose(rng).unwrap()
+        ),
+        industry,
+        reputation: STARTING_REPUTATION,
+        budget: rng.random_range(min_budget..=max_budget),
+        offers: vec![],
+        projects: vec![],
+    }
+}
+
+/// Generates a project offer sized and priced for the customer.
+/// Tasks may require up to two of `skills`.
+pub fn generate_offer(
+    rng: &mut impl Rng,
+    customer: &Customer,
+    offer_id: OfferId,
+    skills: &[SkillId],
+    now: SimDate,
+) -> ProjectOffer {
+    let requirements = (0..rng.random_range(1..=3))
+        .map(|_| NewRequirement {
+            description: REQUIREMENTS.choose(rng).unwrap().to_string(),
+            functionalities: (0..rng.random_range(1..=2))
+                .map(|_| {
+                    let label = FUNCTIONALITIES.choose(rng).unwrap().to_string();
+                    NewFunctionality {
+                        tasks: (0..rng.random_range(1..=3))
+                            .map(|index| generate_task(rng, &label, index + 1, skills))
+                            .collect(),
+                        label,
+                    }
+                })
+                .collect(),
+        })
+        .collect();
+
+    let project = NewProject {
+        name: format!("{} {}", customer.industry, PRODUCTS.choose(rng).unwrap()),
+        deadline: None,
+        requirements,
+    };
+    let total_work = project.total_work();
+    ProjectOffer {
+        id: offer_id,
+        customer_id: customer.id,
+        payout: offer_payout(total_work, customer),
+        duration_days: offer_duration_days(total_work, rng.random_range(1.5..2.5)),
+        project,
+        posted_at: now,
+        expires_at: SimDate::from(now.to_tick() + OFFER_LIFETIME_DAYS * SimDate::QUARTER_TICKS_PER_DAY),
+    }
+}
+
+fn generate_task(rng: &mut impl Rng, label: &str, part: usize, skills: &[SkillId]) -> NewTask {
+    let skill_count = rng.random_range(0..=2);
+    NewTask {
+        description: format!("{} part {}", label, part),
+        work: rng.random_range(20.0..80.0),
+        contribution_weight: 1.0,
+        required_skills: skills.choose_multiple(rng, skill_count).cloned().collect(),
+    }
+}
+
+/// Customers with a better opinion of the company pay up to 25% more, capped by their budget.
+pub fn offer_payout(total_work: f32, customer: &Customer) -> u32 {
+    let reputation_factor = 0.75 + customer.reputation as f32 / 200.0;
+    ((total_work * PAYOUT_PER_WORK_UNIT * reputation_factor) as u32).min(customer.budget)
+}
+
+/// Days a single average employee needs for `total_work`, stretched by `slack`.
+pub fn offer_duration_days(total_work: f32, slack: f32) -> u32 {
+    let days = total_work / (BASE_WORK_PER_TICK * WORK_QUARTER_TICKS_PER_DAY);
+    ((days * slack).ceil() as u32).max(2)
+}
+
+// Chance of posting an offer on a given day, from half the base chance at 0 reputation to 1.5x at 100
+fn daily_offer_chance(customer: &Customer) -> f64 {
+    BASE_DAILY_OFFER_CHANCE * (0.5 + customer.reputation as f64 / 100.0)
+}
+
+fn offer_skills() -> Vec<SkillId> {
+    GLOBAL_SKILLS
+        .get()
+        .map(|skills| {
+            let mut skills: Vec<SkillId> = skills.keys().cloned().collect();
+            // The offers pick from this list, keep it in the same order every run
+            skills.sort_by(|a, b| a.0.cmp(&b.0));
+            skills
+        })
+        .unwrap_or_default()
+}
+
+#[system]
+pub fn init_customers(
+    #[resource] customer_registry: &Arc<Registry<CustomerId, Entity>>,
+    #[resource] sim_rng: &mut SimRng,
+    cmd: &mut CommandBuffer,
+) {
+    info!("Generating customers...");
+    let rng = sim_rng.stream(RngStream::Market);
+    for _ in 0..STARTING_CUSTOMER_COUNT {
+        let customer_id = CustomerId(customer_registry.generate_id());
+        let customer = generate_customer(rng, customer_id);
+        debug!("Generated customer {} ({})", customer.name, customer.industry);
+        let entity = cmd.push((customer, Dirty));
+        customer_registry.insert(customer_id, entity);
+    }
+}
+
+// Once a day, drops expired offers and lets customers post new ones.
+#[system(for_each)]
+pub fn post_project_offers(
+    #[resource] tick_counter: &Arc<TickCounter>,
+    #[resource] offer_registry: &Arc<Registry<OfferId, Entity>>,
+    #[resource] sim_rng: &mut SimRng,
+    entity: &Entity,
+    customer: &mut Customer,
+    cmd: &mut CommandBuffer,
+) {
+    let now = tick_counter.current_date();
+    if now.quarter_tick != OFFER_POSTING_QUARTER_TICK {
+        return;
+    }
+
+    let open_offers = customer.offers.len();
+    customer.offers.retain(|offer| {
+        let expired = offer.is_expired(&now);
+        if expired {
+            debug!("Offer {:?} from {} expired", offer.id, customer.name);
+            offer_registry.remove(&offer.id);
+        }
+        !expired
+    });
+    let mut changed = open_offers != customer.offers.len();
+
+    let rng = sim_rng.stream(RngStream::Market);
+    if customer.offers.len() < MAX_OPEN_OFFERS_PER_CUSTOMER
+        && rng.random_bool(daily_offer_chance(customer).clamp(0.0, 1.0))
+    {
+        let offer_id = OfferId(offer_registry.generate_id());
+        let offer = generate_offer(rng, customer, offer_id, &offer_skills(), now);
+        info!(
+            "{} posted offer '{}' worth {}",
+            customer.name, offer.project.name, offer.payout
+        );
+        customer.offers.push(offer);
+        offer_registry.insert(offer_id, *entity);
+        changed = true;
+    }
+
+    if changed {
+        cmd.add_component(*entity, Dirty);
+    }
+}
+
+// Adjusts customer reputation as their projects get delivered, run late or get cancelled,
+// and pays the company for delivered projects.
+#[system]
+#[read_component(Project)]
+#[read_component(PlayerControlled)]
+#[write_component(Customer)]
+#[write_component(Finances)]
+pub fn settle_customer_projects(
+    #[resource] tick_counter: &Arc<TickCounter>,
+    world: &mut SubWorld,
+    cmd: &mut CommandBuffer,
+) {
+    let projects: HashMap<ProjectId, (ProjectStatus, String)> = <&Project>::query()
+        .iter(world)
+        .map(|project| (project.id, (project.status, project.name.clone())))
+        .collect();
+
+    let mut payouts = vec![];
+    for (entity, customer) in <(Entity, &mut Customer)>::query().iter_mut(world) {
+        let tracked = customer.projects.len();
+        let update = customer.record_project_statuses(|id| projects.get(&id).map(|(status, _)| *status));
+        if update.reputation_change != 0 {
+            info!(
+                "{} reputation changed by {} to {}",
+                customer.name, update.reputation_change, customer.reputation
+            );
+        }
+        if update.reputation_change != 0 || tracked != customer.projects.len() {
+            cmd.add_component(*entity, Dirty);
+        }
+        payouts.extend(update.payouts);
+    }
+    if payouts.is_empty() {
+        return;
+    }
+
+    let now = tick_counter.current_date();
+    for (finances, _) in <(&mut Finances, &PlayerControlled)>::query().iter_mut(world) {
+        for (project_id, payout) in &payouts {
+            let name = projects.get(project_id).map_or("", |(_, name)| name.as_str());
+            info!("Received {} for delivering '{}'", payout, name);
+            finances.record(
+                now,
+                TransactionKind::ProjectPayout,
+                *payout as i64,
+                format!("Delivered: {}", name),
+            );
+        }
+        finances.check_bankruptcy();
+    }
+}
+
+#[system]
+pub fn sync_registry_from_customer(
+    world: &SubWorld,
+    query: &mut Query<(&Customer, Entity)>,
+    #[resource] customer_registry: &Arc<Registry<CustomerId, Entity>>,
+) {
+    info!("Syncing registry from customer...");
+    let x = query
+        .iter(world)
+        .map(|(customer, entity)| (customer.id, *entity));
+    customer_registry.repopulate_from_entities(x);
+}
+
+// Offers are looked up through the customer entity that posted them
+#[system]
+pub fn sync_registry_from_offer(
+    world: &SubWorld,
+    query: &mut Query<(&Customer, Entity)>,
+    #[resource] offer_registry: &Arc<Registry<OfferId, Entity>>,
+) {
+    info!("Syncing registry from offer...");
+    let x = query.iter(world).flat_map(|(customer, entity)| {
+        customer.offers.iter().map(move |offer| (offer.id, *entity))
+    });
+    offer_registry.repopulate_from_entities(x);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn generated_offers_fit_the_customer() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let customer = generate_customer(&mut rng, CustomerId(1));
+        let (min_budget, max_budget) = customer.industry.budget_range();
+        assert!((min_budget..=max_budget).contains(&customer.budget));
+
+        let skills = vec![SkillId("debugging".to_string()), SkillId("testing".to_string())];
+        for id in 1..20 {
+            let offer = generate_offer(&mut rng, &customer, OfferId(id), &skills, SimDate::default());
+            assert!(offer.total_work() > 0.0);
+            assert!(offer.payout <= customer.budget);
+            assert!(offer.duration_days >= 2);
+            assert!(offer.expires_at > offer.posted_at);
+            assert!(offer.project.deadline.is_none());
+        }
+    }
+
+    #[test]
+    fn reputation_raises_payout_and_offer_chance() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut customer = generate_customer(&mut rng, CustomerId(1));
+        customer.budget = u32::MAX;
+        customer.reputation = 10;
+        let low = (offer_payout(100.0, &customer), daily_offer_chance(&customer));
+        customer.reputation = 90;
+        let high = (offer_payout(100.0, &customer), daily_offer_chance(&customer));
+        assert!(high.0 > low.0);
+        assert!(high.1 > low.1);
+    }
+
+    #[test]
+    fn bigger_projects_get_longer_deadlines() {
+        assert!(offer_duration_days(400.0, 2.0) > offer_duration_days(100.0, 2.0));
+        assert_eq!(offer_duration_days(1.0, 1.5), 2);
+    }
+}
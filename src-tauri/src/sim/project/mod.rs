@@ -3,4 +3,8 @@ pub mod requirement;
 pub mod functionality;
 pub mod task;
 pub mod feature_tag;
-pub mod customer;
\ No newline at end of file
+pub mod customer;
+pub mod market;
+pub mod work;
+pub mod systems;
+pub mod utils;
@@ -1,13 +1,155 @@
 use crate::sim::project::functionality::FunctionalityId;
 use crate::sim::project::requirement::RequirementId;
+use crate::sim::sim_date::sim_date::SimDate;
+use crate::utils::errors::ProjectError;
+use bincode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+use std::fmt;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode)]
 pub struct Project {
     pub id: ProjectId,
     pub name: String,
     pub requirements: Vec<RequirementId>,
     pub functionalities: Vec<FunctionalityId>,
+    pub progress: f32, // [0.0 - 1.0]
+    pub status: ProjectStatus,
+    pub deadline: Option<SimDate>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+impl Project {
+    /// Moves the project to `next`, rejecting transitions the status machine does not allow.
+    pub fn transition_to(&mut self, next: ProjectStatus) -> Result<(), ProjectError> {
+        if !self.status.can_transition_to(next) {
+            return Err(ProjectError::InvalidStatusTransition {
+                project_id: self.id,
+                from: self.status,
+                to: next,
+            });
+        }
+        self.status = next;
+        Ok(())
+    }
+
+    pub fn is_past_deadline(&self, now: &SimDate) -> bool {
+        self.deadline.is_some_and(|deadline| *now > deadline)
+    }
+}
+
+/// Lifecycle of a project.
+///
+/// `Proposed → Active → Delivered / Cancelled / Overdue`, an overdue project can still be
+/// delivered or cancelled. `Delivered` and `Cancelled` are final.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, Encode, Decode)]
+pub enum ProjectStatus {
+    #[default]
+    Proposed,
+    Active,
+    Delivered,
+    Cancelled,
+    Overdue,
+}
+
+impl ProjectStatus {
+    pub fn can_transition_to(self, next: ProjectStatus) -> bool {
+        use ProjectStatus::*;
+        matches!(
+            (self, next),
+            (Proposed, Active)
+                | (Proposed, Cancelled)
+                | (Active, Delivered)
+                | (Active, Cancelled)
+                | (Active, Overdue)
+                | (Overdue, Delivered)
+                | (Overdue, Cancelled)
+        )
+    }
+
+    pub fn is_final(self) -> bool {
+        matches!(self, ProjectStatus::Delivered | ProjectStatus::Cancelled)
+    }
+
+    /// Whether work on the project's tasks counts towards delivery.
+    pub fn is_in_progress(self) -> bool {
+        matches!(self, ProjectStatus::Active | ProjectStatus::Overdue)
+    }
+}
+
+impl fmt::Display for ProjectStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            ProjectStatus::Proposed => "Proposed",
+            ProjectStatus::Active => "Active",
+            ProjectStatus::Delivered => "Delivered",
+            ProjectStatus::Cancelled => "Cancelled",
+            ProjectStatus::Overdue => "Overdue",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Encode, Decode)]
 pub struct ProjectId(pub u32);
+impl From<ProjectId> for u32 {
+    fn from(value: ProjectId) -> Self {
+        value.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(status: ProjectStatus) -> Project {
+        Project {
+            id: ProjectId(1),
+            name: "Test".to_string(),
+            requirements: vec![],
+            functionalities: vec![],
+            progress: 0.0,
+            status,
+            deadline: None,
+        }
+    }
+
+    #[test]
+    fn proposed_project_can_be_started_and_delivered() {
+        let mut p = project(ProjectStatus::Proposed);
+        assert!(p.transition_to(ProjectStatus::Active).is_ok());
+        assert!(p.transition_to(ProjectStatus::Delivered).is_ok());
+        assert_eq!(p.status, ProjectStatus::Delivered);
+    }
+
+    #[test]
+    fn overdue_project_can_still_be_delivered() {
+        let mut p = project(ProjectStatus::Active);
+        assert!(p.transition_to(ProjectStatus::Overdue).is_ok());
+        assert!(p.transition_to(ProjectStatus::Delivered).is_ok());
+    }
+
+    #[test]
+    fn final_states_reject_transitions() {
+        for status in [ProjectStatus::Delivered, ProjectStatus::Cancelled] {
+            let mut p = project(status);
+            assert!(status.is_final());
+            assert!(p.transition_to(ProjectStatus::Active).is_err());
+            assert_eq!(p.status, status);
+        }
+    }
+
+    #[test]
+    fn proposed_project_cannot_skip_to_delivered() {
+        let mut p = project(ProjectStatus::Proposed);
+        assert!(p.transition_to(ProjectStatus::Delivered).is_err());
+        assert!(p.transition_to(ProjectStatus::Overdue).is_err());
+    }
+
+    #[test]
+    fn deadline_check() {
+        let mut p = project(ProjectStatus::Active);
+        assert!(!p.is_past_deadline(&SimDate::from(1_000)));
+        p.deadline = Some(SimDate::from(500));
+        assert!(p.is_past_deadline(&SimDate::from(501)));
+        assert!(!p.is_past_deadline(&SimDate::from(500)));
+    }
+}
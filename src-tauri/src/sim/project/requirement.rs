@@ -1,14 +1,21 @@
 use crate::sim::project::feature_tag::FeatureTag;
+use crate::sim::project::project::ProjectId;
 use bincode::{Decode, Encode};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Encode, Decode, Clone)]
 pub struct Requirement {
     pub id: RequirementId,
+    pub project_id: ProjectId,
     pub description: String,
     pub feature_tags: Vec<FeatureTag>,
     // Future: tags, hidden_expectations
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Encode,Decode , Serialize, Deserialize)]
-pub struct RequirementId(pub u32);
\ No newline at end of file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Encode,Decode , Serialize, Deserialize)]
+pub struct RequirementId(pub u32);
+impl From<RequirementId> for u32 {
+    fn from(value: RequirementId) -> Self {
+        value.0
+    }
+}
@@ -0,0 +1,86 @@
+use crate::sim::project::functionality::{Functionality, FunctionalityId};
+use crate::sim::project::project::{Project, ProjectId, ProjectStatus};
+use crate::sim::project::requirement::{Requirement, RequirementId};
+use crate::sim::project::task::{Task, TaskId};
+use crate::sim::registries::registry::Registry;
+use crate::sim::resources::global::TickCounter;
+use legion::world::SubWorld;
+use legion::{system, Entity, Query};
+use std::sync::Arc;
+use tracing::{error, info};
+
+// Moves projects along their status machine based on progress and deadline.
+#[system(for_each)]
+pub fn update_project_status(
+    #[resource] tick_counter: &Arc<TickCounter>,
+    project: &mut Project,
+) {
+    if !project.status.is_in_progress() {
+        return;
+    }
+
+    let next = if project.progress >= 1.0 {
+        ProjectStatus::Delivered
+    } else if project.status == ProjectStatus::Active
+        && project.is_past_deadline(&tick_counter.current_date())
+    {
+        ProjectStatus::Overdue
+    } else {
+        return;
+    };
+
+    match project.transition_to(next) {
+        Ok(()) => info!("Project '{}' is now {}", project.name, project.status),
+        Err(e) => error!("{}", e),
+    }
+}
+
+#[system]
+pub fn sync_registry_from_project(
+    world: &SubWorld,
+    query: &mut Query<(&Project, Entity)>,
+    #[resource] project_registry: &Arc<Registry<ProjectId, Entity>>,
+) {
+    info!("Syncing registry from project...");
+    let x = query
+        .iter(world)
+        .map(|(project, entity)| (project.id, *entity));
+    project_registry.repopulate_from_entities(x);
+}
+
+#[system]
+pub fn sync_registry_from_requirement(
+    world: &SubWorld,
+    query: &mut Query<(&Requirement, Entity)>,
+    #[resource] requirement_registry: &Arc<Registry<RequirementId, Entity>>,
+) {
+    info!("Syncing registry from requirement...");
+    let x = query
+        .iter(world)
+        .map(|(requirement, entity)| (requirement.id, *entity));
+    requirement_registry.repopulate_from_entities(x);
+}
+
+#[system]
+pub fn sync_registry_from_functionality(
+    world: &SubWorld,
+    query: &mut Query<(&Functionality, Entity)>,
+    #[resource] functionality_registry: &Arc<Registry<FunctionalityId, Entity>>,
+) {
+    info!("Syncing registry from functionality...");
+    let x = query
+        .iter(world)
+        .map(|(functionality, entity)| (functionality.id, *entity));
+    functionality_registry.repopulate_from_entities(x);
+}
+
+#[system]
+pub fn sync_registry_from_task(
+    world: &SubWorld,
+    query: &mut Query<(&Task, Entity)>,
+    #[resource] task_registry: &Arc<Registry<TaskId, Entity>>,
+) {
+    info!("Syncing registry from task...");
+    let x = query.iter(world).map(|(task, entity)| (task.id, *entity));
+    task_registry.repopulate_from_entities(x);
+}
@@ -1,13 +1,39 @@
 use crate::sim::person::components::PersonId;
+use crate::sim::person::skills::SkillId;
+use crate::sim::project::project::ProjectId;
+use bincode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode)]
 pub struct Task {
     pub id: TaskId,
+    pub project_id: ProjectId,
     pub description: String,
     pub assigned_to: Option<PersonId>,
+    pub total_work: f32,
     pub remaining_work: f32,
     pub contribution_weight: f32,
+    pub required_skills: Vec<SkillId>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+impl Task {
+    /// Completion of the task in the [0.0 - 1.0] range.
+    pub fn progress(&self) -> f32 {
+        if self.total_work <= 0.0 {
+            return 1.0;
+        }
+        (1.0 - self.remaining_work / self.total_work).clamp(0.0, 1.0)
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.remaining_work <= 0.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Encode, Decode)]
 pub struct TaskId(pub u32);
+impl From<TaskId> for u32 {
+    fn from(value: TaskId) -> Self {
+        value.0
+    }
+}
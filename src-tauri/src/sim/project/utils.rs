@@ -0,0 +1,131 @@
+use crate::sim::person::skills::SkillId;
+use crate::sim::project::functionality::{Functionality, FunctionalityId};
+use crate::sim::project::project::{Project, ProjectId, ProjectStatus};
+use crate::sim::project::requirement::{Requirement, RequirementId};
+use crate::sim::project::task::{Task, TaskId};
+use crate::sim::registries::registry::Registry;
+use crate::sim::sim_date::sim_date::SimDate;
+use bincode::{Decode, Encode};
+use legion::systems::CommandBuffer;
+use legion::Entity;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::info;
+
+/// Description of a project to be spawned, requirements are broken down into functionalities and tasks.
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode)]
+pub struct NewProject {
+    pub name: String,
+    pub deadline: Option<SimDate>,
+    pub requirements: Vec<NewRequirement>,
+}
+
+impl NewProject {
+    pub fn total_work(&self) -> f32 {
+        self.requirements
+            .iter()
+            .flat_map(|requirement| requirement.functionalities.iter())
+            .flat_map(|functionality| functionality.tasks.iter())
+            .map(|task| task.work)
+            .sum()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode)]
+pub struct NewRequirement {
+    pub description: String,
+    pub functionalities: Vec<NewFunctionality>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode)]
+pub struct NewFunctionality {
+    pub label: String,
+    pub tasks: Vec<NewTask>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode)]
+pub struct NewTask {
+    pub description: String,
+    pub work: f32,
+    pub contribution_weight: f32,
+    pub required_skills: Vec<SkillId>,
+}
+
+/// Registries used to look up project related entities by id.
+pub struct ProjectRegistries<'a> {
+    pub projects: &'a Arc<Registry<ProjectId, Entity>>,
+    pub requirements: &'a Arc<Registry<RequirementId, Entity>>,
+    pub functionalities: &'a Arc<Registry<FunctionalityId, Entity>>,
+    pub tasks: &'a Arc<Registry<TaskId, Entity>>,
+}
+
+/// Pushes the project and all of its requirements, functionalities and tasks as entities and registers them.
+/// The project starts out in `status`, usually [`ProjectStatus::Proposed`], accepted offers start out active.
+pub fn spawn_project(
+    cmd: &mut CommandBuffer,
+    registries: &ProjectRegistries,
+    new_project: NewProject,
+    status: ProjectStatus,
+) -> (ProjectId, Entity) {
+    let project_id = ProjectId(registries.projects.generate_id());
+    let mut project = Project {
+        id: project_id,
+        name: new_project.name,
+        requirements: vec![],
+        functionalities: vec![],
+        progress: 0.0,
+        status,
+        deadline: new_project.deadline,
+    };
+
+    for new_requirement in new_project.requirements {
+        let requirement_id = RequirementId(registries.requirements.generate_id());
+        project.requirements.push(requirement_id);
+        let requirement_entity = cmd.push((Requirement {
+            id: requirement_id,
+            project_id,
+            description: new_requirement.description,
+            feature_tags: vec![],
+        },));
+        registries.requirements.insert(requirement_id, requirement_entity);
+
+        for new_functionality in new_requirement.functionalities {
+            let functionality_id = FunctionalityId(registries.functionalities.generate_id());
+            project.functionalities.push(functionality_id);
+
+            let mut task_ids = vec![];
+            for new_task in new_functionality.tasks {
+                let task_id = TaskId(registries.tasks.generate_id());
+                task_ids.push(task_id);
+                let task_entity = cmd.push((Task {
+                    id: task_id,
+                    project_id,
+                    description: new_task.description,
+                    assigned_to: None,
+                    total_work: new_task.work,
+                    remaining_work: new_task.work,
+                    contribution_weight: new_task.contribution_weight,
+                    required_skills: new_task.required_skills,
+                },));
+                registries.tasks.insert(task_id, task_entity);
+            }
+
+            let functionality_entity = cmd.push((Functionality {
+                id: functionality_id,
+                project_id,
+                label: new_functionality.label,
+                related_requirements: vec![requirement_id],
+                task_ids,
+                progress: 0.0,
+            },));
+            registries
+                .functionalities
+                .insert(functionality_id, functionality_entity);
+        }
+    }
+
+    info!("Spawned project '{}' ({:?})", project.name, project_id);
+    let project_entity = cmd.push((project,));
+    registries.projects.insert(project_id, project_entity);
+    (project_id, project_entity)
+}
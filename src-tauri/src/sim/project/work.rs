@@ -0,0 +1,397 @@
+use crate::sim::action::action::{ActionIntent, ActionType};
+use crate::sim::ai::goap::EmployeeGoapFacts;
+use crate::sim::globals::BASE_WORK_PER_TICK;
+use crate::sim::person::components::{Person, PersonId};
+use crate::sim::person::morale::StressLevel;
+use crate::sim::person::needs::Energy;
+use crate::sim::person::skills::{SkillId, SkillSet};
+use crate::sim::person::stats::{StatType, Stats};
+use crate::sim::project::functionality::{Functionality, FunctionalityId};
+use crate::sim::project::project::{Project, ProjectId};
+use crate::sim::project::task::{Task, TaskId};
+use crate::sim::sim_date::sim_date::SimDate;
+use legion::world::SubWorld;
+use legion::{system, IntoQuery};
+use std::collections::{HashMap, HashSet};
+use tracing::{debug, info};
+
+// Stats that drive hands-on task work
+const WORK_STATS: [StatType; 3] = [StatType::Focus, StatType::Precision, StatType::Systems];
+
+/// Amount of work an employee puts into a task in one tick.
+///
+/// Scales [`BASE_WORK_PER_TICK`] by the work stats, the employee's level in the task's required skills,
+/// how rested they are and how far their stress is above their tolerance.
+pub fn work_output(
+    stats: &Stats,
+    skill_set: &SkillSet,
+    required_skills: &[SkillId],
+    energy: &Energy,
+    stress: &StressLevel,
+) -> f32 {
+    BASE_WORK_PER_TICK
+        * stat_factor(stats)
+        * skill_factor(skill_set, required_skills)
+        * energy_factor(energy)
+        * stress_factor(stress)
+}
+
+// 0.0 - 2.0, an average (50) employee works at 1.0
+fn stat_factor(stats: &Stats) -> f32 {
+    let total: u32 = WORK_STATS
+        .iter()
+        .map(|stat| stats.get_stat(*stat) as u32)
+        .sum();
+    (total as f32 / WORK_STATS.len() as f32) / 50.0
+}
+
+// 0.5 - 1.5 depending on the average level in the required skills, 1.0 when the task needs no skill
+fn skill_factor(skill_set: &SkillSet, required_skills: &[SkillId]) -> f32 {
+    if required_skills.is_empty() {
+        return 1.0;
+    }
+    let total: u32 = required_skills
+        .iter()
+        .map(|skill| skill_set.skills.get(skill).copied().unwrap_or(0).min(100))
+        .sum();
+    0.5 + (total as f32 / required_skills.len() as f32) / 100.0
+}
+
+// 0.25 - 1.0, exhausted employees still get a little done
+fn energy_factor(energy: &Energy) -> f32 {
+    0.25 + 0.75 * (energy.value() as f32 / 100.0)
+}
+
+// 1.0 while within baseline tolerance, dropping to 0.4 at the surge tolerance and beyond
+fn stress_factor(stress: &StressLevel) -> f32 {
+    if stress.current <= stress.baseline_tolerance {
+        return 1.0;
+    }
+    let range = (stress.surge_tolerance - stress.baseline_tolerance).max(1.0);
+    let over = ((stress.current - stress.baseline_tolerance) / range).clamp(0.0, 1.0);
+    1.0 - 0.6 * over
+}
+
+/// Progress of a functionality from its tasks, weighted by each task's contribution weight.
+pub fn functionality_progress(
+    task_ids: &[TaskId],
+    task_progress: &HashMap<TaskId, (f32, f32)>,
+) -> f32 {
+    let mut weighted_progress = 0.0;
+    let mut total_weight = 0.0;
+    for task_id in task_ids {
+        if let Some((progress, weight)) = task_progress.get(task_id) {
+            weighted_progress += progress * weight;
+            total_weight += weight;
+        }
+    }
+    if total_weight > 0.0 {
+        weighted_progress / total_weight
+    } else {
+        0.0
+    }
+}
+
+// Projects whose tasks can currently be worked on
+fn projects_in_progress(world: &SubWorld) -> HashSet<ProjectId> {
+    <&Project>::query()
+        .iter(world)
+        .filter(|project| project.status.is_in_progress())
+        .map(|project| project.id)
+        .collect()
+}
+
+// Hands the open tasks of projects in progress to employees without one, the task of the project
+// due first goes to the employee with the lowest id. Runs before the task facts are synced, so an
+// employee given a task can pick the Work goal in the same tick.
+#[system]
+#[read_component(Person)]
+#[read_component(Project)]
+#[write_component(Task)]
+pub fn assign_tasks(world: &mut SubWorld) {
+    let deadlines: HashMap<ProjectId, Option<SimDate>> = <&Project>::query()
+        .iter(world)
+        .filter(|project| project.status.is_in_progress())
+        .map(|project| (project.id, project.deadline))
+        .collect();
+    if deadlines.is_empty() {
+        return;
+    }
+
+    let busy: HashSet<PersonId> = <&Task>::query()
+        .iter(world)
+        .filter(|task| !task.is_done() && deadlines.contains_key(&task.project_id))
+        .filter_map(|task| task.assigned_to)
+        .collect();
+    let mut idle: Vec<PersonId> = <&Person>::query()
+        .iter(world)
+        .map(|person| person.person_id)
+        .filter(|person_id| !busy.contains(person_id))
+        .collect();
+    if idle.is_empty() {
+        return;
+    }
+    idle.sort();
+
+    let mut open: Vec<&mut Task> = <&mut Task>::query()
+        .iter_mut(world)
+        .filter(|task| {
+            !task.is_done() && task.assigned_to.is_none() && deadlines.contains_key(&task.project_id)
+        })
+        .collect();
+    // Projects without a deadline come last
+    open.sort_by_key(|task| {
+        let deadline = deadlines[&task.project_id];
+        (deadline.is_none(), deadline, task.project_id, task.id)
+    });
+
+    for (task, person_id) in open.into_iter().zip(idle) {
+        info!("Assigned task {:?} ({}) to person {:?}", task.id, task.description, person_id);
+        task.assigned_to = Some(person_id);
+    }
+}
+
+// Keeps the task related GOAP facts in line with the tasks assigned to each employee.
+#[system]
+#[read_component(Task)]
+#[read_component(Project)]
+#[read_component(Person)]
+#[write_component(EmployeeGoapFacts)]
+pub fn sync_task_facts(world: &mut SubWorld) {
+    let active_projects = projects_in_progress(world);
+    let mut assigned: HashMap<PersonId, f32> = HashMap::new();
+    for task in <&Task>::query().iter(world) {
+        if task.is_done() || !active_projects.contains(&task.project_id) {
+            continue;
+        }
+        if let Some(person_id) = task.assigned_to {
+            assigned.entry(person_id).or_insert(task.progress());
+        }
+    }
+
+    for (person, facts) in <(&Person, &mut EmployeeGoapFacts)>::query().iter_mut(world) {
+        let progress = assigned.get(&person.person_id);
+        facts.has_assigned_task = progress.is_some();
+        // Truncated, so a task short of done never reads as 100 and stops being worked on
+        facts.task_progress_u8 = progress.map_or(0, |p| (p * 100.0) as u8);
+    }
+}
+
+// Turns the work ticks of employees into progress on the task assigned to them.
+#[system]
+#[read_component(Person)]
+#[read_component(ActionIntent)]
+#[read_component(Stats)]
+#[read_component(SkillSet)]
+#[read_component(Energy)]
+#[read_component(StressLevel)]
+#[read_component(Project)]
+#[write_component(Task)]
+pub fn do_task_work(world: &mut SubWorld) {
+    let active_projects = projects_in_progress(world);
+    let mut workers: HashMap<PersonId, (&Stats, &SkillSet, &Energy, &StressLevel)> = HashMap::new();
+    let (mut task_world, person_world) = world.split::<&mut Task>();
+
+    let mut person_query =
+        <(&Person, &ActionIntent, &Stats, &SkillSet, &Energy, &StressLevel)>::query();
+    for (person, intent, stats, skill_set, energy, stress) in person_query.iter(&person_world) {
+        if matches!(intent.current, ActionType::GeneralWork | ActionType::Work(_)) {
+            workers.insert(person.person_id, (stats, skill_set, energy, stress));
+        }
+    }
+    if workers.is_empty() {
+        return;
+    }
+
+    for task in <&mut Task>::query().iter_mut(&mut task_world) {
+        if task.is_done() || !active_projects.contains(&task.project_id) {
+            continue;
+        }
+        let Some(person_id) = task.assigned_to else {
+            continue;
+        };
+        // Each employee works on one task per tick
+        let Some((stats, skill_set, energy, stress)) = workers.remove(&person_id) else {
+            continue;
+        };
+
+        let output = work_output(stats, skill_set, &task.required_skills, energy, stress);
+        task.remaining_work = (task.remaining_work - output).max(0.0);
+        debug!(
+            "Person {:?} worked {:.2} on task {:?}, {:.2} remaining",
+            person_id, output, task.id, task.remaining_work
+        );
+        if task.is_done() {
+            info!("Task {:?} ({}) completed", task.id, task.description);
+        }
+    }
+}
+
+// Rolls task progress up into functionalities and functionality progress up into projects.
+#[system]
+#[read_component(Task)]
+#[write_component(Functionality)]
+#[write_component(Project)]
+pub fn roll_up_project_progress(world: &mut SubWorld) {
+    let task_progress: HashMap<TaskId, (f32, f32)> = <&Task>::query()
+        .iter(world)
+        .map(|task| (task.id, (task.progress(), task.contribution_weight)))
+        .collect();
+
+    let mut functionality_progress_by_id: HashMap<FunctionalityId, f32> = HashMap::new();
+    for functionality in <&mut Functionality>::query().iter_mut(world) {
+        functionality.progress = functionality_progress(&functionality.task_ids, &task_progress);
+        functionality_progress_by_id.insert(functionality.id, functionality.progress);
+    }
+
+    for project in <&mut Project>::query().iter_mut(world) {
+        let progresses: Vec<f32> = project
+            .functionalities
+            .iter()
+            .filter_map(|id| functionality_progress_by_id.get(id).copied())
+            .collect();
+        project.progress = if progresses.is_empty() {
+            0.0
+        } else {
+            progresses.iter().sum::<f32>() / progresses.len() as f32
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action_queues::offer_manager::OfferCommand;
+    use crate::headless::{HeadlessOptions, HeadlessSim};
+    use crate::integrations::queues::{SimCommand, UICommandQueues};
+    use crate::sim::company::finance::{Finances, TransactionKind};
+    use crate::sim::person::stats::StatsConfig;
+    use crate::sim::project::customer::Customer;
+    use crate::sim::project::project::ProjectStatus;
+    use std::sync::Arc;
+
+    fn stats_with(value: u16) -> Stats {
+        StatsConfig {
+            judgement: value,
+            creativity: value,
+            systems: value,
+            precision: value,
+            focus: value,
+            discipline: value,
+            empathy: value,
+            communication: value,
+            resilience: value,
+            adaptability: value,
+        }
+        .into()
+    }
+
+    fn energy_at(value: u8) -> Energy {
+        let mut energy = Energy::default();
+        energy.level.set_value(value);
+        energy
+    }
+
+    #[test]
+    fn average_rested_employee_works_at_base_rate() {
+        let output = work_output(
+            &stats_with(50),
+            &SkillSet::default(),
+            &[],
+            &energy_at(100),
+            &StressLevel::default(),
+        );
+        assert!((output - BASE_WORK_PER_TICK).abs() < 0.01);
+    }
+
+    #[test]
+    fn better_stats_and_skills_work_faster() {
+        let skill = SkillId("debugging".to_string());
+        let mut skilled = SkillSet::default();
+        skilled.skills.insert(skill.clone(), 90);
+
+        let novice = work_output(
+            &stats_with(40),
+            &SkillSet::default(),
+            std::slice::from_ref(&skill),
+            &energy_at(100),
+            &StressLevel::default(),
+        );
+        let expert = work_output(
+            &stats_with(80),
+            &skilled,
+            &[skill],
+            &energy_at(100),
+            &StressLevel::default(),
+        );
+        assert!(expert > novice * 2.0);
+    }
+
+    #[test]
+    fn tired_and_stressed_employees_work_slower() {
+        let rested = work_output(
+            &stats_with(50),
+            &SkillSet::default(),
+            &[],
+            &energy_at(100),
+            &StressLevel::default(),
+        );
+        let mut stressed = StressLevel::default();
+        stressed.current = stressed.surge_tolerance + 10.0;
+        let worn_out = work_output(
+            &stats_with(50),
+            &SkillSet::default(),
+            &[],
+            &energy_at(10),
+            &stressed,
+        );
+        assert!(worn_out < rested * 0.5);
+    }
+
+    #[test]
+    fn functionality_progress_is_weighted_by_contribution() {
+        let mut progress = HashMap::new();
+        progress.insert(TaskId(1), (1.0, 3.0));
+        progress.insert(TaskId(2), (0.0, 1.0));
+        let value = functionality_progress(&[TaskId(1), TaskId(2), TaskId(3)], &progress);
+        assert!((value - 0.75).abs() < f32::EPSILON);
+        assert_eq!(functionality_progress(&[], &progress), 0.0);
+    }
+
+    #[test]
+    fn accepted_offer_is_worked_on_and_delivered() {
+        let args = ["--headless", "--ticks", "1", "--seed", "4"].map(str::to_string);
+        let mut sim = HeadlessSim::new(&HeadlessOptions::from_args(args).unwrap().unwrap()).unwrap();
+        let offer_of = |world: &legion::World| {
+            <&Customer>::query().iter(world).flat_map(|customer| customer.offers.iter()).map(|offer| (offer.id, offer.payout)).next()
+        };
+        let (offer_id, payout) = (0..96 * 14)
+            .find_map(|_| {
+                sim.tick();
+                offer_of(&sim.world)
+            })
+            .expect("no customer posted an offer in two weeks");
+
+        let queues = Arc::clone(&sim.resources.get::<Arc<UICommandQueues>>().unwrap());
+        queues.runtime.push(SimCommand::Offer(OfferCommand::AcceptOffer { offer_id: offer_id.0 }));
+        let project_status = |world: &legion::World| <&Project>::query().iter(world).next().map(|project| project.status);
+        let delivered = (0..96 * 60).any(|_| {
+            sim.tick();
+            matches!(project_status(&sim.world), Some(ProjectStatus::Delivered))
+        });
+
+        assert!(delivered, "project ended up {:?}", project_status(&sim.world));
+        assert!(<&Task>::query().iter(&sim.world).all(|task| task.is_done() && task.assigned_to.is_some()));
+        // The customer pays on the tick after delivery
+        sim.tick();
+        let finances = <&Finances>::query().iter(&sim.world).next().unwrap();
+        let now = sim.tick_counter.current_date();
+        let paid: i64 = finances
+            .ledger
+            .between(SimDate::default(), now)
+            .filter(|(_, transaction)| transaction.kind == TransactionKind::ProjectPayout)
+            .map(|(_, transaction)| transaction.amount)
+            .sum();
+        assert_eq!(paid, payout as i64);
+    }
+}
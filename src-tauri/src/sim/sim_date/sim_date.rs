@@ -51,7 +51,7 @@ impl Ord for SimDate {
 }
 impl From<u64> for SimDate {
     fn from(ticks: u64) -> Self {
-        const TICKS_PER_DAY: u64 = 96;
+        const TICKS_PER_DAY: u64 = SimDate::QUARTER_TICKS_PER_DAY;
         const DAYS_PER_WEEK: u64 = 7;
         const WEEKS_PER_YEAR: u64 = 52;
         const DAYS_PER_YEAR: u64 = DAYS_PER_WEEK * WEEKS_PER_YEAR;
@@ -76,8 +76,11 @@ impl From<u64> for SimDate {
 }
 
 impl SimDate {
+    /// Number of quarter ticks in a day, 24 hours × 4.
+    pub const QUARTER_TICKS_PER_DAY: u64 = 96;
+
     pub fn to_tick(&self) -> u64 {
-        const TICKS_PER_DAY: u64 = 96;
+        const TICKS_PER_DAY: u64 = SimDate::QUARTER_TICKS_PER_DAY;
         const DAYS_PER_WEEK: u64 = 7;
         const WEEKS_PER_YEAR: u64 = 52;
         const DAYS_PER_YEAR: u64 = WEEKS_PER_YEAR * DAYS_PER_WEEK;
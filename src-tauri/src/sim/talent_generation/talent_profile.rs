@@ -1,6 +1,8 @@
+use crate::master_data::data_pack::MASTER_DATA;
 use crate::sim::locations::locations::LocationId;
 use crate::sim::person::stats::StatType;
 use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::hash::Hash;
 // Assuming this macro is used for StatType
@@ -9,7 +11,8 @@ use std::hash::Hash;
 // --- NEW: StatSkewDetail Struct ---
 // Represents a specific stat and its relative weight in a skew.
 // Note: f32 does not implement Eq, so we can only derive PartialEq.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct StatSkewDetail {
     pub stat_type: StatType,
     pub weight: f32, // Relative weight for skewing this stat
@@ -17,7 +20,8 @@ pub struct StatSkewDetail {
 
 // --- UPDATED: TalentProfile Enum ---
 // Now uses `StatSkewDetail` for boosted and suppressed stats.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub enum TalentProfile {
     /// Represents a talent pool that skews certain stats by a given relative weight.
     /// The first Vec is for boosted stats, the second Vec is for suppressed stats.
@@ -28,6 +32,14 @@ pub enum TalentProfile {
     // Future variants can still be added here.
 }
 
+/// The talent profile of one location, as written in the data packs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LocationTalentEntry {
+    pub location: LocationId,
+    pub profile: TalentProfile,
+}
+
 // --- NEW: LocationTalentProfile Struct ---
 // This struct maps Location IDs to their corresponding Talent Profiles.
 #[derive(Debug, Clone)]
@@ -53,6 +65,17 @@ impl LocationTalentProfile {
         self.profiles.get(location_id)
     }
 
+    /// The profiles as data pack entries, sorted by location.
+    pub fn entries(&self) -> Vec<LocationTalentEntry> {
+        let mut entries: Vec<LocationTalentEntry> = self
+            .profiles
+            .iter()
+            .map(|(location, profile)| LocationTalentEntry { location: location.clone(), profile: profile.clone() })
+            .collect();
+        entries.sort_by(|a, b| a.location.as_str().cmp(b.location.as_str()));
+        entries
+    }
+
     /// Initializes the `LocationTalentProfile` with default data for your locations.
     /// All weights are set to 1.0 initially, allowing for easy relative tweaking later.
     pub fn initialize_default_profiles(&mut self) {
@@ -164,10 +187,24 @@ impl LocationTalentProfile {
 
 // --- Global Static Declaration ---
 
+/// The built-in talent profiles, used when no data pack provides them.
+pub fn default_location_talent_entries() -> Vec<LocationTalentEntry> {
+    let mut profiles = LocationTalentProfile::new();
+    profiles.initialize_default_profiles();
+    profiles.entries()
+}
+
 /// Globally available LocationTalentProfile, containing the talent skew for each location.
-/// Initialized once when first accessed.
+/// Initialized once when first accessed, from the data packs if they are loaded by then.
 pub static LOCATION_TALENT_PROFILES: Lazy<LocationTalentProfile> = Lazy::new(|| {
     let mut profiles = LocationTalentProfile::new();
-    profiles.initialize_default_profiles();
+    match MASTER_DATA.get() {
+        Some(data) => {
+            for entry in &data.location_talent_profiles {
+                profiles.add_profile(entry.location.clone(), entry.profile.clone());
+            }
+        }
+        None => profiles.initialize_default_profiles(),
+    }
     profiles
 });
\ No newline at end of file
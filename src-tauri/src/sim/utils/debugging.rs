@@ -1,7 +1,9 @@
+use bincode::{Decode, Encode};
 use legion::system;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, Encode, Decode)]
 pub struct DebugDisplayComponent {
     // Each entry is a (label, value) pair for display
     pub entries: Vec<(String, String)>,
@@ -7,9 +7,14 @@ static INIT: Once = Once::new();
 
 /// Initialize simple stdout logger with filtering and context info
 pub fn init_logging() {
+    init_logging_with_default("info");
+}
+
+/// Same as [`init_logging`], with the filter used when `RUST_LOG` is not set
+pub fn init_logging_with_default(default_filter: &str) {
     INIT.call_once(|| {
         let env_filter =
-            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default_filter));
 
         tracing_subscriber::fmt()
             .with_timer(ChronoLocal::new("%Y-%m-%dT%H:%M:%S%:z".to_string()))
@@ -1,5 +1,135 @@
-use rand::{rng, Rng};
+use bincode::{Decode, Encode};
+use serde::Serialize;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
 
-pub fn random_variation(base: i32, range: i32) -> i32 {
-    rng().random_range((base - range)..=(base + range))
+pub fn random_variation(rng: &mut impl Rng, base: i32, range: i32) -> i32 {
+    rng.random_range((base - range)..=(base + range))
+}
+
+/// Subsystems drawing from their own stream, so a change in how one of them uses randomness
+/// does not shift the numbers every other one sees.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)]
+pub enum RngStream {
+    Spawning,
+    Market,
+    Morale,
+    Calendar,
+}
+
+/// SplitMix64 finalizer, spreads related seeds far apart.
+fn mix(mut value: u64) -> u64 {
+    value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    value ^ (value >> 31)
+}
+
+fn stream_seed(seed: u64, stream: RngStream) -> u64 {
+    mix(seed.wrapping_add((stream as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15)))
+}
+
+/// What it takes to restore a [`SimRng`], saved with the game.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Encode, Decode)]
+pub struct SimRngState {
+    pub seed: u64,
+    /// Current seed of each stream, in [`RngStream`] order
+    pub stream_seeds: Vec<u64>,
+}
+
+/// The one source of randomness of the simulation. Every draw goes through a stream split off the game seed,
+/// so the same seed and the same commands play out the same way.
+#[derive(Debug, Clone)]
+pub struct SimRng {
+    seed: u64,
+    streams: Vec<StdRng>,
+}
+
+impl Default for SimRng {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl SimRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            streams: RngStream::iter().map(|stream| StdRng::seed_from_u64(stream_seed(seed, stream))).collect(),
+        }
+    }
+
+    /// Restores the streams of a saved game. Streams added since the save start from the game seed.
+    pub fn from_state(state: &SimRngState) -> Self {
+        let streams = RngStream::iter()
+            .map(|stream| {
+                let seed = state.stream_seeds.get(stream as usize).copied().unwrap_or_else(|| stream_seed(state.seed, stream));
+                StdRng::seed_from_u64(seed)
+            })
+            .collect();
+        Self { seed: state.seed, streams }
+    }
+
+    /// Seed of the game, as shown in the save metadata.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn stream(&mut self, stream: RngStream) -> &mut StdRng {
+        &mut self.streams[stream as usize]
+    }
+
+    /// Reseeds every stream from itself and returns the new seeds. The game keeps going the same way
+    /// whether it carries on from here or gets loaded from the returned state.
+    pub fn checkpoint(&mut self) -> SimRngState {
+        let stream_seeds = self
+            .streams
+            .iter_mut()
+            .map(|stream| {
+                let seed = stream.random();
+                *stream = StdRng::seed_from_u64(seed);
+                seed
+            })
+            .collect();
+        SimRngState { seed: self.seed, stream_seeds }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn draws(rng: &mut SimRng, stream: RngStream) -> Vec<u32> {
+        (0..8).map(|_| rng.stream(stream).random()).collect()
+    }
+
+    #[test]
+    fn same_seed_same_numbers() {
+        let mut a = SimRng::new(42);
+        let mut b = SimRng::new(42);
+        assert_eq!(draws(&mut a, RngStream::Market), draws(&mut b, RngStream::Market));
+        assert_ne!(draws(&mut a, RngStream::Market), draws(&mut SimRng::new(43), RngStream::Market));
+    }
+
+    #[test]
+    fn streams_do_not_affect_each_other() {
+        let mut a = SimRng::new(7);
+        let mut b = SimRng::new(7);
+        draws(&mut a, RngStream::Spawning);
+        assert_eq!(draws(&mut a, RngStream::Morale), draws(&mut b, RngStream::Morale));
+        assert_ne!(draws(&mut a, RngStream::Spawning), draws(&mut b, RngStream::Calendar));
+    }
+
+    #[test]
+    fn restored_state_continues_like_the_original() {
+        let mut original = SimRng::new(3);
+        draws(&mut original, RngStream::Calendar);
+        let state = original.checkpoint();
+        let mut restored = SimRng::from_state(&state);
+        assert_eq!(restored.seed(), 3);
+        for stream in RngStream::iter() {
+            assert_eq!(draws(&mut original, stream), draws(&mut restored, stream));
+        }
+    }
 }
@@ -76,7 +76,7 @@ pub fn run_simulation_thread(config: SimThreadConfig) {
         saves_directory,
     } = config;
 
-    resources.insert(Arc::new(AppContext { app_handle }));
+    resources.insert(Arc::new(AppContext::new(app_handle)));
     resources.insert(Arc::clone(&reset_request));
     resources.insert(Arc::clone(&first_run));
     resources.insert(Arc::clone(&sim_manager));
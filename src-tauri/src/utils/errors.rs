@@ -1,12 +1,14 @@
 use bincode::error::{DecodeError, EncodeError};
 use std::fmt;
+use std::path::PathBuf;
 use std::time::SystemTimeError;
+use crate::sim::project::project::{ProjectId, ProjectStatus};
 
 #[derive(Debug)]
 pub enum SaveDataToDBError {
     Encoding(bincode::error::EncodeError),
     Db(sled::Error),
-    
+    MissingHandle,
 }
 
 #[derive(Debug)]
@@ -44,6 +46,7 @@ impl fmt::Display for SaveDataToDBError {
         match self {
             SaveDataToDBError::Encoding(e) => write!(f, "Encoding failed: {}", e),
             SaveDataToDBError::Db(e) => write!(f, "Database insert failed: {}", e),
+            SaveDataToDBError::MissingHandle => write!(f, "Database handle is missing"),
         }
     }
 }
@@ -103,6 +106,22 @@ pub enum SavesManagementError {
     TimeError(SystemTimeError),
     EmptySaveSlotError,
     LoadError(LoadDataFromDBError),
+    /// Data packs the save was made with that are not active, or changed since
+    MissingDataPacks(Vec<String>),
+    /// Save written by a newer version of the game
+    SaveTooNew { version: String, supported: String },
+    /// A migration step could not bring the save to the next version
+    UnmigratableSave { version: String, reason: String },
+    /// No checkpoint with this id in the save slot
+    CheckpointNotFound(String),
+    /// Slot id that is not a plain folder name in the saves directory
+    InvalidSlotId(String),
+    /// The entries of a save do not match the checksum it recorded
+    Corrupt { slot_id: String, details: String },
+    /// The file is not a save archive, or it was damaged
+    InvalidArchive(String),
+    /// Save archive exported by another version of the game
+    ArchiveVersionMismatch { version: String, supported: String },
 }
 
 // Implement Display for manual error message formatting if needed
@@ -117,6 +136,22 @@ impl std::fmt::Display for SavesManagementError {
             SavesManagementError::TimeError(e) => write!(f, "Time error: {}", e),
             SavesManagementError::EmptySaveSlotError => write!(f, "Slot is empty"),
             SavesManagementError::LoadError(e) => write!(f, "Load data error: {}", e),
+            SavesManagementError::MissingDataPacks(packs) => {
+                write!(f, "Save needs data packs that are not active: {}", packs.join(", "))
+            }
+            SavesManagementError::SaveTooNew { version, supported } => {
+                write!(f, "Save version {} is newer than the supported {}", version, supported)
+            }
+            SavesManagementError::UnmigratableSave { version, reason } => {
+                write!(f, "Save version {} cannot be migrated: {}", version, reason)
+            }
+            SavesManagementError::CheckpointNotFound(id) => write!(f, "Checkpoint not found: {}", id),
+            SavesManagementError::InvalidSlotId(id) => write!(f, "Invalid save slot id: {}", id),
+            SavesManagementError::Corrupt { slot_id, details } => write!(f, "Save slot {} is corrupt: {}", slot_id, details),
+            SavesManagementError::InvalidArchive(reason) => write!(f, "Invalid save archive: {}", reason),
+            SavesManagementError::ArchiveVersionMismatch { version, supported } => {
+                write!(f, "Save archive version {} does not match the supported {}", version, supported)
+            }
         }
     }
 }
@@ -133,6 +168,14 @@ impl std::error::Error for SavesManagementError {
             SavesManagementError::TimeError(e) => Some(e),
             SavesManagementError::EmptySaveSlotError => Some(self),
             SavesManagementError::LoadError(e) => Some(e),
+            SavesManagementError::MissingDataPacks(_) => None,
+            SavesManagementError::SaveTooNew { .. } => None,
+            SavesManagementError::UnmigratableSave { .. } => None,
+            SavesManagementError::CheckpointNotFound(_) => None,
+            SavesManagementError::InvalidSlotId(_) => None,
+            SavesManagementError::Corrupt { .. } => None,
+            SavesManagementError::InvalidArchive(_) => None,
+            SavesManagementError::ArchiveVersionMismatch { .. } => None,
         }
     }
 }
@@ -178,4 +221,205 @@ impl From<LoadDataFromDBError> for SavesManagementError {
     fn from(err: LoadDataFromDBError) -> SavesManagementError {
         SavesManagementError::LoadError(err)
     }
-}
\ No newline at end of file
+}
+#[derive(Debug)]
+pub enum ProjectError {
+    InvalidStatusTransition {
+        project_id: ProjectId,
+        from: ProjectStatus,
+        to: ProjectStatus,
+    },
+    NotFound(ProjectId),
+}
+
+impl fmt::Display for ProjectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProjectError::InvalidStatusTransition { project_id, from, to } => write!(
+                f,
+                "Project {} cannot move from {} to {}",
+                project_id.0, from, to
+            ),
+            ProjectError::NotFound(project_id) => write!(f, "Project {} not found", project_id.0),
+        }
+    }
+}
+
+impl std::error::Error for ProjectError {}
+
+/// Problems in the skill master data, found while building the skill graph at startup.
+#[derive(Debug, PartialEq)]
+pub enum SkillGraphError {
+    DuplicateSkill(String),
+    InvalidField { skill: String, field: &'static str, value: String },
+    /// A feedforward or feedback link to a skill that does not exist
+    UnknownLink { skill: String, field: &'static str, target: String },
+    /// A feedforward link to a lower tier, or a feedback link to a higher one
+    TierOrder { skill: String, field: &'static str, target: String },
+    /// Skills that feed forward into each other, in order
+    Cycle(Vec<String>),
+}
+
+impl fmt::Display for SkillGraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkillGraphError::DuplicateSkill(skill) => write!(f, "Skill '{}' is defined more than once", skill),
+            SkillGraphError::InvalidField { skill, field, value } => {
+                write!(f, "Skill '{}' has an invalid {}: '{}'", skill, field, value)
+            }
+            SkillGraphError::UnknownLink { skill, field, target } => {
+                write!(f, "Skill '{}' lists unknown skill '{}' in {}", skill, target, field)
+            }
+            SkillGraphError::TierOrder { skill, field, target } => write!(
+                f,
+                "Skill '{}' lists '{}' in {}, which goes against the Foundational -> Conceptual -> Applied tier order",
+                skill, target, field
+            ),
+            SkillGraphError::Cycle(skills) => write!(f, "Skills feed forward in a cycle: {}", skills.join(" -> ")),
+        }
+    }
+}
+
+impl std::error::Error for SkillGraphError {}
+
+/// Problems reading a data pack file. Each points at the file, and the line or field where possible.
+#[derive(Debug)]
+pub enum DataPackError {
+    Io { file: PathBuf, error: std::io::Error },
+    /// The file is not valid JSON, or does not match the schema of its content
+    Parse { file: PathBuf, line: usize, column: usize, message: String },
+    /// The file parsed, but a value in it is not usable
+    Invalid { file: PathBuf, line: Option<usize>, field: String, message: String },
+    UnsupportedFormat(PathBuf),
+}
+
+impl fmt::Display for DataPackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataPackError::Io { file, error } => write!(f, "{}: {}", file.display(), error),
+            DataPackError::Parse { file, line, column, message } => {
+                write!(f, "{}:{}:{}: {}", file.display(), line, column, message)
+            }
+            DataPackError::Invalid { file, line: Some(line), field, message } => {
+                write!(f, "{}:{}: {}: {}", file.display(), line, field, message)
+            }
+            DataPackError::Invalid { file, line: None, field, message } => {
+                write!(f, "{}: {}: {}", file.display(), field, message)
+            }
+            DataPackError::UnsupportedFormat(file) => {
+                write!(f, "{}: unsupported data pack format, data packs are read from .json files", file.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for DataPackError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DataPackError::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Failures of a headless simulation run.
+#[derive(Debug)]
+pub enum HeadlessError {
+    /// Bad command line, with the usage to show
+    Args(String),
+    SkillGraph(SkillGraphError),
+    UnknownPreset { kind: &'static str, name: String },
+    Io(std::io::Error),
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for HeadlessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HeadlessError::Args(message) => write!(f, "{}", message),
+            HeadlessError::SkillGraph(e) => write!(f, "Invalid skill master data: {}", e),
+            HeadlessError::UnknownPreset { kind, name } => write!(f, "No {} named '{}'", kind, name),
+            HeadlessError::Io(e) => write!(f, "IO error: {}", e),
+            HeadlessError::Json(e) => write!(f, "JSON error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for HeadlessError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            HeadlessError::SkillGraph(e) => Some(e),
+            HeadlessError::Io(e) => Some(e),
+            HeadlessError::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<SkillGraphError> for HeadlessError {
+    fn from(value: SkillGraphError) -> Self {
+        Self::SkillGraph(value)
+    }
+}
+
+impl From<std::io::Error> for HeadlessError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<serde_json::Error> for HeadlessError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Json(value)
+    }
+}
+
+/// Failures of the save dump and diff tool.
+#[derive(Debug)]
+pub enum SaveToolError {
+    /// Bad command line, with the usage to show
+    Args(String),
+    Save(SavesManagementError),
+    Io(std::io::Error),
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for SaveToolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveToolError::Args(message) => write!(f, "{}", message),
+            SaveToolError::Save(e) => write!(f, "{}", e),
+            SaveToolError::Io(e) => write!(f, "IO error: {}", e),
+            SaveToolError::Json(e) => write!(f, "JSON error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SaveToolError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SaveToolError::Save(e) => Some(e),
+            SaveToolError::Io(e) => Some(e),
+            SaveToolError::Json(e) => Some(e),
+            SaveToolError::Args(_) => None,
+        }
+    }
+}
+
+impl From<SavesManagementError> for SaveToolError {
+    fn from(value: SavesManagementError) -> Self {
+        Self::Save(value)
+    }
+}
+
+impl From<std::io::Error> for SaveToolError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<serde_json::Error> for SaveToolError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Json(value)
+    }
+}
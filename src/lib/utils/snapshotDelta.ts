@@ -0,0 +1,47 @@
+import {listen, type UnlistenFn} from '@tauri-apps/api/event';
+import {invoke} from "@tauri-apps/api/core";
+import type {Writable} from "svelte/store";
+
+// Mirrors SnapshotDelta in src-tauri/src/integrations/snapshots_emitter/delta.rs
+export type SnapshotDelta<T> = {
+    seq: number;
+    // When true, `added` is the whole collection
+    full: boolean;
+    added: [unknown, T][];
+    updated: [unknown, Partial<T>][];
+    removed: unknown[];
+};
+
+// Keeps `store` in sync with a collection the backend sends as deltas.
+// A missed message shows up as a gap in `seq`, the backend is then asked for everything again.
+export function listenDelta<T>(eventName: string, store: Writable<T[]>): Promise<UnlistenFn> {
+    const entries = new Map<string, T>();
+    let lastSeq: number | null = null;
+
+    return listen<SnapshotDelta<T>>(eventName, (event) => {
+        const delta = event.payload;
+        if (delta.full) {
+            entries.clear();
+        } else if (lastSeq === null || delta.seq !== lastSeq + 1) {
+            lastSeq = null;
+            invoke('refresh_data');
+            return;
+        }
+        lastSeq = delta.seq;
+
+        for (const [key, value] of delta.added) {
+            entries.set(JSON.stringify(key), value);
+        }
+        for (const [key, fields] of delta.updated) {
+            const id = JSON.stringify(key);
+            const existing = entries.get(id);
+            if (existing !== undefined) {
+                entries.set(id, {...existing, ...fields});
+            }
+        }
+        for (const key of delta.removed) {
+            entries.delete(JSON.stringify(key));
+        }
+        store.set([...entries.values()]);
+    });
+}